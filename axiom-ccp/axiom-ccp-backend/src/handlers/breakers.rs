@@ -0,0 +1,89 @@
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use tracing::{info, instrument};
+use crate::handlers::bindings::push_reload_to_shell;
+use crate::handlers::registry::{AppState, BreakerPolicy};
+
+const SHELL_BASE_URL: &str = "http://localhost:9000";
+
+/// GET /api/v1/tomains/{id}/breakers
+/// Proxies the Shell's live circuit breakers for this tomain (state, window counts, policy).
+#[instrument]
+pub async fn list_breakers(Path(id): Path<String>) -> impl IntoResponse {
+    match reqwest::get(format!("{}/admin/breakers/{}", SHELL_BASE_URL, id)).await {
+        Ok(res) => {
+            let status = StatusCode::from_u16(res.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+            let body = res.text().await.unwrap_or_default();
+            Response::builder()
+                .status(status)
+                .header("Content-Type", "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        }
+        Err(e) => (StatusCode::BAD_GATEWAY, format!("Shell not reachable: {}", e)).into_response(),
+    }
+}
+
+/// POST /api/v1/tomains/{id}/breakers/{env}/{alias}/{action}
+/// Forces a breaker open or resets it (`action` is `open` or `reset`).
+#[instrument]
+pub async fn control_breaker(Path((id, env, alias, action)): Path<(String, String, String, String)>) -> impl IntoResponse {
+    if action != "open" && action != "reset" {
+        return (StatusCode::BAD_REQUEST, format!("Unknown breaker action '{}'", action)).into_response();
+    }
+
+    info!("🧯 {} breaker {}/{}/{}", action, id, env, alias);
+    let url = format!("{}/admin/breakers/{}/{}/{}/{}", SHELL_BASE_URL, id, env, alias, action);
+    match reqwest::Client::new().post(url).send().await {
+        Ok(res) => {
+            let status = StatusCode::from_u16(res.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+            (status, res.text().await.unwrap_or_default()).into_response()
+        }
+        Err(e) => (StatusCode::BAD_GATEWAY, format!("Shell not reachable: {}", e)).into_response(),
+    }
+}
+
+/// GET /api/v1/tomains/{id}/breaker-policy
+/// Circuit breaker policies per environment and alias.
+#[instrument(skip(state))]
+pub async fn get_policies(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let reg = state.registry.read().await;
+    if !reg.tomains.contains_key(&id) {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Tomain not found"}))).into_response();
+    }
+    (StatusCode::OK, Json(reg.breaker_policies.get(&id).cloned().unwrap_or_default())).into_response()
+}
+
+/// PUT /api/v1/tomains/{id}/breaker-policy/{env}/{alias}
+/// Replaces the breaker policy for one alias in one environment (or GLOBAL) and hot-reloads the Shell.
+#[instrument(skip(state))]
+pub async fn set_policy(
+    State(state): State<AppState>,
+    Path((id, env, alias)): Path<(String, String, String)>,
+    Json(policy): Json<BreakerPolicy>,
+) -> impl IntoResponse {
+    let env = env.to_uppercase();
+    if policy.failure_rate.is_some_and(|r| !(0.0..=1.0).contains(&r)) {
+        return (StatusCode::BAD_REQUEST, "failure_rate must be between 0.0 and 1.0").into_response();
+    }
+    {
+        let mut reg = state.registry.write().await;
+        if !reg.tomains.contains_key(&id) {
+            return (StatusCode::NOT_FOUND, "Tomain not found").into_response();
+        }
+        info!("🧯 Breaker policy for {} ({}/{}): {:?}", id, env, alias, policy);
+        reg.breaker_policies.entry(id.clone()).or_default().entry(env).or_default().insert(alias, policy);
+        reg.flush();
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, "Breaker policy updated").into_response()
+}
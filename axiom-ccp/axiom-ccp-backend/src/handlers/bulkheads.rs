@@ -0,0 +1,76 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use tracing::{info, instrument};
+use crate::handlers::bindings::push_reload_to_shell;
+use crate::handlers::registry::{AppState, BulkheadPolicy};
+
+fn invalid(policy: &BulkheadPolicy) -> bool {
+    policy.max_concurrency == Some(0)
+        || matches!((policy.min_concurrency, policy.max_concurrency), (Some(min), Some(max)) if min > max)
+}
+
+/// GET /api/v1/tomains/{id}/bulkhead
+/// Ingress concurrency limits per environment.
+#[instrument(skip(state))]
+pub async fn get_bulkheads(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let reg = state.registry.read().await;
+    if !reg.tomains.contains_key(&id) {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Tomain not found"}))).into_response();
+    }
+    (StatusCode::OK, Json(reg.bulkheads.get(&id).cloned().unwrap_or_default())).into_response()
+}
+
+/// PUT /api/v1/tomains/{id}/bulkhead/{env}
+/// Replaces the ingress limit for one environment (or GLOBAL) and hot-reloads the Shell.
+#[instrument(skip(state))]
+pub async fn set_bulkhead(
+    State(state): State<AppState>,
+    Path((id, env)): Path<(String, String)>,
+    Json(policy): Json<BulkheadPolicy>,
+) -> impl IntoResponse {
+    if invalid(&policy) {
+        return (StatusCode::BAD_REQUEST, "max_concurrency must be at least 1 and not below min_concurrency").into_response();
+    }
+    let env = env.to_uppercase();
+    {
+        let mut reg = state.registry.write().await;
+        if !reg.tomains.contains_key(&id) {
+            return (StatusCode::NOT_FOUND, "Tomain not found").into_response();
+        }
+        info!("🚧 Bulkhead for {} ({}): {:?}", id, env, policy);
+        reg.bulkheads.entry(id.clone()).or_default().insert(env, policy);
+        reg.flush();
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, "Bulkhead updated").into_response()
+}
+
+/// PUT /api/v1/bulkheads/egress/{alias}
+/// Sets how many calls to one alias may be in flight across all tomains.
+#[instrument(skip(state))]
+pub async fn set_egress_bulkhead(
+    State(state): State<AppState>,
+    Path(alias): Path<String>,
+    Json(policy): Json<BulkheadPolicy>,
+) -> impl IntoResponse {
+    if invalid(&policy) {
+        return (StatusCode::BAD_REQUEST, "max_concurrency must be at least 1 and not below min_concurrency").into_response();
+    }
+    {
+        let mut reg = state.registry.write().await;
+        info!("🚧 Egress bulkhead for {}: {:?}", alias, policy);
+        reg.egress_bulkheads.insert(alias, policy);
+        reg.flush();
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, "Egress bulkhead updated").into_response()
+}
@@ -0,0 +1,99 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use tracing::{info, instrument, warn};
+use crate::handlers::bindings::push_reload_to_shell;
+use crate::handlers::registry::{AppState, FaultRule};
+
+fn validate(env: &str, rule: &FaultRule) -> Result<(), &'static str> {
+    let rates = [rule.error_rate, rule.reset_rate, rule.truncate_rate];
+    if rates.iter().flatten().any(|r| !(0.0..=1.0).contains(r)) {
+        return Err("error_rate, reset_rate and truncate_rate must be between 0.0 and 1.0");
+    }
+    if rule.error_rate.unwrap_or(0.0) + rule.reset_rate.unwrap_or(0.0) > 1.0 {
+        return Err("error_rate and reset_rate together cannot exceed 1.0");
+    }
+    if rule.error_statuses.iter().flatten().any(|s| !(100..=599).contains(s)) {
+        return Err("error_statuses must be HTTP status codes");
+    }
+    // GREEN and RED are the live perspectives; the Shell treats rules under them as PROD
+    if matches!(env, "PROD" | "GREEN" | "RED") && rule.force != Some(true) {
+        return Err("Fault injection in PROD (GREEN/RED) requires \"force\": true");
+    }
+    Ok(())
+}
+
+/// GET /api/v1/tomains/{id}/chaos
+/// Fault injection rules per environment and alias.
+#[instrument(skip(state))]
+pub async fn get_rules(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let reg = state.registry.read().await;
+    if !reg.tomains.contains_key(&id) {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Tomain not found"}))).into_response();
+    }
+    (StatusCode::OK, Json(reg.fault_injection.get(&id).cloned().unwrap_or_default())).into_response()
+}
+
+/// PUT /api/v1/tomains/{id}/chaos/{env}/{alias}
+/// Starts injecting faults into one alias in one environment (or GLOBAL) and hot-reloads the Shell.
+#[instrument(skip(state))]
+pub async fn set_rule(
+    State(state): State<AppState>,
+    Path((id, env, alias)): Path<(String, String, String)>,
+    Json(rule): Json<FaultRule>,
+) -> impl IntoResponse {
+    let env = env.to_uppercase();
+    if let Err(msg) = validate(&env, &rule) {
+        return (StatusCode::BAD_REQUEST, msg).into_response();
+    }
+    {
+        let mut reg = state.registry.write().await;
+        if !reg.tomains.contains_key(&id) {
+            return (StatusCode::NOT_FOUND, "Tomain not found").into_response();
+        }
+        if rule.force == Some(true) {
+            warn!("🐒 Forced fault injection for {} ({}/{}): {:?}", id, env, alias, rule);
+        } else {
+            info!("🐒 Fault injection for {} ({}/{}): {:?}", id, env, alias, rule);
+        }
+        reg.fault_injection.entry(id).or_default().entry(env).or_default().insert(alias, rule);
+        reg.flush();
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, "Fault injection rule updated").into_response()
+}
+
+/// DELETE /api/v1/tomains/{id}/chaos/{env}/{alias}
+/// Stops injecting faults into the alias.
+#[instrument(skip(state))]
+pub async fn delete_rule(
+    State(state): State<AppState>,
+    Path((id, env, alias)): Path<(String, String, String)>,
+) -> impl IntoResponse {
+    let env = env.to_uppercase();
+    {
+        let mut reg = state.registry.write().await;
+        let removed = reg.fault_injection.get_mut(&id)
+            .and_then(|envs| envs.get_mut(&env))
+            .and_then(|aliases| aliases.remove(&alias))
+            .is_some();
+        if !removed {
+            return (StatusCode::NOT_FOUND, "Fault injection rule not found").into_response();
+        }
+        if let Some(envs) = reg.fault_injection.get_mut(&id) {
+            envs.retain(|_, aliases| !aliases.is_empty());
+        }
+        reg.flush();
+        info!("🐒 Fault injection for {} ({}/{}) cleared", id, env, alias);
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, "Fault injection rule cleared").into_response()
+}
@@ -0,0 +1,59 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::{info, instrument};
+use crate::handlers::bindings::push_reload_to_shell;
+use crate::handlers::registry::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct SetConfigRequest {
+    pub values: HashMap<String, serde_json::Value>,
+}
+
+/// GET /api/v1/tomains/{id}/config
+/// Config sets for every environment, with their versions.
+#[instrument(skip(state))]
+pub async fn get_config(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let reg = state.registry.read().await;
+    if !reg.tomains.contains_key(&id) {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Tomain not found"}))).into_response();
+    }
+    let config = reg.config.get(&id).cloned().unwrap_or_default();
+    (StatusCode::OK, Json(config)).into_response()
+}
+
+/// PUT /api/v1/tomains/{id}/config/{env}
+/// Replaces one environment's config values, bumps its version and hot-reloads the Shell.
+#[instrument(skip(state))]
+pub async fn set_config(
+    State(state): State<AppState>,
+    Path((id, env)): Path<(String, String)>,
+    Json(payload): Json<SetConfigRequest>,
+) -> impl IntoResponse {
+    let env = env.to_uppercase();
+    let version = {
+        let mut reg = state.registry.write().await;
+        if !reg.tomains.contains_key(&id) {
+            return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Tomain not found"}))).into_response();
+        }
+        let set = reg.config.entry(id.clone()).or_default().entry(env.clone()).or_default();
+        set.version += 1;
+        set.values = payload.values;
+        set.updated_at = Some(chrono::Utc::now().to_rfc3339());
+        let version = set.version;
+        reg.flush();
+        info!("🎛️ Config for {} ({}) updated to version {}", id, env, version);
+        version
+    };
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, Json(serde_json::json!({ "environment": env, "version": version }))).into_response()
+}
@@ -0,0 +1,100 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use tracing::{info, instrument};
+use crate::handlers::bindings::push_reload_to_shell;
+use crate::handlers::registry::{AppState, CachePolicy};
+
+const SHELL_BASE_URL: &str = "http://localhost:9000";
+
+/// GET /api/v1/tomains/{id}/egress-cache
+/// Egress cache policies per environment and alias.
+#[instrument(skip(state))]
+pub async fn get_policies(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let reg = state.registry.read().await;
+    if !reg.tomains.contains_key(&id) {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Tomain not found"}))).into_response();
+    }
+    (StatusCode::OK, Json(reg.egress_cache.get(&id).cloned().unwrap_or_default())).into_response()
+}
+
+/// PUT /api/v1/tomains/{id}/egress-cache/{env}/{alias}
+/// Turns on caching for one alias in one environment (or GLOBAL) and hot-reloads the Shell.
+#[instrument(skip(state))]
+pub async fn set_policy(
+    State(state): State<AppState>,
+    Path((id, env, alias)): Path<(String, String, String)>,
+    Json(policy): Json<CachePolicy>,
+) -> impl IntoResponse {
+    let env = env.to_uppercase();
+    {
+        let mut reg = state.registry.write().await;
+        if !reg.tomains.contains_key(&id) {
+            return (StatusCode::NOT_FOUND, "Tomain not found").into_response();
+        }
+        info!("🗄️ Egress cache for {} ({}/{}): {:?}", id, env, alias, policy);
+        reg.egress_cache.entry(id).or_default().entry(env).or_default().insert(alias, policy);
+        reg.flush();
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, "Egress cache policy updated").into_response()
+}
+
+/// DELETE /api/v1/tomains/{id}/egress-cache/{env}/{alias}
+/// Turns caching off for the alias; entries already cached are purged from the Shell.
+#[instrument(skip(state))]
+pub async fn delete_policy(
+    State(state): State<AppState>,
+    Path((id, env, alias)): Path<(String, String, String)>,
+) -> impl IntoResponse {
+    let env = env.to_uppercase();
+    {
+        let mut reg = state.registry.write().await;
+        let removed = reg.egress_cache.get_mut(&id)
+            .and_then(|envs| envs.get_mut(&env))
+            .and_then(|aliases| aliases.remove(&alias))
+            .is_some();
+        if !removed {
+            return (StatusCode::NOT_FOUND, "Egress cache policy not found").into_response();
+        }
+        if let Some(envs) = reg.egress_cache.get_mut(&id) {
+            envs.retain(|_, aliases| !aliases.is_empty());
+        }
+        reg.flush();
+        info!("🗄️ Egress cache for {} ({}/{}) removed", id, env, alias);
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    let filter = serde_json::json!({ "tomain_id": id, "alias": alias });
+    let _ = reqwest::Client::new().post(format!("{}/admin/egress-cache/purge", SHELL_BASE_URL)).json(&filter).send().await;
+    (StatusCode::OK, "Egress cache policy removed").into_response()
+}
+
+/// POST /api/v1/tomains/{id}/egress-cache/purge
+/// Drops this tomain's cached responses in the Shell; body may narrow it to an `environment` and/or `alias`.
+#[instrument]
+pub async fn purge(
+    Path(id): Path<String>,
+    Json(filter): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    let filter = serde_json::json!({
+        "tomain_id": id,
+        "environment": filter.get("environment"),
+        "alias": filter.get("alias"),
+    });
+    info!("🧹 Purging egress cache: {}", filter);
+    match reqwest::Client::new().post(format!("{}/admin/egress-cache/purge", SHELL_BASE_URL)).json(&filter).send().await {
+        Ok(res) => {
+            let status = StatusCode::from_u16(res.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+            (status, res.text().await.unwrap_or_default()).into_response()
+        }
+        Err(e) => (StatusCode::BAD_GATEWAY, format!("Shell not reachable: {}", e)).into_response(),
+    }
+}
@@ -0,0 +1,46 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use tracing::{info, instrument};
+use crate::handlers::bindings::push_reload_to_shell;
+use crate::handlers::registry::{AppState, EgressPolicy};
+
+/// GET /api/v1/tomains/{id}/egress-policy
+/// Raw-URL egress policies per environment.
+#[instrument(skip(state))]
+pub async fn get_policies(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let reg = state.registry.read().await;
+    if !reg.tomains.contains_key(&id) {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Tomain not found"}))).into_response();
+    }
+    (StatusCode::OK, Json(reg.egress_policies.get(&id).cloned().unwrap_or_default())).into_response()
+}
+
+/// PUT /api/v1/tomains/{id}/egress-policy/{env}
+/// Replaces the policy for one environment (or GLOBAL) and hot-reloads the Shell.
+#[instrument(skip(state))]
+pub async fn set_policy(
+    State(state): State<AppState>,
+    Path((id, env)): Path<(String, String)>,
+    Json(policy): Json<EgressPolicy>,
+) -> impl IntoResponse {
+    let env = env.to_uppercase();
+    {
+        let mut reg = state.registry.write().await;
+        if !reg.tomains.contains_key(&id) {
+            return (StatusCode::NOT_FOUND, "Tomain not found").into_response();
+        }
+        info!("🌐 Egress policy for {} ({}): raw URLs {}, hosts {:?}", id, env, if policy.allow_raw_urls { "allowed" } else { "denied" }, policy.hosts);
+        reg.egress_policies.entry(id.clone()).or_default().insert(env, policy);
+        reg.flush();
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, "Egress policy updated").into_response()
+}
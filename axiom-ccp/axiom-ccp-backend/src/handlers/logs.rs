@@ -0,0 +1,66 @@
+use axum::{
+    body::Body,
+    extract::{Path, RawQuery},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use tracing::{info, instrument};
+
+const SHELL_BASE_URL: &str = "http://localhost:9000";
+
+fn shell_logs_url(id: &str, suffix: &str, query: &Option<String>) -> String {
+    match query {
+        Some(q) if !q.is_empty() => format!("{}/admin/logs/{}{}?{}", SHELL_BASE_URL, id, suffix, q),
+        _ => format!("{}/admin/logs/{}{}", SHELL_BASE_URL, id, suffix),
+    }
+}
+
+/// GET /api/v1/tomains/{id}/logs?slot=&level=&since=&until=&limit=
+/// Proxies the Shell's captured guest logs for a tomain.
+#[instrument]
+pub async fn get_logs(
+    Path(id): Path<String>,
+    RawQuery(query): RawQuery,
+) -> impl IntoResponse {
+    match reqwest::get(shell_logs_url(&id, "", &query)).await {
+        Ok(res) => {
+            let status = StatusCode::from_u16(res.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+            let body = res.text().await.unwrap_or_default();
+            Response::builder()
+                .status(status)
+                .header("Content-Type", "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        }
+        Err(e) => (StatusCode::BAD_GATEWAY, format!("Shell not reachable: {}", e)).into_response(),
+    }
+}
+
+/// GET /api/v1/tomains/{id}/logs/tail?slot=&level=
+/// Relays the Shell's Server-Sent Events log tail chunk by chunk.
+#[instrument]
+pub async fn tail_logs(
+    Path(id): Path<String>,
+    RawQuery(query): RawQuery,
+) -> impl IntoResponse {
+    let res = match reqwest::get(shell_logs_url(&id, "/tail", &query)).await {
+        Ok(res) if res.status().is_success() => res,
+        Ok(res) => return (StatusCode::BAD_GATEWAY, format!("Shell returned {}", res.status())).into_response(),
+        Err(e) => return (StatusCode::BAD_GATEWAY, format!("Shell not reachable: {}", e)).into_response(),
+    };
+
+    info!("📜 Relaying log tail for {}", id);
+    let stream = futures_util::stream::unfold(res, |mut res| async move {
+        match res.chunk().await {
+            Ok(Some(chunk)) => Some((Ok::<_, reqwest::Error>(chunk), res)),
+            Ok(None) => None,
+            Err(e) => Some((Err(e), res)),
+        }
+    });
+
+    Response::builder()
+        .header("Content-Type", "text/event-stream")
+        .header("Cache-Control", "no-cache")
+        .body(Body::from_stream(stream))
+        .unwrap()
+}
@@ -1,4 +1,17 @@
 pub mod bindings;
+pub mod breakers;
+pub mod bulkheads;
+pub mod chaos;
+pub mod config;
 pub mod docs;
+pub mod egress_cache;
+pub mod egress_policy;
+pub mod logs;
+pub mod oauth;
+pub mod rate_limits;
 pub mod registry;
+pub mod retry_policy;
+pub mod schedules;
+pub mod secrets;
+pub mod tls;
 pub mod tomain;
@@ -0,0 +1,85 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use tracing::{info, instrument};
+use crate::handlers::bindings::push_reload_to_shell;
+use crate::handlers::registry::{AppState, OAuthClient};
+
+/// GET /api/v1/tomains/{id}/oauth
+/// OAuth2 client-credentials configs per environment and alias.
+#[instrument(skip(state))]
+pub async fn get_clients(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let reg = state.registry.read().await;
+    if !reg.tomains.contains_key(&id) {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Tomain not found"}))).into_response();
+    }
+    (StatusCode::OK, Json(reg.oauth2_clients.get(&id).cloned().unwrap_or_default())).into_response()
+}
+
+/// PUT /api/v1/tomains/{id}/oauth/{env}/{alias}
+/// Makes the Shell fetch bearer tokens for one alias in one environment (or GLOBAL) and hot-reloads it.
+/// The referenced client secret must already be set with PUT /api/v1/tomains/{id}/secrets/{key}.
+#[instrument(skip(state))]
+pub async fn set_client(
+    State(state): State<AppState>,
+    Path((id, env, alias)): Path<(String, String, String)>,
+    Json(client): Json<OAuthClient>,
+) -> impl IntoResponse {
+    if !client.token_url.starts_with("https://") && !client.token_url.starts_with("http://") {
+        return (StatusCode::BAD_REQUEST, "token_url must be an http(s) URL").into_response();
+    }
+    if client.auth_method.as_deref().is_some_and(|m| m != "client_secret_basic" && m != "client_secret_post") {
+        return (StatusCode::BAD_REQUEST, "auth_method must be client_secret_basic or client_secret_post").into_response();
+    }
+    let env = env.to_uppercase();
+    {
+        let mut reg = state.registry.write().await;
+        if !reg.tomains.contains_key(&id) {
+            return (StatusCode::NOT_FOUND, "Tomain not found").into_response();
+        }
+        let secret_set = reg.secrets.get(&id).is_some_and(|keys| keys.contains_key(&client.client_secret_ref));
+        if !secret_set {
+            return (StatusCode::BAD_REQUEST, format!("Secret '{}' is not set for {}", client.client_secret_ref, id)).into_response();
+        }
+        info!("🔑 OAuth2 client for {} ({}/{}): {} via {}", id, env, alias, client.client_id, client.token_url);
+        reg.oauth2_clients.entry(id).or_default().entry(env).or_default().insert(alias, client);
+        reg.flush();
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, "OAuth2 client updated").into_response()
+}
+
+/// DELETE /api/v1/tomains/{id}/oauth/{env}/{alias}
+/// Falls back to the alias's static vault token, if any.
+#[instrument(skip(state))]
+pub async fn delete_client(
+    State(state): State<AppState>,
+    Path((id, env, alias)): Path<(String, String, String)>,
+) -> impl IntoResponse {
+    let env = env.to_uppercase();
+    {
+        let mut reg = state.registry.write().await;
+        let removed = reg.oauth2_clients.get_mut(&id)
+            .and_then(|envs| envs.get_mut(&env))
+            .and_then(|aliases| aliases.remove(&alias))
+            .is_some();
+        if !removed {
+            return (StatusCode::NOT_FOUND, "OAuth2 client not found").into_response();
+        }
+        if let Some(envs) = reg.oauth2_clients.get_mut(&id) {
+            envs.retain(|_, aliases| !aliases.is_empty());
+        }
+        reg.flush();
+        info!("🔑 OAuth2 client for {} ({}/{}) removed", id, env, alias);
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, "OAuth2 client removed").into_response()
+}
@@ -0,0 +1,107 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::{info, instrument};
+use crate::handlers::bindings::push_reload_to_shell;
+use crate::handlers::registry::AppState;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub rate_per_sec: f64,
+    /// Bucket capacity; defaults to one second of `rate_per_sec`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<f64>,
+}
+
+/// Ingress limit for a tomain, counted per caller identity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitPolicy {
+    /// `tomain` (one shared bucket), `subject` (JWT sub), `api_key` or `client_ip`
+    #[serde(default = "default_key_by")]
+    pub key_by: String,
+    #[serde(flatten)]
+    pub limit: RateLimit,
+    /// Per-function overrides
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub functions: HashMap<String, RateLimit>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_header: Option<String>,
+}
+
+fn default_key_by() -> String { "tomain".to_string() }
+
+fn invalid_limit(limit: &RateLimit) -> bool {
+    limit.rate_per_sec <= 0.0 || limit.burst.is_some_and(|b| b < 1.0)
+}
+
+/// GET /api/v1/tomains/{id}/rate-limit
+#[instrument(skip(state))]
+pub async fn get_rate_limit(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let reg = state.registry.read().await;
+    let limit = reg.rate_limits.as_ref().and_then(|rl| rl.get(&id)).cloned().unwrap_or(serde_json::Value::Null);
+    (StatusCode::OK, Json(limit))
+}
+
+/// PUT /api/v1/tomains/{id}/rate-limit
+/// Replaces the tomain's ingress policy and hot-reloads the Shell.
+#[instrument(skip(state))]
+pub async fn set_rate_limit(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(policy): Json<RateLimitPolicy>,
+) -> impl IntoResponse {
+    if !["tomain", "subject", "api_key", "client_ip"].contains(&policy.key_by.as_str()) {
+        return (StatusCode::BAD_REQUEST, format!("Unknown key_by '{}'", policy.key_by)).into_response();
+    }
+    if invalid_limit(&policy.limit) || policy.functions.values().any(invalid_limit) {
+        return (StatusCode::BAD_REQUEST, "rate_per_sec must be positive and burst at least 1").into_response();
+    }
+    {
+        let mut reg = state.registry.write().await;
+        if !reg.tomains.contains_key(&id) {
+            return (StatusCode::NOT_FOUND, "Tomain not found").into_response();
+        }
+        info!("🚦 Rate limit for {}: {} req/sec by {}", id, policy.limit.rate_per_sec, policy.key_by);
+        let value = serde_json::to_value(&policy).unwrap_or_default();
+        reg.rate_limits.get_or_insert_with(HashMap::new).insert(id.clone(), value);
+        reg.flush();
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, "Rate limit updated").into_response()
+}
+
+/// PUT /api/v1/rate-limits/downstream/{alias}
+/// Sets the egress limit for one alias across all tomains and hot-reloads the Shell.
+#[instrument(skip(state))]
+pub async fn set_downstream_limit(
+    State(state): State<AppState>,
+    Path(alias): Path<String>,
+    Json(limit): Json<RateLimit>,
+) -> impl IntoResponse {
+    if invalid_limit(&limit) {
+        return (StatusCode::BAD_REQUEST, "rate_per_sec must be positive and burst at least 1").into_response();
+    }
+    {
+        let mut reg = state.registry.write().await;
+        info!("🚦 Downstream rate limit for {}: {} req/sec", alias, limit.rate_per_sec);
+        let downstream = reg.rate_limits.get_or_insert_with(HashMap::new)
+            .entry("downstream".to_string())
+            .or_insert_with(|| serde_json::json!({}));
+        if let Some(map) = downstream.as_object_mut() {
+            map.insert(alias, serde_json::to_value(&limit).unwrap_or_default());
+        }
+        reg.flush();
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, "Downstream rate limit updated").into_response()
+}
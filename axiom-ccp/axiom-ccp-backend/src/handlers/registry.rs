@@ -17,6 +17,9 @@ pub struct AxiomRegistry {
     pub bindings: HashMap<String, HashMap<String, HashMap<String, String>>>,
     /// tomain_id → { key → value } (secrets/env vars)
     pub secrets: HashMap<String, HashMap<String, String>>,
+    /// tomain_id → { environment → secret keys its kernels may read there }
+    #[serde(default)]
+    pub secret_grants: HashMap<String, HashMap<String, Vec<String>>>,
     /// tomain_id → { logical_name → alias_name (@main-db) }
     pub manifests: HashMap<String, HashMap<String, String>>,
     /// list of repository paths or metadata
@@ -29,6 +32,42 @@ pub struct AxiomRegistry {
     pub vault: Option<HashMap<String, String>>,
     /// Global infra info (e.g. registry URL, VPC ID, etc)
     pub infra: HashMap<String, String>,
+    /// tomain_id → { function → cron expression } from axiom.toml [schedules]
+    #[serde(default)]
+    pub schedules: HashMap<String, HashMap<String, String>>,
+    /// tomain_id → capabilities declared in axiom.toml [capabilities]; absent = nothing granted
+    #[serde(default)]
+    pub capabilities: HashMap<String, Capabilities>,
+    /// tomain_id → { environment (or GLOBAL) → policy for raw-URL egress }
+    #[serde(default)]
+    pub egress_policies: HashMap<String, HashMap<String, EgressPolicy>>,
+    /// tomain_id → { environment → non-secret config values }
+    #[serde(default)]
+    pub config: HashMap<String, HashMap<String, ConfigSet>>,
+    /// tomain_id → { environment (or GLOBAL) → { alias → egress retry policy } }
+    #[serde(default)]
+    pub retry_policies: HashMap<String, HashMap<String, HashMap<String, RetryPolicy>>>,
+    /// tomain_id → { environment (or GLOBAL) → { alias → circuit breaker policy } }
+    #[serde(default)]
+    pub breaker_policies: HashMap<String, HashMap<String, HashMap<String, BreakerPolicy>>>,
+    /// tomain_id → { environment (or GLOBAL) → ingress concurrency limit }
+    #[serde(default)]
+    pub bulkheads: HashMap<String, HashMap<String, BulkheadPolicy>>,
+    /// alias → egress concurrency limit, shared by every tomain calling it
+    #[serde(default)]
+    pub egress_bulkheads: HashMap<String, BulkheadPolicy>,
+    /// tomain_id → { environment (or GLOBAL) → { alias → egress response cache policy } }; absent = uncached
+    #[serde(default)]
+    pub egress_cache: HashMap<String, HashMap<String, HashMap<String, CachePolicy>>>,
+    /// tomain_id → { environment (or GLOBAL) → { alias → chaos fault injection rule } }
+    #[serde(default)]
+    pub fault_injection: HashMap<String, HashMap<String, HashMap<String, FaultRule>>>,
+    /// tomain_id → { environment (or GLOBAL) → { alias → OAuth2 client-credentials config } }
+    #[serde(default)]
+    pub oauth2_clients: HashMap<String, HashMap<String, HashMap<String, OAuthClient>>>,
+    /// tomain_id → { environment (or GLOBAL) → { alias → TLS profile (mTLS, CAs, SNI) } }
+    #[serde(default)]
+    pub tls_profiles: HashMap<String, HashMap<String, HashMap<String, TlsProfile>>>,
 }
 
 fn default_perspective() -> String { "DEV".to_string() }
@@ -67,6 +106,201 @@ pub struct TomainEntry {
     pub apis: Option<Vec<ApiDetail>>,
 }
 
+/// Host capabilities a tomain's kernels may import. Alias lists also accept "*".
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Capabilities {
+    #[serde(default)]
+    pub http: Vec<String>,
+    #[serde(default)]
+    pub db: Vec<String>,
+    #[serde(default)]
+    pub tomains: Vec<String>,
+    #[serde(default)]
+    pub kv: bool,
+    #[serde(default)]
+    pub secrets: bool,
+    /// Event topics the kernels may publish and subscribe to
+    #[serde(default)]
+    pub events: EventCapabilities,
+    #[serde(default)]
+    pub logging: bool,
+}
+
+/// Topic patterns; `*` matches one dot-separated token, `>` the rest.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventCapabilities {
+    #[serde(default)]
+    pub publish: Vec<String>,
+    #[serde(default)]
+    pub subscribe: Vec<String>,
+}
+
+/// Which raw URLs a tomain may call. Hosts accept exact names, "*.example.com" and "*";
+/// empty ports mean default ports only, empty schemes mean https only.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EgressPolicy {
+    #[serde(default)]
+    pub allow_raw_urls: bool,
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    #[serde(default)]
+    pub ports: Vec<u16>,
+    #[serde(default)]
+    pub schemes: Vec<String>,
+}
+
+/// Egress retry policy for one alias. Unset fields take the Shell's defaults: 3 attempts,
+/// 100ms–2s jittered backoff, retry on 502/503/504, 5s per attempt, 15s overall, idempotent methods only.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_delay_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_delay_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jitter: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_on: Option<Vec<u16>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attempt_timeout_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline_ms: Option<u64>,
+    /// Retry POST/PATCH too, sending the same idempotency key on every attempt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_non_idempotent: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_header: Option<String>,
+}
+
+/// Circuit breaker policy for one alias. Unset fields take the Shell's defaults: open at a 50%
+/// failure rate over 60s once 5 calls were seen, stay open 30s, then close after 1 successful probe.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BreakerPolicy {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_rate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_requests: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub half_open_probes: Option<u32>,
+}
+
+/// Concurrency limit with a bounded wait queue. Unset fields take the Shell's defaults
+/// (256 concurrent and 1024 queued for ingress, 64 and 256 for egress, 2s queue timeout).
+/// `adaptive` lets the limit shrink under latency above `target_latency_ms` and grow back.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkheadPolicy {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrency: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_queue: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_timeout_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adaptive: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_concurrency: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_latency_ms: Option<u64>,
+}
+
+/// Opt-in cache for GET/HEAD egress calls. Freshness follows the downstream's Cache-Control
+/// unless `ttl_secs` overrides it; unset fields take the Shell's defaults (1h cap, 1 MiB entries).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CachePolicy {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_ttl_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vary_headers: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_entry_bytes: Option<usize>,
+}
+
+/// Faults the Shell injects into egress and DB calls for one alias. Rates are 0.0-1.0;
+/// unset fields take the Shell's defaults (enabled, no faults, 503 for injected errors).
+/// The Shell ignores the rule in PROD (the live GREEN and RED perspectives) unless `force` is set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FaultRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_jitter_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_rate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_statuses: Option<Vec<u16>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reset_rate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncate_rate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub force: Option<bool>,
+}
+
+/// OAuth2 client-credentials grant the Shell uses to fetch bearer tokens for a binding.
+/// `client_secret_ref` names a key in the tomain's `secrets`; the secret itself never lives here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthClient {
+    pub token_url: String,
+    pub client_id: String,
+    pub client_secret_ref: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audience: Option<String>,
+    /// "client_secret_basic" (default) or "client_secret_post"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_method: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_skew_secs: Option<u64>,
+}
+
+/// TLS settings the Shell applies to one binding. Cert and key refs name keys in the tomain's
+/// `secrets` (PEM); `ca_bundles` are PEM bundles trusted on top of the public roots.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TlsProfile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_cert_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_key_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ca_bundles: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sni_override: Option<String>,
+    /// "1.2" or "1.3"; "1.0"/"1.1" only outside PROD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_tls_version: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub insecure_skip_verify: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub insecure_skip_hostname_verify: bool,
+}
+
+impl TlsProfile {
+    pub fn is_insecure(&self) -> bool {
+        self.insecure_skip_verify
+            || self.insecure_skip_hostname_verify
+            || matches!(self.min_tls_version.as_deref(), Some("1.0" | "1.1"))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigSet {
+    /// Bumped on every change so the Shell can report which version each slot runs with
+    pub version: u64,
+    pub values: HashMap<String, serde_json::Value>,
+    pub updated_at: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiDetail {
     pub name: String,
@@ -80,9 +314,21 @@ impl AxiomRegistry {
         self.tomains.remove(id);
         self.bindings.remove(id);
         self.secrets.remove(id);
+        self.secret_grants.remove(id);
         if let Some(rl) = &mut self.rate_limits { rl.remove(id); }
         if let Some(pk) = &mut self.public_keys { pk.remove(id); }
         if let Some(v) = &mut self.vault { v.remove(id); }
+        self.schedules.remove(id);
+        self.config.remove(id);
+        self.capabilities.remove(id);
+        self.egress_policies.remove(id);
+        self.retry_policies.remove(id);
+        self.breaker_policies.remove(id);
+        self.bulkheads.remove(id);
+        self.egress_cache.remove(id);
+        self.fault_injection.remove(id);
+        self.oauth2_clients.remove(id);
+        self.tls_profiles.remove(id);
         self.flush();
     }
 
@@ -0,0 +1,77 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use tracing::{info, instrument};
+use crate::handlers::bindings::push_reload_to_shell;
+use crate::handlers::registry::{AppState, RetryPolicy};
+
+/// GET /api/v1/tomains/{id}/retry-policy
+/// Egress retry policies per environment and alias.
+#[instrument(skip(state))]
+pub async fn get_policies(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let reg = state.registry.read().await;
+    if !reg.tomains.contains_key(&id) {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Tomain not found"}))).into_response();
+    }
+    (StatusCode::OK, Json(reg.retry_policies.get(&id).cloned().unwrap_or_default())).into_response()
+}
+
+/// PUT /api/v1/tomains/{id}/retry-policy/{env}/{alias}
+/// Replaces the retry policy for one alias in one environment (or GLOBAL) and hot-reloads the Shell.
+#[instrument(skip(state))]
+pub async fn set_policy(
+    State(state): State<AppState>,
+    Path((id, env, alias)): Path<(String, String, String)>,
+    Json(policy): Json<RetryPolicy>,
+) -> impl IntoResponse {
+    let env = env.to_uppercase();
+    if policy.max_attempts == Some(0) {
+        return (StatusCode::BAD_REQUEST, "max_attempts must be at least 1").into_response();
+    }
+    {
+        let mut reg = state.registry.write().await;
+        if !reg.tomains.contains_key(&id) {
+            return (StatusCode::NOT_FOUND, "Tomain not found").into_response();
+        }
+        info!("🔁 Retry policy for {} ({}/{}): {:?}", id, env, alias, policy);
+        reg.retry_policies.entry(id.clone()).or_default().entry(env).or_default().insert(alias, policy);
+        reg.flush();
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, "Retry policy updated").into_response()
+}
+
+/// DELETE /api/v1/tomains/{id}/retry-policy/{env}/{alias}
+/// Reverts the alias to the Shell's default retry policy.
+#[instrument(skip(state))]
+pub async fn delete_policy(
+    State(state): State<AppState>,
+    Path((id, env, alias)): Path<(String, String, String)>,
+) -> impl IntoResponse {
+    let env = env.to_uppercase();
+    {
+        let mut reg = state.registry.write().await;
+        let removed = reg.retry_policies.get_mut(&id)
+            .and_then(|envs| envs.get_mut(&env))
+            .and_then(|aliases| aliases.remove(&alias))
+            .is_some();
+        if !removed {
+            return (StatusCode::NOT_FOUND, "Retry policy not found").into_response();
+        }
+        if let Some(envs) = reg.retry_policies.get_mut(&id) {
+            envs.retain(|_, aliases| !aliases.is_empty());
+        }
+        reg.flush();
+        info!("🔁 Retry policy for {} ({}/{}) removed", id, env, alias);
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, "Retry policy removed").into_response()
+}
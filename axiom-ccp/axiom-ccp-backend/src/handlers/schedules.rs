@@ -0,0 +1,46 @@
+use axum::{
+    body::Body,
+    extract::Path,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use tracing::{info, instrument};
+
+const SHELL_BASE_URL: &str = "http://localhost:9000";
+
+/// GET /api/v1/tomains/{id}/schedules
+/// Proxies the Shell's live schedule state (next/last run, pause flag, history).
+#[instrument]
+pub async fn list_schedules(Path(id): Path<String>) -> impl IntoResponse {
+    match reqwest::get(format!("{}/admin/schedules/{}", SHELL_BASE_URL, id)).await {
+        Ok(res) => {
+            let status = StatusCode::from_u16(res.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+            let body = res.text().await.unwrap_or_default();
+            Response::builder()
+                .status(status)
+                .header("Content-Type", "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        }
+        Err(e) => (StatusCode::BAD_GATEWAY, format!("Shell not reachable: {}", e)).into_response(),
+    }
+}
+
+/// POST /api/v1/tomains/{id}/schedules/{function}/{action}
+/// Pauses or resumes one schedule (`action` is `pause` or `resume`).
+#[instrument]
+pub async fn control_schedule(Path((id, function, action)): Path<(String, String, String)>) -> impl IntoResponse {
+    if action != "pause" && action != "resume" {
+        return (StatusCode::BAD_REQUEST, format!("Unknown schedule action '{}'", action)).into_response();
+    }
+
+    info!("⏰ {} schedule {}/{}", action, id, function);
+    let url = format!("{}/admin/schedules/{}/{}/{}", SHELL_BASE_URL, id, function, action);
+    match reqwest::Client::new().post(url).send().await {
+        Ok(res) => {
+            let status = StatusCode::from_u16(res.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+            (status, res.text().await.unwrap_or_default()).into_response()
+        }
+        Err(e) => (StatusCode::BAD_GATEWAY, format!("Shell not reachable: {}", e)).into_response(),
+    }
+}
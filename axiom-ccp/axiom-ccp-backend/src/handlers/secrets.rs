@@ -0,0 +1,103 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use tracing::{info, instrument};
+use crate::handlers::bindings::push_reload_to_shell;
+use crate::handlers::registry::AppState;
+
+#[derive(Deserialize)]
+pub struct SetSecretRequest {
+    pub value: String,
+    /// Environments whose kernels may read the secret (e.g. ["QA", "PROD"])
+    #[serde(default)]
+    pub environments: Vec<String>,
+}
+
+/// GET /api/v1/tomains/{id}/secrets
+/// Lists secret keys and the environments granted each one. Values are never returned.
+#[instrument(skip(state))]
+pub async fn list_secrets(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let reg = state.registry.read().await;
+    let grants = reg.secret_grants.get(&id);
+    let mut keys: Vec<&String> = reg.secrets.get(&id).map(|s| s.keys().collect()).unwrap_or_default();
+    keys.sort();
+
+    let secrets: Vec<serde_json::Value> = keys.into_iter().map(|key| {
+        let mut environments: Vec<&String> = grants
+            .map(|g| g.iter().filter(|(_, keys)| keys.contains(key)).map(|(env, _)| env).collect())
+            .unwrap_or_default();
+        environments.sort();
+        serde_json::json!({ "key": key, "environments": environments })
+    }).collect();
+    (StatusCode::OK, Json(secrets))
+}
+
+/// PUT /api/v1/tomains/{id}/secrets/{key}
+/// Sets a secret's value and replaces the environments it is granted in, then hot-reloads the Shell.
+#[instrument(skip(state, payload))]
+pub async fn set_secret(
+    State(state): State<AppState>,
+    Path((id, key)): Path<(String, String)>,
+    Json(payload): Json<SetSecretRequest>,
+) -> impl IntoResponse {
+    {
+        let mut reg = state.registry.write().await;
+        if !reg.tomains.contains_key(&id) {
+            return (StatusCode::NOT_FOUND, "Tomain not found").into_response();
+        }
+        reg.secrets.entry(id.clone()).or_default().insert(key.clone(), payload.value);
+
+        let environments: Vec<String> = payload.environments.iter().map(|e| e.to_uppercase()).collect();
+        let grants = reg.secret_grants.entry(id.clone()).or_default();
+        for (env, keys) in grants.iter_mut() {
+            if !environments.contains(env) {
+                keys.retain(|k| k != &key);
+            }
+        }
+        for env in &environments {
+            let keys = grants.entry(env.clone()).or_default();
+            if !keys.contains(&key) {
+                keys.push(key.clone());
+            }
+        }
+        grants.retain(|_, keys| !keys.is_empty());
+        reg.flush();
+        info!("🔐 Secret '{}' set for {} (granted in {:?})", key, id, environments);
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, "Secret stored").into_response()
+}
+
+/// DELETE /api/v1/tomains/{id}/secrets/{key}
+#[instrument(skip(state))]
+pub async fn delete_secret(
+    State(state): State<AppState>,
+    Path((id, key)): Path<(String, String)>,
+) -> impl IntoResponse {
+    {
+        let mut reg = state.registry.write().await;
+        let removed = reg.secrets.get_mut(&id).and_then(|s| s.remove(&key)).is_some();
+        if !removed {
+            return (StatusCode::NOT_FOUND, "Secret not found").into_response();
+        }
+        if let Some(grants) = reg.secret_grants.get_mut(&id) {
+            for keys in grants.values_mut() {
+                keys.retain(|k| k != &key);
+            }
+            grants.retain(|_, keys| !keys.is_empty());
+        }
+        reg.flush();
+        info!("🔐 Secret '{}' deleted for {}", key, id);
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, "Secret deleted").into_response()
+}
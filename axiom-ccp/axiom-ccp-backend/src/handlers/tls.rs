@@ -0,0 +1,105 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use tracing::{info, instrument, warn};
+use crate::handlers::bindings::push_reload_to_shell;
+use crate::handlers::registry::{AppState, TlsProfile};
+
+fn validate(env: &str, profile: &TlsProfile) -> Result<(), &'static str> {
+    if profile.client_cert_ref.is_some() != profile.client_key_ref.is_some() {
+        return Err("client_cert_ref and client_key_ref must be set together");
+    }
+    if profile.min_tls_version.as_deref().is_some_and(|v| !matches!(v, "1.0" | "1.1" | "1.2" | "1.3")) {
+        return Err("min_tls_version must be 1.0, 1.1, 1.2 or 1.3");
+    }
+    if profile.ca_bundles.iter().any(|pem| !pem.contains("-----BEGIN CERTIFICATE-----")) {
+        return Err("ca_bundles must be PEM certificates");
+    }
+    // GLOBAL profiles also apply to PROD, and the Shell treats the live GREEN and RED perspectives as PROD
+    if profile.is_insecure() && matches!(env, "PROD" | "GLOBAL" | "GREEN" | "RED") {
+        return Err("Insecure TLS settings (skipped verification, TLS < 1.2) are not allowed in PROD (GREEN/RED) or GLOBAL");
+    }
+    Ok(())
+}
+
+/// GET /api/v1/tomains/{id}/tls
+/// TLS profiles per environment and alias.
+#[instrument(skip(state))]
+pub async fn get_profiles(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let reg = state.registry.read().await;
+    if !reg.tomains.contains_key(&id) {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Tomain not found"}))).into_response();
+    }
+    (StatusCode::OK, Json(reg.tls_profiles.get(&id).cloned().unwrap_or_default())).into_response()
+}
+
+/// PUT /api/v1/tomains/{id}/tls/{env}/{alias}
+/// Replaces the TLS profile for one alias in one environment (or GLOBAL) and hot-reloads the Shell.
+/// Referenced cert and key secrets must already be set.
+#[instrument(skip(state, profile))]
+pub async fn set_profile(
+    State(state): State<AppState>,
+    Path((id, env, alias)): Path<(String, String, String)>,
+    Json(profile): Json<TlsProfile>,
+) -> impl IntoResponse {
+    let env = env.to_uppercase();
+    if let Err(msg) = validate(&env, &profile) {
+        return (StatusCode::BAD_REQUEST, msg).into_response();
+    }
+    {
+        let mut reg = state.registry.write().await;
+        if !reg.tomains.contains_key(&id) {
+            return (StatusCode::NOT_FOUND, "Tomain not found").into_response();
+        }
+        let keys = reg.secrets.get(&id);
+        let missing = [&profile.client_cert_ref, &profile.client_key_ref].into_iter().flatten()
+            .find(|key| !keys.is_some_and(|k| k.contains_key(*key)));
+        if let Some(key) = missing {
+            return (StatusCode::BAD_REQUEST, format!("Secret '{}' is not set for {}", key, id)).into_response();
+        }
+        if profile.is_insecure() {
+            warn!("🔓 Insecure TLS profile for {} ({}/{})", id, env, alias);
+        } else {
+            info!("🔒 TLS profile for {} ({}/{}) updated", id, env, alias);
+        }
+        reg.tls_profiles.entry(id).or_default().entry(env).or_default().insert(alias, profile);
+        reg.flush();
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, "TLS profile updated").into_response()
+}
+
+/// DELETE /api/v1/tomains/{id}/tls/{env}/{alias}
+/// The alias goes back to the Shell's default client.
+#[instrument(skip(state))]
+pub async fn delete_profile(
+    State(state): State<AppState>,
+    Path((id, env, alias)): Path<(String, String, String)>,
+) -> impl IntoResponse {
+    let env = env.to_uppercase();
+    {
+        let mut reg = state.registry.write().await;
+        let removed = reg.tls_profiles.get_mut(&id)
+            .and_then(|envs| envs.get_mut(&env))
+            .and_then(|aliases| aliases.remove(&alias))
+            .is_some();
+        if !removed {
+            return (StatusCode::NOT_FOUND, "TLS profile not found").into_response();
+        }
+        if let Some(envs) = reg.tls_profiles.get_mut(&id) {
+            envs.retain(|_, aliases| !aliases.is_empty());
+        }
+        reg.flush();
+        info!("🔒 TLS profile for {} ({}/{}) removed", id, env, alias);
+    }
+
+    tokio::spawn(push_reload_to_shell());
+    (StatusCode::OK, "TLS profile removed").into_response()
+}
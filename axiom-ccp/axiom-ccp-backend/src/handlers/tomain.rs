@@ -268,7 +268,7 @@ pub async fn get_manifest(
                 "tomain_id": id,
                 "wit": entry.wit,
                 "perspective": entry.perspective,
-                "capabilities": ["http", "persistence", "tracing"],
+                "capabilities": reg.capabilities.get(&id),
                 "repo_url": entry.repo_url,
                 "features": entry.features,
             });
@@ -457,6 +457,10 @@ pub struct UpdateManifestRequest {
     pub resources: std::collections::HashMap<String, ResourceDef>,
     pub apis: Option<Vec<crate::handlers::registry::ApiDetail>>,
     pub vault_path: Option<String>,
+    /// function → cron expression
+    pub schedules: Option<std::collections::HashMap<String, String>>,
+    /// Host capabilities the kernel may import
+    pub capabilities: Option<crate::handlers::registry::Capabilities>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -492,6 +496,20 @@ pub async fn update_manifest(
             entry.repo_url = Some(vault);
         }
     }
+
+    if let Some(schedules) = payload.schedules {
+        if schedules.is_empty() {
+            reg.schedules.remove(&id);
+        } else {
+            reg.schedules.insert(id.clone(), schedules);
+        }
+    }
+
+    // A manifest without [capabilities] grants nothing, so drop whatever an earlier sync stored
+    match payload.capabilities {
+        Some(capabilities) => { reg.capabilities.insert(id.clone(), capabilities); }
+        None => { reg.capabilities.remove(&id); }
+    }
     
     reg.flush();
     
@@ -1,6 +1,6 @@
 mod handlers;
 
-use axum::{routing::{get, post}, Router};
+use axum::{routing::{get, post, put}, Router};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -38,7 +38,37 @@ async fn main() {
         .route("/api/v1/tomains/{id}/features", post(handlers::tomain::register_feature))
         .route("/api/v1/tomains/{id}/features/{feature_name}/wasm", post(handlers::tomain::upload_feature_wasm))
         .route("/api/v1/tomains/{id}/retire", post(handlers::tomain::retire_tomain))
+        .route("/api/v1/tomains/{id}/logs", get(handlers::logs::get_logs))
+        .route("/api/v1/tomains/{id}/logs/tail", get(handlers::logs::tail_logs))
+        .route("/api/v1/tomains/{id}/schedules", get(handlers::schedules::list_schedules))
+        .route("/api/v1/tomains/{id}/schedules/{function}/{action}", post(handlers::schedules::control_schedule))
+        .route("/api/v1/tomains/{id}/chaos", get(handlers::chaos::get_rules))
+        .route("/api/v1/tomains/{id}/chaos/{env}/{alias}", put(handlers::chaos::set_rule).delete(handlers::chaos::delete_rule))
+        .route("/api/v1/tomains/{id}/config", get(handlers::config::get_config))
+        .route("/api/v1/tomains/{id}/config/{env}", put(handlers::config::set_config))
+        .route("/api/v1/tomains/{id}/egress-cache", get(handlers::egress_cache::get_policies))
+        .route("/api/v1/tomains/{id}/egress-cache/purge", post(handlers::egress_cache::purge))
+        .route("/api/v1/tomains/{id}/egress-cache/{env}/{alias}", put(handlers::egress_cache::set_policy).delete(handlers::egress_cache::delete_policy))
+        .route("/api/v1/tomains/{id}/egress-policy", get(handlers::egress_policy::get_policies))
+        .route("/api/v1/tomains/{id}/egress-policy/{env}", put(handlers::egress_policy::set_policy))
+        .route("/api/v1/tomains/{id}/breakers", get(handlers::breakers::list_breakers))
+        .route("/api/v1/tomains/{id}/breakers/{env}/{alias}/{action}", post(handlers::breakers::control_breaker))
+        .route("/api/v1/tomains/{id}/breaker-policy", get(handlers::breakers::get_policies))
+        .route("/api/v1/tomains/{id}/breaker-policy/{env}/{alias}", put(handlers::breakers::set_policy))
+        .route("/api/v1/tomains/{id}/bulkhead", get(handlers::bulkheads::get_bulkheads))
+        .route("/api/v1/tomains/{id}/bulkhead/{env}", put(handlers::bulkheads::set_bulkhead))
+        .route("/api/v1/tomains/{id}/oauth", get(handlers::oauth::get_clients))
+        .route("/api/v1/tomains/{id}/oauth/{env}/{alias}", put(handlers::oauth::set_client).delete(handlers::oauth::delete_client))
+        .route("/api/v1/tomains/{id}/rate-limit", get(handlers::rate_limits::get_rate_limit).put(handlers::rate_limits::set_rate_limit))
+        .route("/api/v1/tomains/{id}/retry-policy", get(handlers::retry_policy::get_policies))
+        .route("/api/v1/tomains/{id}/retry-policy/{env}/{alias}", put(handlers::retry_policy::set_policy).delete(handlers::retry_policy::delete_policy))
+        .route("/api/v1/tomains/{id}/secrets", get(handlers::secrets::list_secrets))
+        .route("/api/v1/tomains/{id}/secrets/{key}", put(handlers::secrets::set_secret).delete(handlers::secrets::delete_secret))
+        .route("/api/v1/tomains/{id}/tls", get(handlers::tls::get_profiles))
+        .route("/api/v1/tomains/{id}/tls/{env}/{alias}", put(handlers::tls::set_profile).delete(handlers::tls::delete_profile))
         .route("/api/v1/tomains/resolve/{*tomain}", get(handlers::tomain::resolve_tomain))
+        .route("/api/v1/bulkheads/egress/{alias}", put(handlers::bulkheads::set_egress_bulkhead))
+        .route("/api/v1/rate-limits/downstream/{alias}", put(handlers::rate_limits::set_downstream_limit))
         .route("/api/v1/bindings", get(handlers::bindings::list_bindings).post(handlers::bindings::register_binding))
         .route("/api/v1/bindings/resolve", get(handlers::bindings::resolve_binding))
         .route("/api/v1/bindings/delete", post(handlers::bindings::delete_binding))
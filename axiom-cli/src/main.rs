@@ -100,6 +100,29 @@ enum Commands {
     },
     /// Show the Axiom Dashboard (Pillar #3 & #4)
     Status,
+    /// Show captured kernel logs from the Shell
+    Logs {
+        /// Micro-service/Tomain name (defaults to current session)
+        #[arg(short = 'm', long = "ms")]
+        ms: Option<String>,
+        /// Only show logs from this slot (e.g. GREEN, BLUE, RED)
+        #[arg(short = 'e', long = "env")]
+        env: Option<String>,
+        /// Minimum level (error/warn/info/debug/trace)
+        #[arg(short = 'l', long = "level")]
+        level: Option<String>,
+        /// Number of recent entries to show
+        #[arg(short = 'n', long = "lines", default_value_t = 100)]
+        lines: usize,
+        /// Keep streaming new entries as they arrive
+        #[arg(short = 'f', long = "follow")]
+        follow: bool,
+    },
+    /// Inject faults into a dependency to test how kernels cope (non-PROD unless forced)
+    Chaos {
+        #[command(subcommand)]
+        command: ChaosCommands,
+    },
     /// Feature management
     Feature {
         #[command(subcommand)]
@@ -120,6 +143,55 @@ enum FeatureCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum ChaosCommands {
+    /// Start injecting faults into calls to an alias
+    Set {
+        /// Egress or DB alias to misbehave (e.g. inventory)
+        alias: String,
+        /// Micro-service/Tomain name (defaults to current session)
+        #[arg(short = 'm', long = "ms")]
+        ms: Option<String>,
+        /// Environment the rule applies to (defaults to the session's; "global" for all)
+        #[arg(short = 'e', long = "env")]
+        env: Option<String>,
+        /// Latency added to every call, in milliseconds
+        #[arg(long = "latency")]
+        latency_ms: Option<u64>,
+        /// Extra random latency on top, in milliseconds
+        #[arg(long = "jitter")]
+        latency_jitter_ms: Option<u64>,
+        /// Share of calls (0.0-1.0) failed with one of --status
+        #[arg(long = "error-rate")]
+        error_rate: Option<f64>,
+        /// Status codes for injected errors (repeatable, default 503)
+        #[arg(long = "status")]
+        status: Vec<u16>,
+        /// Share of calls (0.0-1.0) failed as a dropped connection
+        #[arg(long = "reset-rate")]
+        reset_rate: Option<f64>,
+        /// Share of calls (0.0-1.0) whose response body is cut short
+        #[arg(long = "truncate-rate")]
+        truncate_rate: Option<f64>,
+        /// Allow the rule to apply in PROD
+        #[arg(long = "force")]
+        force: bool,
+    },
+    /// Stop injecting faults into an alias
+    Clear {
+        alias: String,
+        #[arg(short = 'm', long = "ms")]
+        ms: Option<String>,
+        #[arg(short = 'e', long = "env")]
+        env: Option<String>,
+    },
+    /// Show the fault injection rules for a tomain
+    List {
+        #[arg(short = 'm', long = "ms")]
+        ms: Option<String>,
+    },
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct AxiomSession {
     pub tomain_id: String,
@@ -147,7 +219,42 @@ struct AxiomConfig {
 
 #[derive(Debug, Serialize, Deserialize)]
 struct AxiomManifest {
+    #[serde(default)]
     pub resources: HashMap<String, ResourceDef>,
+    /// [schedules] function = "cron expression"
+    #[serde(default)]
+    pub schedules: HashMap<String, String>,
+    /// [capabilities] host imports the kernel may use; omitted = nothing beyond metrics, config and health
+    pub capabilities: Option<Capabilities>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Capabilities {
+    /// Egress aliases (or "*")
+    #[serde(default)]
+    pub http: Vec<String>,
+    #[serde(default)]
+    pub db: Vec<String>,
+    /// Tomains callable in-process
+    #[serde(default)]
+    pub tomains: Vec<String>,
+    #[serde(default)]
+    pub kv: bool,
+    #[serde(default)]
+    pub secrets: bool,
+    /// [capabilities.events] publish = [...], subscribe = [...]
+    #[serde(default)]
+    pub events: EventCapabilities,
+    #[serde(default)]
+    pub logging: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct EventCapabilities {
+    #[serde(default)]
+    pub publish: Vec<String>,
+    #[serde(default)]
+    pub subscribe: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -274,6 +381,29 @@ async fn main() -> Result<()> {
         Commands::Status => {
             show_status().await?;
         }
+        Commands::Logs { ms, env, level, lines, follow } => {
+            show_logs(ms, env, level, lines, follow).await?;
+        }
+        Commands::Chaos { command } => match command {
+            ChaosCommands::Set { alias, ms, env, latency_ms, latency_jitter_ms, error_rate, status, reset_rate, truncate_rate, force } => {
+                let rule = serde_json::json!({
+                    "latency_ms": latency_ms,
+                    "latency_jitter_ms": latency_jitter_ms,
+                    "error_rate": error_rate,
+                    "error_statuses": if status.is_empty() { None } else { Some(status) },
+                    "reset_rate": reset_rate,
+                    "truncate_rate": truncate_rate,
+                    "force": force,
+                });
+                set_chaos(ms, env, alias, rule).await?;
+            }
+            ChaosCommands::Clear { alias, ms, env } => {
+                clear_chaos(ms, env, alias).await?;
+            }
+            ChaosCommands::List { ms } => {
+                list_chaos(ms).await?;
+            }
+        },
         Commands::Feature { command } => match command {
             FeatureCommands::Start { name } => {
                 start_feature(name).await?;
@@ -663,10 +793,14 @@ async fn deploy_kernel(color: &str) -> Result<()> {
     
     // Check for axiom.toml & interface1.wit
     let mut resources = std::collections::HashMap::new();
+    let mut schedules = std::collections::HashMap::new();
+    let mut capabilities = None;
     if Path::new("axiom.toml").exists() {
         if let Ok(content) = fs::read_to_string("axiom.toml") {
             if let Ok(manifest) = toml::from_str::<AxiomManifest>(&content) {
                 resources = manifest.resources;
+                schedules = manifest.schedules;
+                capabilities = manifest.capabilities;
             }
         }
     }
@@ -868,7 +1002,9 @@ async fn deploy_kernel(color: &str) -> Result<()> {
                 let sync_res = client.post(format!("{}/tomains/{}/manifest", CCP_BASE_URL, session.tomain_id))
                     .json(&serde_json::json!({
                         "resources": resources,
-                        "apis": apis_metadata
+                        "apis": apis_metadata,
+                        "schedules": schedules,
+                        "capabilities": capabilities
                     }))
                     .send()
                     .await;
@@ -1378,6 +1514,161 @@ async fn show_status() -> Result<()> {
     Ok(())
 }
 
+async fn show_logs(ms: Option<String>, env: Option<String>, level: Option<String>, lines: usize, follow: bool) -> Result<()> {
+    let session_res = load_session();
+    let tomain_id = ms.or_else(|| session_res.as_ref().ok().map(|s| s.tomain_id.clone()))
+        .context("No tomain ID provided and no active session found.")?;
+
+    let mut params: Vec<(&str, String)> = Vec::new();
+    if let Some(env) = &env {
+        params.push(("slot", env.to_uppercase()));
+    }
+    if let Some(level) = &level {
+        params.push(("level", level.to_lowercase()));
+    }
+
+    let client = reqwest::Client::new();
+    let mut recent_params = params.clone();
+    recent_params.push(("limit", lines.to_string()));
+    let entries: Vec<serde_json::Value> = client.get(format!("http://localhost:9000/admin/logs/{}", tomain_id))
+        .query(&recent_params)
+        .send()
+        .await
+        .context("Failed to reach Axiom Shell on port 9000")?
+        .json()
+        .await
+        .context("Shell returned an unreadable log payload")?;
+
+    for entry in &entries {
+        print_log_entry(entry);
+    }
+
+    if !follow {
+        return Ok(());
+    }
+
+    println!("{} Following logs for {} (Ctrl+C to stop)...", "📜".cyan(), tomain_id.bold());
+    let mut res = client.get(format!("http://localhost:9000/admin/logs/{}/tail", tomain_id))
+        .query(&params)
+        .send()
+        .await
+        .context("Failed to open log tail on Axiom Shell")?;
+
+    // Server-Sent Events: entries arrive as `data: {json}` lines, possibly split across chunks
+    let mut pending = String::new();
+    while let Some(chunk) = res.chunk().await? {
+        pending.push_str(&String::from_utf8_lossy(&chunk));
+        while let Some(newline) = pending.find('\n') {
+            let line: String = pending.drain(..=newline).collect();
+            if let Some(data) = line.trim_end().strip_prefix("data:")
+                && let Ok(entry) = serde_json::from_str::<serde_json::Value>(data.trim())
+            {
+                print_log_entry(&entry);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_log_entry(entry: &serde_json::Value) {
+    let level = entry["level"].as_str().unwrap_or("info");
+    let level_label = match level {
+        "error" => "ERROR".red().bold(),
+        "warn" => "WARN ".yellow().bold(),
+        "info" => "INFO ".green(),
+        "debug" => "DEBUG".blue(),
+        _ => "TRACE".dimmed(),
+    };
+    let timestamp = entry["timestamp"].as_str().unwrap_or("");
+    let slot = entry["slot"].as_str().unwrap_or("");
+    let request_id = entry["request_id"].as_str().unwrap_or("");
+    let short_request = request_id.get(..8).unwrap_or(request_id);
+
+    let mut line = format!("{} {} [{}] ({}) {}",
+        timestamp.dimmed(), level_label, slot.cyan(), short_request.dimmed(), entry["message"].as_str().unwrap_or(""));
+    if let Some(fields) = entry["fields"].as_object() {
+        for (k, v) in fields {
+            let value = v.as_str().map(|s| s.to_string()).unwrap_or_else(|| v.to_string());
+            line.push_str(&format!(" {}={}", k.bold(), value));
+        }
+    }
+    println!("{}", line);
+}
+
+/// Tomain and upper-cased environment for a chaos rule, falling back to the active session.
+fn chaos_target(ms: Option<String>, env: Option<String>) -> Result<(String, String)> {
+    let session_res = load_session();
+    let tomain_id = ms.or_else(|| session_res.as_ref().ok().map(|s| s.tomain_id.clone()))
+        .context("No tomain ID provided and no active session found.")?;
+    let env = env.or_else(|| session_res.as_ref().ok().map(|s| s.environment.clone()))
+        .unwrap_or_else(|| "DEV".to_string())
+        .to_uppercase();
+    Ok((tomain_id, env))
+}
+
+async fn set_chaos(ms: Option<String>, env: Option<String>, alias: String, rule: serde_json::Value) -> Result<()> {
+    let (tomain_id, env) = chaos_target(ms, env)?;
+    if matches!(env.as_str(), "PROD" | "GREEN" | "RED") {
+        println!("{} Injecting faults into PROD traffic for {}.", "⚠️".yellow(), tomain_id.bold());
+    }
+
+    let res = reqwest::Client::new()
+        .put(format!("{}/tomains/{}/chaos/{}/{}", CCP_BASE_URL, tomain_id, env, alias))
+        .json(&rule)
+        .send()
+        .await
+        .context("Failed to reach CCP")?;
+    if !res.status().is_success() {
+        let msg = res.text().await.unwrap_or_default();
+        return Err(anyhow::anyhow!("{} Chaos rule rejected: {}", "❌".red(), msg));
+    }
+
+    println!("{} Injecting faults into '{}' for {} ({}).", "🐒".cyan(), alias.bold(), tomain_id.bold(), env.yellow());
+    Ok(())
+}
+
+async fn clear_chaos(ms: Option<String>, env: Option<String>, alias: String) -> Result<()> {
+    let (tomain_id, env) = chaos_target(ms, env)?;
+    let res = reqwest::Client::new()
+        .delete(format!("{}/tomains/{}/chaos/{}/{}", CCP_BASE_URL, tomain_id, env, alias))
+        .send()
+        .await
+        .context("Failed to reach CCP")?;
+    if !res.status().is_success() {
+        let msg = res.text().await.unwrap_or_default();
+        return Err(anyhow::anyhow!("{} Could not clear chaos rule: {}", "❌".red(), msg));
+    }
+
+    println!("{} Fault injection for '{}' cleared ({}).", "✅".green(), alias.bold(), env.yellow());
+    Ok(())
+}
+
+async fn list_chaos(ms: Option<String>) -> Result<()> {
+    let (tomain_id, _) = chaos_target(ms, None)?;
+    let rules: HashMap<String, HashMap<String, serde_json::Value>> = reqwest::get(format!("{}/tomains/{}/chaos", CCP_BASE_URL, tomain_id))
+        .await
+        .context("Failed to reach CCP")?
+        .json()
+        .await
+        .context("CCP returned an unreadable chaos payload")?;
+
+    if rules.is_empty() {
+        println!("{} No fault injection rules for {}.", "ℹ️".blue(), tomain_id.bold());
+        return Ok(());
+    }
+    println!("\n{}", format!("─── Chaos rules for {} ───", tomain_id).bold().cyan());
+    for (env, aliases) in &rules {
+        for (alias, rule) in aliases {
+            let forced = rule["force"].as_bool().unwrap_or(false);
+            let inactive = rule["enabled"].as_bool() == Some(false) || (matches!(env.as_str(), "PROD" | "GREEN" | "RED") && !forced);
+            let state = if inactive { "inactive".dimmed() } else { "active".green() };
+            println!("  {:<10} {:<20} [{}] {}", env.yellow(), alias.bold(), state, rule);
+        }
+    }
+    println!();
+    Ok(())
+}
+
 async fn start_feature(name: String) -> Result<()> {
     let session = load_session()?;
     println!("{} Starting feature: {}...", "🌿".green(), name.bold());
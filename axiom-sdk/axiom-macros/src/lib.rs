@@ -3,8 +3,24 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, ItemFn, Attribute, Lit, Meta, ReturnType, FnArg, Pat};
 
+/// Exposes a kernel function through the Shell.
+///
+/// Accepts an optional cron schedule (`sec min hour day-of-month month day-of-week`), e.g.
+/// `#[axiom_api(schedule = "0 */5 * * * *")]`, which the Shell's scheduler picks up on deploy.
 #[proc_macro_attribute]
-pub fn axiom_api(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn axiom_api(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut schedule: Option<String> = None;
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("schedule") {
+            let expr: syn::LitStr = meta.value()?.parse()?;
+            schedule = Some(expr.value());
+            Ok(())
+        } else {
+            Err(meta.error("unsupported axiom_api attribute, expected `schedule = \"...\"`"))
+        }
+    });
+    parse_macro_input!(attr with attr_parser);
+
     let input = parse_macro_input!(item as ItemFn);
     let fn_name = &input.sig.ident;
     let vis = &input.vis;
@@ -59,6 +75,10 @@ pub fn axiom_api(_attr: TokenStream, item: TokenStream) -> TokenStream {
     let invoke_fn_name = quote::format_ident!("__axiom_call_{}", fn_name);
     let metadata_fn_name = quote::format_ident!("__axiom_metadata_{}", fn_name);
     let params_tokens = params_metadata.iter().map(|p| quote! { #p });
+    let schedule_tokens = match &schedule {
+        Some(expr) => quote! { Some(#expr) },
+        None => quote! { None::<&str> },
+    };
 
     let expanded = quote! {
         #input
@@ -89,7 +109,51 @@ pub fn axiom_api(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 "name": stringify!(#fn_name),
                 "summary": #summary,
                 "parameters": [#(#params_tokens),*],
-                "invoke": stringify!(#invoke_fn_name)
+                "invoke": stringify!(#invoke_fn_name),
+                "schedule": #schedule_tokens
+            }).to_string();
+            let json_with_null = format!("{}\0", json);
+            let s = Box::leak(json_with_null.into_boxed_str());
+            s.as_ptr()
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Subscribes a kernel function to an event topic, e.g. `#[axiom_subscribe("orders.created")]`.
+///
+/// The function takes the payload (`&str` or `String`) and returns `()` or a `Result`; an `Err`
+/// asks the Shell to redeliver. Topics may use `*` for one token and `>` for the remainder.
+#[proc_macro_attribute]
+pub fn axiom_subscribe(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let topic = parse_macro_input!(attr as syn::LitStr);
+    let input = parse_macro_input!(item as ItemFn);
+    let fn_name = &input.sig.ident;
+
+    let event_fn_name = quote::format_ident!("__axiom_event_{}", fn_name);
+    let subscription_fn_name = quote::format_ident!("__axiom_subscription_{}", fn_name);
+
+    let expanded = quote! {
+        #input
+
+        #[unsafe(no_mangle)]
+        pub extern "C" fn #event_fn_name(payload_ptr: u32, payload_len: u32) -> u32 {
+            let payload = if payload_ptr > 0 && payload_len > 0 {
+                let slice = unsafe { core::slice::from_raw_parts(payload_ptr as *const u8, payload_len as usize) };
+                core::str::from_utf8(slice).unwrap_or("")
+            } else {
+                ""
+            };
+            let res = #fn_name(payload.into());
+            axiom_sdk::events::Ack::ack_code(&res)
+        }
+
+        #[unsafe(no_mangle)]
+        pub extern "C" fn #subscription_fn_name() -> *const u8 {
+            let json = serde_json::json!({
+                "topic": #topic,
+                "handler": stringify!(#event_fn_name)
             }).to_string();
             let json_with_null = format!("{}\0", json);
             let s = Box::leak(json_with_null.into_boxed_str());
@@ -100,6 +164,29 @@ pub fn axiom_api(_attr: TokenStream, item: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+/// Marks the kernel's health check, which the Shell runs before a new deploy takes traffic.
+///
+/// The function takes no arguments and returns `()`, `bool` or a `Result`; `false` or an `Err`
+/// fails the deploy and the slot keeps serving its previous kernel.
+#[proc_macro_attribute]
+pub fn axiom_health(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as ItemFn);
+    let fn_name = &input.sig.ident;
+
+    let expanded = quote! {
+        #input
+
+        #[unsafe(no_mangle)]
+        pub extern "C" fn __axiom_health() -> *const u8 {
+            let status = axiom_sdk::health::Report::status(&#fn_name());
+            let s = Box::leak(format!("{}\0", status).into_boxed_str());
+            s.as_ptr()
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
 #[proc_macro]
 pub fn axiom_export_reflect(input: TokenStream) -> TokenStream {
     let idents = syn::parse_macro_input!(input with syn::punctuated::Punctuated::<syn::Ident, syn::Token![,]>::parse_terminated);
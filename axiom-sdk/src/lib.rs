@@ -1,19 +1,250 @@
 pub use axiom_macros::axiom_api;
 pub use axiom_macros::axiom_export_reflect;
+pub use axiom_macros::axiom_health;
+pub use axiom_macros::axiom_subscribe;
 
+/// Outbound HTTP through the Shell's Egress Guard. Aliases resolve to the URL bound for this
+/// environment; `Request` adds a path, query, headers, method and timeout on top of it.
+///
+/// `get`, `post`, `put` and `delete` keep returning the body text (or an `Error: ...` string);
+/// `request` and the `*_response` helpers return a `Response` or a typed `Error`.
 pub mod http {
+    use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+    use serde::de::DeserializeOwned;
+    use std::time::Duration;
+
     #[link(wasm_import_module = "axiom")]
     unsafe extern "C" {
-        /// Calls the Host's HTTP proxy. 
+        /// Calls the Host's HTTP proxy.
         /// Returns a pointer to the null-terminated response string in Wasm memory.
         fn http_call(
-            alias_ptr: *const u8, 
-            method_ptr: *const u8, 
-            body_ptr: *const u8, 
+            alias_ptr: *const u8,
+            method_ptr: *const u8,
+            body_ptr: *const u8,
+            body_len: u32
+        ) -> u32;
+
+        /// Sends a request described by JSON (`alias`, `method`, `path`, `query`, `headers`,
+        /// `timeout_ms`) with a raw body. Returns a pointer to a null-terminated JSON reply:
+        /// `{"ok":true,"status":..,"headers":[[k,v]..],"body":"<base64>"}` or
+        /// `{"ok":false,"error":{"kind":"..","message":".."}}`.
+        fn http_request(
+            req_ptr: *const u8,
+            req_len: u32,
+            body_ptr: *const u8,
             body_len: u32
         ) -> u32;
     }
 
+    /// An outbound request. `Authorization` is set by the Shell from the vault and can't be overridden.
+    #[derive(Debug, Clone)]
+    pub struct Request {
+        alias: String,
+        method: String,
+        path: Option<String>,
+        query: Vec<(String, String)>,
+        headers: Vec<(String, String)>,
+        timeout: Option<Duration>,
+        body: Vec<u8>,
+    }
+
+    impl Request {
+        pub fn new(method: &str, alias: &str) -> Self {
+            Self {
+                alias: alias.to_string(),
+                method: method.to_uppercase(),
+                path: None,
+                query: Vec::new(),
+                headers: Vec::new(),
+                timeout: None,
+                body: Vec::new(),
+            }
+        }
+
+        pub fn get(alias: &str) -> Self { Self::new("GET", alias) }
+        pub fn post(alias: &str) -> Self { Self::new("POST", alias) }
+        pub fn put(alias: &str) -> Self { Self::new("PUT", alias) }
+        pub fn patch(alias: &str) -> Self { Self::new("PATCH", alias) }
+        pub fn delete(alias: &str) -> Self { Self::new("DELETE", alias) }
+        pub fn head(alias: &str) -> Self { Self::new("HEAD", alias) }
+
+        /// Appended to the bound base URL, e.g. `.path("/orders/42")`.
+        pub fn path(mut self, path: &str) -> Self {
+            self.path = Some(path.to_string());
+            self
+        }
+
+        pub fn query(mut self, key: &str, value: &str) -> Self {
+            self.query.push((key.to_string(), value.to_string()));
+            self
+        }
+
+        pub fn header(mut self, name: &str, value: &str) -> Self {
+            self.headers.push((name.to_string(), value.to_string()));
+            self
+        }
+
+        /// Overall timeout across retries; it can only shorten the binding's retry deadline.
+        pub fn timeout(mut self, timeout: Duration) -> Self {
+            self.timeout = Some(timeout);
+            self
+        }
+
+        /// Raw body in any content type; set `Content-Type` with `.header()`.
+        pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+            self.body = body.into();
+            self
+        }
+
+        /// Serializes `value` as the body and sets `Content-Type: application/json`.
+        pub fn json<T: serde::Serialize>(self, value: &T) -> Self {
+            let body = serde_json::to_vec(value).unwrap_or_default();
+            self.header("Content-Type", "application/json").body(body)
+        }
+
+        /// Sends the request. Any status the downstream answers with is `Ok`; see `Response::error_for_status`.
+        pub fn send(self) -> Result<Response, Error> {
+            let req = serde_json::json!({
+                "alias": self.alias,
+                "method": self.method,
+                "path": self.path,
+                "query": self.query,
+                "headers": self.headers,
+                "timeout_ms": self.timeout.map(|t| t.as_millis() as u64),
+            }).to_string();
+
+            let ptr = unsafe {
+                http_request(
+                    req.as_ptr(),
+                    req.len() as u32,
+                    self.body.as_ptr(),
+                    self.body.len() as u32
+                )
+            };
+
+            if ptr == 0 {
+                return Err(Error::Transport("HTTP call failed".to_string()));
+            }
+
+            let c_str = unsafe { std::ffi::CStr::from_ptr(ptr as *const i8) };
+            let reply: serde_json::Value = serde_json::from_str(&c_str.to_string_lossy())
+                .map_err(|e| Error::Transport(format!("Invalid reply from host: {}", e)))?;
+
+            if reply["ok"].as_bool() != Some(true) {
+                let message = reply["error"]["message"].as_str().unwrap_or_default().to_string();
+                return Err(Error::from_kind(reply["error"]["kind"].as_str().unwrap_or_default(), message));
+            }
+            let headers = serde_json::from_value(reply["headers"].clone()).unwrap_or_default();
+            let body = BASE64.decode(reply["body"].as_str().unwrap_or_default())
+                .map_err(|e| Error::Transport(format!("Invalid body from host: {}", e)))?;
+            Ok(Response {
+                status: reply["status"].as_u64().unwrap_or_default() as u16,
+                headers,
+                body,
+            })
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct Response {
+        pub status: u16,
+        pub headers: Vec<(String, String)>,
+        pub body: Vec<u8>,
+    }
+
+    impl Response {
+        pub fn is_success(&self) -> bool {
+            (200..300).contains(&self.status)
+        }
+
+        /// First header with this name, case-insensitively.
+        pub fn header(&self, name: &str) -> Option<&str> {
+            self.headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+        }
+
+        pub fn text(&self) -> String {
+            String::from_utf8_lossy(&self.body).into_owned()
+        }
+
+        pub fn json<T: DeserializeOwned>(&self) -> Result<T, Error> {
+            serde_json::from_slice(&self.body).map_err(|e| Error::Decode(e.to_string()))
+        }
+
+        /// Turns a non-2xx response into `Error::Status`.
+        pub fn error_for_status(self) -> Result<Self, Error> {
+            if self.is_success() {
+                Ok(self)
+            } else {
+                Err(Error::Status(self.status, self.text()))
+            }
+        }
+    }
+
+    /// Why a call produced no usable response. All but `Status` and `Decode` come from the Shell;
+    /// kinds a newer Shell reports that this SDK doesn't know arrive as `Transport`.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[non_exhaustive]
+    pub enum Error {
+        /// The alias has no binding in this environment
+        NoBinding(String),
+        /// Refused by capabilities or egress policy
+        PolicyDenied(String),
+        RateLimited(String),
+        CircuitOpen(String),
+        /// Too many calls to this alias in flight. Sent by Shells with egress bulkheads; older
+        /// ones never shed egress calls.
+        Overloaded(String),
+        Timeout(String),
+        /// Connection-level failure (DNS, connect, TLS, reset)
+        Transport(String),
+        /// The Shell couldn't obtain credentials for the binding (e.g. its OAuth2 token). Only
+        /// Shells with OAuth2 client-credential bindings send it.
+        Auth(String),
+        /// Every retry failed without a response; a retryable status on the last attempt comes
+        /// back as the `Response`
+        RetriesExhausted(String),
+        /// Non-2xx status and body; raised by the SDK in `Response::error_for_status`
+        Status(u16, String),
+        /// The body wasn't the JSON the caller expected; raised by the SDK in `Response::json`
+        Decode(String),
+    }
+
+    impl Error {
+        fn from_kind(kind: &str, message: String) -> Self {
+            match kind {
+                "NoBinding" => Error::NoBinding(message),
+                "PolicyDenied" => Error::PolicyDenied(message),
+                "RateLimited" => Error::RateLimited(message),
+                "CircuitOpen" => Error::CircuitOpen(message),
+                "Overloaded" => Error::Overloaded(message),
+                "Timeout" => Error::Timeout(message),
+                "Auth" => Error::Auth(message),
+                "RetriesExhausted" => Error::RetriesExhausted(message),
+                _ => Error::Transport(message),
+            }
+        }
+    }
+
+    impl std::fmt::Display for Error {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Error::NoBinding(m) => write!(f, "NoBinding: {}", m),
+                Error::PolicyDenied(m) => write!(f, "PolicyDenied: {}", m),
+                Error::RateLimited(m) => write!(f, "RateLimited: {}", m),
+                Error::CircuitOpen(m) => write!(f, "CircuitOpen: {}", m),
+                Error::Overloaded(m) => write!(f, "Overloaded: {}", m),
+                Error::Timeout(m) => write!(f, "Timeout: {}", m),
+                Error::Transport(m) => write!(f, "Transport: {}", m),
+                Error::Auth(m) => write!(f, "Auth: {}", m),
+                Error::RetriesExhausted(m) => write!(f, "RetriesExhausted: {}", m),
+                Error::Status(status, body) => write!(f, "Status {}: {}", status, body),
+                Error::Decode(m) => write!(f, "Decode: {}", m),
+            }
+        }
+    }
+
+    impl std::error::Error for Error {}
+
     pub fn get(alias: &str) -> String {
         call(alias, "GET", None)
     }
@@ -33,21 +264,21 @@ pub mod http {
     fn call(alias: &str, method: &str, body: Option<&str>) -> String {
         let alias_null = format!("{}\0", alias);
         let method_null = format!("{}\0", method);
-        
+
         let (b_ptr, b_len) = match body {
             Some(b) => (b.as_ptr(), b.len() as u32),
             None => (std::ptr::null(), 0),
         };
 
-        let ptr = unsafe { 
+        let ptr = unsafe {
             http_call(
-                alias_null.as_ptr(), 
+                alias_null.as_ptr(),
                 method_null.as_ptr(),
                 b_ptr,
                 b_len
-            ) 
+            )
         };
-        
+
         if ptr == 0 {
             return "Error: HTTP call failed".to_string();
         }
@@ -55,11 +286,44 @@ pub mod http {
         let c_str = unsafe { std::ffi::CStr::from_ptr(ptr as *const i8) };
         c_str.to_string_lossy().into_owned()
     }
+
+    /// Starts a structured request, e.g. `http::request("PATCH", "orders").path("/42").send()`.
+    pub fn request(method: &str, alias: &str) -> Request {
+        Request::new(method, alias)
+    }
+
+    pub fn get_response(alias: &str) -> Result<Response, Error> {
+        Request::get(alias).send()
+    }
+
+    pub fn post_response(alias: &str, body: &str) -> Result<Response, Error> {
+        Request::post(alias).body(body).send()
+    }
+
+    pub fn put_response(alias: &str, body: &str) -> Result<Response, Error> {
+        Request::put(alias).body(body).send()
+    }
+
+    pub fn delete_response(alias: &str) -> Result<Response, Error> {
+        Request::delete(alias).send()
+    }
+
+    /// GETs `alias` and decodes a 2xx JSON body.
+    pub fn get_json<T: DeserializeOwned>(alias: &str) -> Result<T, Error> {
+        get_response(alias)?.error_for_status()?.json()
+    }
+
+    /// POSTs `value` as JSON and decodes a 2xx JSON body.
+    pub fn post_json<B: serde::Serialize, T: DeserializeOwned>(alias: &str, value: &B) -> Result<T, Error> {
+        Request::post(alias).json(value).send()?.error_for_status()?.json()
+    }
 }
 
 #[link(wasm_import_module = "axiom")]
 unsafe extern "C" {
     pub fn axiom_log(ptr: *const u8, len: usize, level: u32);
+    /// Same as `axiom_log`, plus a JSON object of structured key/value fields.
+    pub fn axiom_log_kv(ptr: *const u8, len: usize, level: u32, fields_ptr: *const u8, fields_len: usize);
 }
 
 #[doc(hidden)]
@@ -69,31 +333,84 @@ pub fn __axiom_log_internal(msg: &str, level: u32) {
     }
 }
 
+#[doc(hidden)]
+pub fn __axiom_log_fields(msg: &str, level: u32, fields: &[(&str, serde_json::Value)]) {
+    if fields.is_empty() {
+        return __axiom_log_internal(msg, level);
+    }
+    let map: serde_json::Map<String, serde_json::Value> = fields.iter()
+        .map(|(k, v)| (k.to_string(), v.clone()))
+        .collect();
+    let fields_json = serde_json::Value::Object(map).to_string();
+    unsafe {
+        axiom_log_kv(msg.as_ptr(), msg.len(), level, fields_json.as_ptr(), fields_json.len());
+    }
+}
+
+#[doc(hidden)]
+pub fn __axiom_field_display<T: std::fmt::Display + ?Sized>(value: &T) -> serde_json::Value {
+    serde_json::Value::String(value.to_string())
+}
+
+#[doc(hidden)]
+pub fn __axiom_field_debug<T: std::fmt::Debug + ?Sized>(value: &T) -> serde_json::Value {
+    serde_json::Value::String(format!("{:?}", value))
+}
+
+#[doc(hidden)]
+pub fn __axiom_field_value<T: serde::Serialize + ?Sized>(value: &T) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or(serde_json::Value::Null)
+}
+
+/// Shared expansion for the logging macros. Leading `key = value` pairs become structured
+/// fields (`%` formats with Display, `?` with Debug, bare values must be `Serialize`);
+/// everything after them is a regular `format!` message.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __axiom_log {
+    (@fields $level:expr, [$($fields:tt)*] $key:ident = %$value:expr, $($rest:tt)+) => {
+        $crate::__axiom_log!(@fields $level, [$($fields)* (stringify!($key), $crate::__axiom_field_display(&$value)),] $($rest)+)
+    };
+    (@fields $level:expr, [$($fields:tt)*] $key:ident = ?$value:expr, $($rest:tt)+) => {
+        $crate::__axiom_log!(@fields $level, [$($fields)* (stringify!($key), $crate::__axiom_field_debug(&$value)),] $($rest)+)
+    };
+    (@fields $level:expr, [$($fields:tt)*] $key:ident = $value:expr, $($rest:tt)+) => {
+        $crate::__axiom_log!(@fields $level, [$($fields)* (stringify!($key), $crate::__axiom_field_value(&$value)),] $($rest)+)
+    };
+    (@fields $level:expr, [$($fields:tt)*] $($arg:tt)+) => {
+        $crate::__axiom_log_fields(&format!($($arg)+), $level, &[$($fields)*])
+    };
+}
+
+/// Logs at info level: `info!(user_id = %id, "fetched profile")`.
 #[macro_export]
 macro_rules! info {
     ($($arg:tt)*) => {
-        $crate::__axiom_log_internal(&format!($($arg)*), 2);
+        $crate::__axiom_log!(@fields 2, [] $($arg)*);
     };
 }
 
+/// Logs at warn level, with optional leading `key = value` fields.
 #[macro_export]
 macro_rules! warn {
     ($($arg:tt)*) => {
-        $crate::__axiom_log_internal(&format!($($arg)*), 1);
+        $crate::__axiom_log!(@fields 1, [] $($arg)*);
     };
 }
 
+/// Logs at error level, with optional leading `key = value` fields.
 #[macro_export]
 macro_rules! error {
     ($($arg:tt)*) => {
-        $crate::__axiom_log_internal(&format!($($arg)*), 0);
+        $crate::__axiom_log!(@fields 0, [] $($arg)*);
     };
 }
 
+/// Logs at debug level, with optional leading `key = value` fields.
 #[macro_export]
 macro_rules! debug {
     ($($arg:tt)*) => {
-        $crate::__axiom_log_internal(&format!($($arg)*), 3);
+        $crate::__axiom_log!(@fields 3, [] $($arg)*);
     };
 }
 
@@ -187,7 +504,335 @@ pub mod db {
 
             let c_str = unsafe { std::ffi::CStr::from_ptr(ptr as *const i8) };
             let res_json = c_str.to_string_lossy();
-            serde_json::from_str(&res_json).map_err(|e| e.to_string())
+            let value: serde_json::Value = serde_json::from_str(&res_json).map_err(|e| e.to_string())?;
+            // Denials come back as `{"ok":false,"error":{"kind":..,"message":..}}`, like the `http_request` import
+            if value.get("ok") == Some(&serde_json::Value::Bool(false)) {
+                let kind = value["error"]["kind"].as_str().unwrap_or("Transport");
+                let message = value["error"]["message"].as_str().unwrap_or_default();
+                return Err(format!("{}: {}", kind, message));
+            }
+            serde_json::from_value(value).map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Business metrics recorded by the kernel and exported on the Shell's `/metrics` endpoint
+/// as `axiom_kernel_<name>{tomain, slot, ...labels}`.
+///
+/// Label sets are static so a kernel can't explode series cardinality at runtime:
+///
+/// ```ignore
+/// const ORDERS: metrics::Counter = metrics::counter("orders_total", &[("channel", "web")]);
+/// ORDERS.inc();
+/// ```
+pub mod metrics {
+    #[link(wasm_import_module = "axiom")]
+    unsafe extern "C" {
+        /// Records one sample. `kind` is 0 (counter), 1 (gauge) or 2 (histogram);
+        /// labels are a JSON array of `[key, value]` pairs.
+        fn axiom_metric(
+            kind: u32,
+            name_ptr: *const u8,
+            name_len: u32,
+            labels_ptr: *const u8,
+            labels_len: u32,
+            value: f64
+        );
+    }
+
+    pub type Labels = &'static [(&'static str, &'static str)];
+
+    pub struct Counter {
+        name: &'static str,
+        labels: Labels,
+    }
+
+    pub struct Gauge {
+        name: &'static str,
+        labels: Labels,
+    }
+
+    pub struct Histogram {
+        name: &'static str,
+        labels: Labels,
+    }
+
+    pub const fn counter(name: &'static str, labels: Labels) -> Counter {
+        Counter { name, labels }
+    }
+
+    pub const fn gauge(name: &'static str, labels: Labels) -> Gauge {
+        Gauge { name, labels }
+    }
+
+    pub const fn histogram(name: &'static str, labels: Labels) -> Histogram {
+        Histogram { name, labels }
+    }
+
+    impl Counter {
+        pub fn inc(&self) {
+            self.inc_by(1.0);
+        }
+
+        /// Negative increments are ignored by the Shell.
+        pub fn inc_by(&self, value: f64) {
+            record(0, self.name, self.labels, value);
+        }
+    }
+
+    impl Gauge {
+        pub fn set(&self, value: f64) {
+            record(1, self.name, self.labels, value);
+        }
+    }
+
+    impl Histogram {
+        pub fn observe(&self, value: f64) {
+            record(2, self.name, self.labels, value);
+        }
+    }
+
+    fn record(kind: u32, name: &str, labels: Labels, value: f64) {
+        let labels_json = if labels.is_empty() {
+            String::new()
+        } else {
+            serde_json::to_string(labels).unwrap_or_default()
+        };
+        unsafe {
+            axiom_metric(
+                kind,
+                name.as_ptr(),
+                name.len() as u32,
+                labels_json.as_ptr(),
+                labels_json.len() as u32,
+                value
+            );
+        }
+    }
+}
+
+pub mod events {
+    #[link(wasm_import_module = "axiom")]
+    unsafe extern "C" {
+        /// Publishes a payload on a topic. Returns 0 when accepted, 1 when the tomain's
+        /// manifest does not grant the topic, 2 when the bus could not take the event.
+        fn event_publish(
+            topic_ptr: *const u8,
+            topic_len: u32,
+            payload_ptr: *const u8,
+            payload_len: u32
+        ) -> u32;
+    }
+
+    /// Publishes `payload` (usually JSON) to every kernel subscribed to `topic` in this slot.
+    /// Delivery is asynchronous and at-least-once, so handlers should be idempotent.
+    pub fn publish(topic: &str, payload: &str) -> Result<(), String> {
+        let code = unsafe {
+            event_publish(topic.as_ptr(), topic.len() as u32, payload.as_ptr(), payload.len() as u32)
+        };
+        match code {
+            0 => Ok(()),
+            1 => Err(format!("Topic '{}' is not granted to this tomain", topic)),
+            _ => Err(format!("Event bus rejected publish to '{}'", topic)),
+        }
+    }
+    /// How a `#[axiom_subscribe]` handler's return value maps to an ack (0) or a nack.
+    /// `()` always acks; `Err` nacks so the Shell retries the delivery.
+    #[doc(hidden)]
+    pub trait Ack {
+        fn ack_code(&self) -> u32;
+    }
+
+    impl Ack for () {
+        fn ack_code(&self) -> u32 {
+            0
+        }
+    }
+
+    impl<T, E> Ack for Result<T, E> {
+        fn ack_code(&self) -> u32 {
+            if self.is_ok() { 0 } else { 1 }
+        }
+    }
+}
+
+/// Key-value state that outlives a single invocation. Keys are private to the tomain and
+/// the slot it runs in, so QA writes never show up in PROD. Values are plain strings; store
+/// JSON if you need structure.
+pub mod kv {
+    use std::time::Duration;
+
+    #[link(wasm_import_module = "axiom")]
+    unsafe extern "C" {
+        /// Each call returns a pointer to a null-terminated JSON reply:
+        /// `{"ok":true,"value":...}` or `{"ok":false,"error":"..."}`. A `ttl_ms` of 0 means no expiry.
+        fn kv_get(key_ptr: *const u8, key_len: u32) -> u32;
+        fn kv_set(key_ptr: *const u8, key_len: u32, value_ptr: *const u8, value_len: u32, ttl_ms: u64) -> u32;
+        fn kv_delete(key_ptr: *const u8, key_len: u32) -> u32;
+        fn kv_cas(
+            key_ptr: *const u8,
+            key_len: u32,
+            expected_ptr: *const u8,
+            expected_len: u32,
+            has_expected: u32,
+            new_ptr: *const u8,
+            new_len: u32,
+            ttl_ms: u64
+        ) -> u32;
+        fn kv_scan(prefix_ptr: *const u8, prefix_len: u32, limit: u32) -> u32;
+    }
+
+    pub fn get(key: &str) -> Result<Option<String>, String> {
+        let ptr = unsafe { kv_get(key.as_ptr(), key.len() as u32) };
+        reply(ptr)
+    }
+
+    pub fn set(key: &str, value: &str, ttl: Option<Duration>) -> Result<(), String> {
+        let ptr = unsafe { kv_set(key.as_ptr(), key.len() as u32, value.as_ptr(), value.len() as u32, ttl_ms(ttl)) };
+        reply::<Option<()>>(ptr).map(|_| ())
+    }
+
+    /// Returns whether the key existed.
+    pub fn delete(key: &str) -> Result<bool, String> {
+        let ptr = unsafe { kv_delete(key.as_ptr(), key.len() as u32) };
+        reply(ptr)
+    }
+
+    /// Sets `key` to `new` only if it currently holds `expected` (`None`: only if absent).
+    /// Returns false when another writer got there first.
+    pub fn compare_and_swap(key: &str, expected: Option<&str>, new: &str, ttl: Option<Duration>) -> Result<bool, String> {
+        let (e_ptr, e_len) = match expected {
+            Some(e) => (e.as_ptr(), e.len() as u32),
+            None => (std::ptr::null(), 0),
+        };
+        let ptr = unsafe {
+            kv_cas(
+                key.as_ptr(),
+                key.len() as u32,
+                e_ptr,
+                e_len,
+                expected.is_some() as u32,
+                new.as_ptr(),
+                new.len() as u32,
+                ttl_ms(ttl)
+            )
+        };
+        reply(ptr)
+    }
+
+    /// Key/value pairs whose key starts with `prefix`, sorted by key. A `limit` of 0 uses the Shell's maximum.
+    pub fn scan(prefix: &str, limit: u32) -> Result<Vec<(String, String)>, String> {
+        let ptr = unsafe { kv_scan(prefix.as_ptr(), prefix.len() as u32, limit) };
+        reply(ptr)
+    }
+
+    fn ttl_ms(ttl: Option<Duration>) -> u64 {
+        ttl.map(|t| (t.as_millis() as u64).max(1)).unwrap_or(0)
+    }
+
+    fn reply<T: serde::de::DeserializeOwned>(ptr: u32) -> Result<T, String> {
+        if ptr == 0 {
+            return Err("KV call failed".to_string());
+        }
+        let c_str = unsafe { std::ffi::CStr::from_ptr(ptr as *const i8) };
+        let json: serde_json::Value = serde_json::from_str(&c_str.to_string_lossy()).map_err(|e| e.to_string())?;
+        if json["ok"].as_bool() == Some(true) {
+            serde_json::from_value(json["value"].clone()).map_err(|e| e.to_string())
+        } else {
+            Err(json["error"].as_str().unwrap_or("KV call failed").to_string())
+        }
+    }
+}
+
+/// Direct calls to other kernels loaded in the same Shell. No HTTP hop: the callee runs
+/// in-process under this request's id and trace, and must be promoted to this environment.
+pub mod tomain {
+    #[link(wasm_import_module = "axiom")]
+    unsafe extern "C" {
+        /// Returns a pointer to a null-terminated JSON reply:
+        /// `{"ok":true,"value":"<callee result>"}` or `{"ok":false,"error":"..."}`.
+        fn tomain_call(
+            target_ptr: *const u8,
+            target_len: u32,
+            func_ptr: *const u8,
+            func_len: u32,
+            args_ptr: *const u8,
+            args_len: u32
+        ) -> u32;
+    }
+
+    /// Calls `func` on the `target` tomain with JSON `args`, e.g. `tomain::call("billing", "charge", r#"{"amount":5}"#)`.
+    pub fn call(target: &str, func: &str, args: &str) -> Result<String, String> {
+        let ptr = unsafe {
+            tomain_call(
+                target.as_ptr(),
+                target.len() as u32,
+                func.as_ptr(),
+                func.len() as u32,
+                args.as_ptr(),
+                args.len() as u32
+            )
+        };
+        if ptr == 0 {
+            return Err(format!("Call to {}/{} failed", target, func));
+        }
+        let c_str = unsafe { std::ffi::CStr::from_ptr(ptr as *const i8) };
+        let json: serde_json::Value = serde_json::from_str(&c_str.to_string_lossy()).map_err(|e| e.to_string())?;
+        match json["value"].as_str() {
+            Some(value) if json["ok"].as_bool() == Some(true) => Ok(value.to_string()),
+            _ => Err(json["error"].as_str().unwrap_or("Call failed").to_string()),
+        }
+    }
+}
+
+/// Per-environment configuration set in the CCP. Values change without a redeploy and are
+/// also exposed to the kernel as WASI environment variables.
+pub mod config {
+    use serde::de::DeserializeOwned;
+
+    #[link(wasm_import_module = "axiom")]
+    unsafe extern "C" {
+        /// Returns a pointer to a null-terminated JSON reply: `{"ok":true,"value":...,"version":N}`.
+        /// `value` is null when the key isn't set.
+        fn config_get(key_ptr: *const u8, key_len: u32) -> u32;
+    }
+
+    /// Reads and deserializes a config value, e.g. `config::get::<u32>("PAGE_SIZE")`.
+    pub fn get<T: DeserializeOwned>(key: &str) -> Result<T, String> {
+        let ptr = unsafe { config_get(key.as_ptr(), key.len() as u32) };
+        if ptr == 0 {
+            return Err("Config lookup failed".to_string());
+        }
+        let c_str = unsafe { std::ffi::CStr::from_ptr(ptr as *const i8) };
+        let json: serde_json::Value = serde_json::from_str(&c_str.to_string_lossy()).map_err(|e| e.to_string())?;
+        match &json["value"] {
+            serde_json::Value::Null => Err(format!("Config key '{}' is not set", key)),
+            value => serde_json::from_value(value.clone()).map_err(|e| format!("Config key '{}': {}", key, e)),
+        }
+    }
+}
+
+/// Secrets from the CCP secrets store. A key is only readable if it is granted to this
+/// tomain in the environment the kernel is running in; every read is audited by the Shell.
+pub mod secrets {
+    #[link(wasm_import_module = "axiom")]
+    unsafe extern "C" {
+        /// Returns a pointer to a null-terminated JSON reply:
+        /// `{"ok":true,"value":"..."}` or `{"ok":false,"error":"..."}`.
+        fn secret_get(key_ptr: *const u8, key_len: u32) -> u32;
+    }
+
+    /// Reads a granted secret, e.g. `secrets::get("STRIPE_KEY")`. Don't log the result.
+    pub fn get(key: &str) -> Result<String, String> {
+        let ptr = unsafe { secret_get(key.as_ptr(), key.len() as u32) };
+        if ptr == 0 {
+            return Err("Secret lookup failed".to_string());
+        }
+        let c_str = unsafe { std::ffi::CStr::from_ptr(ptr as *const i8) };
+        let json: serde_json::Value = serde_json::from_str(&c_str.to_string_lossy()).map_err(|e| e.to_string())?;
+        match json["value"].as_str() {
+            Some(value) if json["ok"].as_bool() == Some(true) => Ok(value.to_string()),
+            _ => Err(json["error"].as_str().unwrap_or("Secret lookup failed").to_string()),
         }
     }
 }
@@ -211,4 +856,31 @@ pub mod health {
         let c_str = unsafe { std::ffi::CStr::from_ptr(ptr as *const i8) };
         c_str.to_string_lossy().into_owned()
     }
+
+    /// What an `#[axiom_health]` check's return value reports: "Healthy" or why it isn't.
+    #[doc(hidden)]
+    pub trait Report {
+        fn status(&self) -> String;
+    }
+
+    impl Report for () {
+        fn status(&self) -> String {
+            "Healthy".to_string()
+        }
+    }
+
+    impl Report for bool {
+        fn status(&self) -> String {
+            if *self { "Healthy".to_string() } else { "Unhealthy".to_string() }
+        }
+    }
+
+    impl<T, E: std::fmt::Display> Report for Result<T, E> {
+        fn status(&self) -> String {
+            match self {
+                Ok(_) => "Healthy".to_string(),
+                Err(e) => format!("Unhealthy: {}", e),
+            }
+        }
+    }
 }
@@ -1,10 +1,22 @@
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use tracing::info;
+use crate::supervisor::TenantLifecycle;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SlotStatus {
+    pub env: String,
+    pub generation: u64,
+    pub lifecycle: TenantLifecycle,
+    pub updated_at: DateTime<Utc>,
+}
 
 pub struct InfraRegistry {
-    pub statuses: Arc<RwLock<HashMap<String, String>>>,
+    /// tomain_id -> lifecycle of every instance the shell currently holds for it
+    pub statuses: Arc<RwLock<HashMap<String, Vec<SlotStatus>>>>,
 }
 
 impl InfraRegistry {
@@ -14,9 +26,26 @@ impl InfraRegistry {
         }
     }
 
-    pub async fn update_status(&self, id: &str, status: &str) -> anyhow::Result<()> {
-        info!("Updating Tomain {} status to {}", id, status);
-        self.statuses.write().await.insert(id.to_string(), status.to_string());
-        Ok(())
+    /// Records a lifecycle transition. Retired instances are dropped from the map.
+    pub async fn update_status(&self, id: &str, env: &str, generation: u64, lifecycle: TenantLifecycle) {
+        info!("Updating Tomain {} ({} #{}) status to {:?}", id, env, generation, lifecycle);
+        let mut statuses = self.statuses.write().await;
+        let slots = statuses.entry(id.to_string()).or_insert_with(Vec::new);
+        slots.retain(|s| s.generation != generation);
+        if lifecycle != TenantLifecycle::Retired {
+            slots.push(SlotStatus {
+                env: env.to_string(),
+                generation,
+                lifecycle,
+                updated_at: Utc::now(),
+            });
+        }
+        if slots.is_empty() {
+            statuses.remove(id);
+        }
+    }
+
+    pub async fn snapshot(&self) -> HashMap<String, Vec<SlotStatus>> {
+        self.statuses.read().await.clone()
     }
 }
@@ -2,21 +2,35 @@ use anyhow::{Result, Context, anyhow};
 use std::sync::Arc;
 use crate::supervisor::TenantInstance;
 use crate::runtime::WasmSupervisor;
+use crate::egress::{EgressError, EgressResponse};
 use wasmtime::*;
 use wasmtime_wasi::preview1::WasiP1Ctx;
 use wasmtime_wasi::WasiCtxBuilder;
-use tracing::{info, error, warn};
+use tracing::{info, error, warn, Instrument};
 
 pub struct HostState {
     pub wasi: WasiP1Ctx,
     pub supervisor: Arc<WasmSupervisor>,
     pub tomain_id: String,
+    pub slot: String,
+    /// Correlates every log line and host call made during one invocation
+    pub request_id: String,
+    /// Config snapshot taken when the store was created
+    pub config: Arc<crate::config::ConfigSet>,
+    /// How many kernel-to-kernel hops led to this invocation (0 for ingress, schedules, jobs)
+    pub call_depth: u32,
+    /// The tomain's capability manifest, empty if it never declared one
+    pub capabilities: Arc<crate::capabilities::Capabilities>,
 }
 
+/// Fuel budget handed to every store; what's left after a call is reported as consumption.
+const FUEL_PER_CALL: u64 = 1_000_000;
+/// Export generated by `#[axiom_health]`; returns a C string, "Healthy" or why the kernel isn't.
+const HEALTH_EXPORT: &str = "__axiom_health";
+
 pub async fn invoke_reflect(supervisor: Arc<WasmSupervisor>, tenant: Arc<TenantInstance>) -> Result<String> {
-    let mut store = create_store(supervisor, tenant.id.clone(), &tenant.engine)?;
-    let linker = create_linker(&tenant.engine)?;
-    let instance: Instance = linker.instantiate_async(&mut store, &tenant.module).await?;
+    let mut store = create_store(supervisor.clone(), &tenant)?;
+    let instance = instantiate(&supervisor, &tenant, &mut store).await?;
     
     let func = instance.get_typed_func::<(), u32>(&mut store, "reflect")?;
     let ptr = func.call_async(&mut store, ()).await?;
@@ -36,17 +50,106 @@ pub async fn invoke_reflect(supervisor: Arc<WasmSupervisor>, tenant: Arc<TenantI
     Ok(json)
 }
 
+/// Instantiates the kernel and, when it has a `#[axiom_health]` check, runs it. Anything other
+/// than "Healthy" is an error, so a pre-warm keeps the previous instance.
 pub async fn invoke_health(supervisor: Arc<WasmSupervisor>, tenant: Arc<TenantInstance>) -> Result<String> {
-    let mut store = create_store(supervisor, tenant.id.clone(), &tenant.engine)?;
-    let linker = create_linker(&tenant.engine)?;
-    let _instance: Instance = linker.instantiate_async(&mut store, &tenant.module).await?;
-    Ok("Healthy".to_string())
+    let mut store = create_store(supervisor.clone(), &tenant)?;
+    let instance = instantiate(&supervisor, &tenant, &mut store).await?;
+    let Ok(check) = instance.get_typed_func::<(), u32>(&mut store, HEALTH_EXPORT) else {
+        return Ok("Healthy".to_string());
+    };
+    let ptr = check.call_async(&mut store, ()).await? as usize;
+
+    let memory = instance.get_memory(&mut store, "memory")
+        .context("Failed to find memory")?;
+    let data = memory.data(&store);
+    let end = data.get(ptr..).and_then(|d| d.iter().position(|b| *b == 0)).map(|p| ptr + p)
+        .context("Health check returned an invalid pointer")?;
+    let status = String::from_utf8_lossy(&data[ptr..end]).to_string();
+    if status != "Healthy" {
+        return Err(anyhow!("Kernel health check reported: {}", status));
+    }
+    Ok(status)
+}
+
+/// Collects the `__axiom_metadata_*` JSON blobs the `#[axiom_api]` macro exports for each function.
+pub async fn invoke_metadata(supervisor: Arc<WasmSupervisor>, tenant: Arc<TenantInstance>) -> Result<Vec<serde_json::Value>> {
+    collect_export_json(supervisor, tenant, "__axiom_metadata_").await
+}
+
+/// Collects the `__axiom_subscription_*` blobs `#[axiom_subscribe]` exports: `{ "topic", "handler" }`.
+pub async fn invoke_subscriptions(supervisor: Arc<WasmSupervisor>, tenant: Arc<TenantInstance>) -> Result<Vec<serde_json::Value>> {
+    collect_export_json(supervisor, tenant, "__axiom_subscription_").await
+}
+
+/// Calls every `() -> *const u8` export whose name starts with `prefix` and parses the C string it returns.
+async fn collect_export_json(supervisor: Arc<WasmSupervisor>, tenant: Arc<TenantInstance>, prefix: &str) -> Result<Vec<serde_json::Value>> {
+    let names: Vec<String> = tenant.module.exports()
+        .map(|e| e.name().to_string())
+        .filter(|n| n.starts_with(prefix))
+        .collect();
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut store = create_store(supervisor.clone(), &tenant)?;
+    let instance = instantiate(&supervisor, &tenant, &mut store).await?;
+    let memory = instance.get_memory(&mut store, "memory")
+        .context("Failed to find memory")?;
+
+    let mut blobs = Vec::new();
+    for name in names {
+        let f = instance.get_typed_func::<(), u32>(&mut store, &name)?;
+        let ptr = f.call_async(&mut store, ()).await? as usize;
+        let data = memory.data(&store);
+        let end = data[ptr..].iter().position(|b| *b == 0).map(|p| ptr + p).unwrap_or(data.len());
+        match serde_json::from_slice(&data[ptr..end]) {
+            Ok(blob) => blobs.push(blob),
+            Err(e) => warn!("Malformed {} export for {}: {}", name, tenant.id, e),
+        }
+    }
+    Ok(blobs)
+}
+
+/// Delivers one event payload to a subscription handler. The handler acks with 0; anything else is a nack.
+pub async fn invoke_event(supervisor: Arc<WasmSupervisor>, tenant: Arc<TenantInstance>, handler: &str, payload: &str) -> Result<()> {
+    let mut store = create_store(supervisor.clone(), &tenant)?;
+    let instance = instantiate(&supervisor, &tenant, &mut store).await?;
+    let f = instance.get_typed_func::<(u32, u32), u32>(&mut store, handler)
+        .context(format!("Event handler '{}' not found in Wasm module", handler))?;
+    let memory = instance.get_memory(&mut store, "memory")
+        .context("Failed to find memory")?;
+
+    let bytes = payload.as_bytes();
+    let write_offset = memory.data_size(&store);
+    memory.grow(&mut store, (bytes.len() as u64).div_ceil(65536).max(1))?;
+    memory.data_mut(&mut store)[write_offset..write_offset + bytes.len()].copy_from_slice(bytes);
+
+    let code = f.call_async(&mut store, (write_offset as u32, bytes.len() as u32))
+        .instrument(tracing::info_span!("axiom.guest", axiom.function = %handler))
+        .await?;
+    record_usage(&supervisor, &tenant, &mut store, &instance);
+
+    if code == 0 {
+        Ok(())
+    } else {
+        Err(anyhow!("Handler '{}' rejected the event (code {})", handler, code))
+    }
 }
 
 pub async fn invoke_call(supervisor: Arc<WasmSupervisor>, tenant: Arc<TenantInstance>, func_name: &str, query_json: String) -> Result<String> {
-    let mut store = create_store(supervisor, tenant.id.clone(), &tenant.engine)?;
-    let linker = create_linker(&tenant.engine)?;
-    let instance: Instance = linker.instantiate_async(&mut store, &tenant.module).await?;
+    invoke_call_from(supervisor, tenant, func_name, query_json, None).await
+}
+
+/// Like `invoke_call`, but when `caller` is set the callee inherits its request id and call depth.
+pub async fn invoke_call_from(supervisor: Arc<WasmSupervisor>, tenant: Arc<TenantInstance>, func_name: &str, query_json: String, caller: Option<&crate::runtime::CallerContext>) -> Result<String> {
+    let mut store = create_store(supervisor.clone(), &tenant)?;
+    if let Some(caller) = caller {
+        let state = store.data_mut();
+        state.request_id = caller.request_id.clone();
+        state.call_depth = caller.depth + 1;
+    }
+    let instance = instantiate(&supervisor, &tenant, &mut store).await?;
     
     // Name variants to try
     let call_variants = vec![
@@ -75,7 +178,9 @@ pub async fn invoke_call(supervisor: Arc<WasmSupervisor>, tenant: Arc<TenantInst
             memory.data_mut(&mut store)[write_offset as usize..write_offset as usize + json_bytes.len()]
                 .copy_from_slice(json_bytes);
             
-            res_ptr = Some(f.call_async(&mut store, (write_offset, json_len)).await?);
+            res_ptr = Some(f.call_async(&mut store, (write_offset, json_len))
+                .instrument(tracing::info_span!("axiom.guest", axiom.function = %variant))
+                .await?);
             break;
         }
     }
@@ -84,17 +189,22 @@ pub async fn invoke_call(supervisor: Arc<WasmSupervisor>, tenant: Arc<TenantInst
     if res_ptr.is_none() {
         for variant in &plain_variants {
             if let Ok(f) = instance.get_typed_func::<(), u32>(&mut store, variant) {
-                res_ptr = Some(f.call_async(&mut store, ()).await?);
+                res_ptr = Some(f.call_async(&mut store, ())
+                    .instrument(tracing::info_span!("axiom.guest", axiom.function = %variant))
+                    .await?);
                 break;
             } else if let Ok(f) = instance.get_typed_func::<(), ()>(&mut store, variant) {
-                f.call_async(&mut store, ()).await?;
+                f.call_async(&mut store, ())
+                    .instrument(tracing::info_span!("axiom.guest", axiom.function = %variant))
+                    .await?;
                 res_ptr = Some(0);
                 break;
             }
         }
     }
 
-    let res_ptr = res_ptr.context(format!("Function '{}' not found in Wasm module", func_name))?;
+    record_usage(&supervisor, &tenant, &mut store, &instance);
+    let res_ptr = res_ptr.ok_or_else(|| anyhow::Error::new(crate::runtime::UnknownTarget(format!("Function '{}' not found in Wasm module", func_name))))?;
 
     if res_ptr == 0 { return Ok("Success (void/0)".to_string()); }
 
@@ -111,19 +221,57 @@ pub async fn invoke_call(supervisor: Arc<WasmSupervisor>, tenant: Arc<TenantInst
     Ok(String::from_utf8_lossy(&data[start..end]).to_string())
 }
 
-fn create_store(supervisor: Arc<WasmSupervisor>, tomain_id: String, engine: &Engine) -> Result<Store<HostState>> {
-    let wasi = WasiCtxBuilder::new().inherit_stdout().inherit_stderr().build_p1();
+async fn instantiate(supervisor: &WasmSupervisor, tenant: &TenantInstance, store: &mut Store<HostState>) -> Result<Instance> {
+    let capabilities = store.data().capabilities.clone();
+    let linker = create_linker(&tenant.engine, &capabilities)?;
+    let started = std::time::Instant::now();
+    let instance = linker.instantiate_async(&mut *store, &tenant.module)
+        .instrument(tracing::info_span!("axiom.instantiate", axiom.tomain = %tenant.id, axiom.slot = %tenant.env))
+        .await?;
+    supervisor.metrics.observe(
+        "axiom_instantiate_duration_seconds",
+        &[("tomain", &tenant.id), ("slot", &tenant.env)],
+        started.elapsed().as_secs_f64(),
+    );
+    Ok(instance)
+}
+
+/// Reports fuel burned and the linear memory high-water mark for a finished call.
+fn record_usage(supervisor: &WasmSupervisor, tenant: &TenantInstance, store: &mut Store<HostState>, instance: &Instance) {
+    let labels = [("tomain", tenant.id.as_str()), ("slot", tenant.env.as_str())];
+    if let Ok(remaining) = store.get_fuel() {
+        supervisor.metrics.inc_counter("axiom_fuel_consumed_total", &labels, FUEL_PER_CALL.saturating_sub(remaining) as f64);
+    }
+    if let Some(memory) = instance.get_memory(&mut *store, "memory") {
+        supervisor.metrics.max_gauge("axiom_memory_high_water_bytes", &labels, memory.data_size(&*store) as f64);
+    }
+}
+
+fn create_store(supervisor: Arc<WasmSupervisor>, tenant: &TenantInstance) -> Result<Store<HostState>> {
+    let config = supervisor.config.get(&tenant.id, &tenant.env).unwrap_or_default();
+    let capabilities = supervisor.capabilities.get(&tenant.id);
+    let mut builder = WasiCtxBuilder::new();
+    builder.inherit_stdout().inherit_stderr().envs(&config.env_vars());
+    let wasi = builder.build_p1();
     let state = HostState {
         wasi,
         supervisor,
-        tomain_id,
+        tomain_id: tenant.id.clone(),
+        slot: tenant.env.clone(),
+        request_id: uuid::Uuid::new_v4().to_string(),
+        config,
+        call_depth: 0,
+        capabilities,
     };
-    let mut store = Store::new(engine, state);
-    store.set_fuel(1_000_000)?;
+    let mut store = Store::new(&tenant.engine, state);
+    store.set_fuel(FUEL_PER_CALL)?;
     Ok(store)
 }
 
-fn create_linker(engine: &Engine) -> Result<Linker<HostState>> {
+/// Links WASI plus the `axiom` host functions. Gated functions are only linked when the capability
+/// manifest grants them, so an ungranted import fails to instantiate instead of running.
+fn create_linker(engine: &Engine, capabilities: &crate::capabilities::Capabilities) -> Result<Linker<HostState>> {
+    let granted = |capability: &str| capabilities.grants(capability);
     let mut linker = Linker::new(engine);
     wasmtime_wasi::preview1::add_to_linker_async(&mut linker, |t: &mut HostState| &mut t.wasi)?;
     
@@ -133,181 +281,128 @@ fn create_linker(engine: &Engine) -> Result<Linker<HostState>> {
     })?;
 
     // Pillar #9: Egress Guard
-    linker.func_wrap_async("axiom", "http_call", |mut caller: Caller<'_, HostState>, (alias_ptr, method_ptr, body_ptr, body_len): (u32, u32, u32, u32)| {
-        Box::new(async move {
-            let memory = caller.get_export("memory").and_then(|e| e.into_memory()).context("Failed to get memory")?;
-            
-            // 1. Read alias, method, and body from Wasm memory
-            let alias = read_wasm_string(&caller, &memory, alias_ptr as usize)?;
-            let method_name = read_wasm_string(&caller, &memory, method_ptr as usize)?.to_uppercase();
-            
-            let body_bytes = if body_ptr > 0 && body_len > 0 {
-                let mut buf = vec![0u8; body_len as usize];
-                memory.read(&caller, body_ptr as usize, &mut buf)?;
-                Some(buf)
-            } else {
-                None
-            };
-            
-            let (supervisor, tomain_id) = {
-                let state = caller.data();
-                (state.supervisor.clone(), state.tomain_id.clone())
-            };
-            let environment = supervisor.perspective.get(&tomain_id).map(|p| p.value().clone()).unwrap_or_else(|| "GREEN".to_string());
-            
-            // Pillar #3: Sampling Rate Adjustment
-            if environment == "BLUE" {
-                info!("📊 [SAMPLING++]: Trace sampling rate increased for BLUE perspective.");
-            }
-            
-            // Pillar #4: Audit Mode (RED)
-            if environment == "RED" {
-                let audit_entry = format!("HTTP {} {} (Alias: {})", method_name, tomain_id, alias);
-                supervisor.audit_log.entry(tomain_id.clone()).or_insert_with(Vec::new).push(audit_entry);
-                info!("🔴 [AUDIT]: Recorded state change: HTTP {} to {}", method_name, alias);
-            }
-            
-            // Pillar #6: Security Boundary
-            // Ensure target service is promoted to the caller's environment
-            if supervisor.manager.get_tenant(&alias, &environment).await.is_none() {
-                // Check if it's an external URL (starts with http) or a logical alias
-                if !alias.starts_with("http") {
-                    warn!("🛑 Security Boundary: Service '{}' is not promoted to {} environment. Call blocked.", alias, environment);
-                    return Ok(write_wasm_string(&mut caller, &memory, &format!("Error: Security Boundary: {} not promoted to {}", alias, environment)));
-                }
-            }
-
-            // 2. Resolve alias to physical URL
-            match supervisor.egress.resolve(&tomain_id, &alias, &environment).await {
-                Ok(url) => {
-                    info!("🚀 Egress Guard: Resolved '{}' -> {} (Method: {}, Tomain: {}, Env: {})", alias, url, method_name, tomain_id, environment);
-                    
-                    // 3. Downstream Resilience Guards
-                    let resilience = supervisor.resilience.clone();
-                    
-                    // a. Rate Limiting (10 req/sec default for now)
-                    if !resilience.traffic.check_downstream(&alias, 10.0) {
-                        warn!("⏳ Downstream Rate Limit: Throttling '{}'", alias);
-                        return Ok(write_wasm_string(&mut caller, &memory, "Error: Rate Limit Exceeded (429)"));
-                    }
-
-                    // b. Circuit Breaker
-                    if !resilience.fault.breakers.entry(alias.clone()).or_insert_with(crate::resilience::CircuitBreaker::new).value_mut().should_allow() {
-                        warn!("🚨 Downstream Circuit OPEN: Blocking call to '{}'", alias);
-                        return Ok(write_wasm_string(&mut caller, &memory, "Error: Circuit Breaker Open"));
-                    }
+    if granted("http") {
+        // Legacy ABI: null-terminated alias and method, optional body
+        linker.func_wrap_async("axiom", "http_call", |mut caller: Caller<'_, HostState>, (alias_ptr, method_ptr, body_ptr, body_len): (u32, u32, u32, u32)| {
+            Box::new(async move {
+                let memory = caller.get_export("memory").and_then(|e| e.into_memory()).context("Failed to get memory")?;
+                let request = EgressRequest {
+                    alias: read_wasm_string(&caller, &memory, alias_ptr as usize)?,
+                    method: read_wasm_string(&caller, &memory, method_ptr as usize)?,
+                    ..Default::default()
+                };
+                let body = if body_ptr > 0 && body_len > 0 {
+                    Some(read_wasm_bytes(&caller, &memory, body_ptr, body_len)?)
+                } else {
+                    None
+                };
+                let (supervisor, ctx, capabilities) = (caller.data().supervisor.clone(), caller_context(caller.data()), caller.data().capabilities.clone());
+                // Body text on any status, `Error: <Kind>: <detail>` when no response came back
+                let text = match egress_call(supervisor, ctx, capabilities, request, body).await {
+                    Ok(resp) => String::from_utf8_lossy(&resp.body).into_owned(),
+                    Err(err) => format!("Error: {}", err),
+                };
+                Ok(write_wasm_string(&mut caller, &memory, &text))
+            })
+        })?;
 
-                    // 4. Exponential Backoff Retries (Pillar #2)
-                    let mut attempts = 0;
-                    let max_retries = 3;
-                    let mut last_result: Result<reqwest::Response, anyhow::Error> = Err(anyhow!("Request not started"));
-
-                    while attempts <= max_retries {
-                        if attempts > 0 {
-                            let delay = 2u64.pow(attempts as u32 - 1);
-                            info!("🔁 Retrying '{}' (Attempt {}/3) in {}s...", alias, attempts, delay);
-                            tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
-                        }
-
-                        // We need to clone the request builder for retries
-                        // reqwest::RequestBuilder doesn't implement Clone, so we re-create it
-                        let mut retry_req = supervisor.http_client.request(
-                            match method_name.as_str() {
-                                "POST" => reqwest::Method::POST,
-                                "PUT" => reqwest::Method::PUT,
-                                "DELETE" => reqwest::Method::DELETE,
-                                _ => reqwest::Method::GET,
-                            }, 
-                            &url
-                        );
-                        if let Some(token) = resilience.security.get_vault_token(&alias) {
-                            retry_req = retry_req.header("Authorization", format!("Bearer {}", token));
-                        }
-                        if let Some(ref body) = body_bytes {
-                            retry_req = retry_req.body(body.clone());
-                        }
-
-                        match retry_req.send().await {
-                            Ok(resp) if resp.status().is_success() => {
-                                let text = resp.text().await.unwrap_or_else(|_| "Error reading body".to_string());
-                                resilience.fault.breakers.get_mut(&alias).unwrap().report_success();
-                                return Ok(write_wasm_string(&mut caller, &memory, &text));
-                            }
-                            Ok(resp) if resp.status().is_server_error() => {
-                                warn!("⚠️ Transient error ({}) on '{}'. Retrying...", resp.status(), alias);
-                                last_result = Err(anyhow!("Server Error: {}", resp.status()));
-                            }
-                            Ok(resp) => {
-                                let text = resp.text().await.unwrap_or_else(|_| "Error reading body".to_string());
-                                resilience.fault.breakers.get_mut(&alias).unwrap().report_failure();
-                                return Ok(write_wasm_string(&mut caller, &memory, &text));
-                            }
-                            Err(e) => {
-                                warn!("⚠️ Request error: {:?}. Retrying...", e);
-                                last_result = Err(anyhow::Error::new(e));
-                            }
-                        }
-                        attempts += 1;
-                    }
-
-                    // If max retries exhausted
-                    resilience.fault.breakers.get_mut(&alias).unwrap().report_failure();
-                    warn!("❌ Max retries exhausted for '{}': {:?}", alias, last_result);
-                    Ok(write_wasm_string(&mut caller, &memory, &format!("Error: Downstream FAILED after 3 retries: {:?}", last_result)))
-                },
-                Err(_) => {
-                    warn!("🛑 Egress Guard: Blocking call to unauthorized alias '{}' (Tomain: {})", alias, tomain_id);
-                    Ok(0u32) 
-                }
-            }
-        })
-    })?;
+        // Full request: JSON `EgressRequest` plus the body bytes, which may be any content type.
+        // Replies with `{"ok":true,"status":..,"headers":[[k,v]..],"body":"<base64>"}`
+        // or `{"ok":false,"error":{"kind":"<EgressError kind>","message":".."}}`.
+        linker.func_wrap_async("axiom", "http_request", |mut caller: Caller<'_, HostState>, (req_ptr, req_len, body_ptr, body_len): (u32, u32, u32, u32)| {
+            Box::new(async move {
+                let memory = caller.get_export("memory").and_then(|e| e.into_memory()).context("Failed to get memory")?;
+                let request: EgressRequest = serde_json::from_slice(&read_wasm_bytes(&caller, &memory, req_ptr, req_len)?)
+                    .context("Failed to parse http request")?;
+                let body = if body_len > 0 {
+                    Some(read_wasm_bytes(&caller, &memory, body_ptr, body_len)?)
+                } else {
+                    None
+                };
+                let (supervisor, ctx, capabilities) = (caller.data().supervisor.clone(), caller_context(caller.data()), caller.data().capabilities.clone());
+                let reply = egress_reply(egress_call(supervisor, ctx, capabilities, request, body).await);
+                Ok(write_wasm_string(&mut caller, &memory, &reply))
+            })
+        })?;
+    }
 
     // Pillar #1: Database Bridge
-    linker.func_wrap_async("axiom", "db_execute", |mut caller: Caller<'_, HostState>, (alias_ptr, query_ptr, query_len): (u32, u32, u32)| {
-        Box::new(async move {
-            let memory = caller.get_export("memory").and_then(|e| e.into_memory()).context("Failed to get memory")?;
+    if granted("db") {
+        linker.func_wrap_async("axiom", "db_execute", |mut caller: Caller<'_, HostState>, (alias_ptr, query_ptr, query_len): (u32, u32, u32)| {
+            Box::new(async move {
+                let memory = caller.get_export("memory").and_then(|e| e.into_memory()).context("Failed to get memory")?;
             
-            let alias = read_wasm_string(&caller, &memory, alias_ptr as usize)?;
-            let query_json = if query_ptr > 0 && query_len > 0 {
-                let mut buf = vec![0u8; query_len as usize];
-                memory.read(&caller, query_ptr as usize, &mut buf)?;
-                String::from_utf8_lossy(&buf).to_string()
-            } else {
-                return Ok(0u32);
-            };
+                let alias = read_wasm_string(&caller, &memory, alias_ptr as usize)?;
+                let query_json = if query_ptr > 0 && query_len > 0 {
+                    let mut buf = vec![0u8; query_len as usize];
+                    memory.read(&caller, query_ptr as usize, &mut buf)?;
+                    String::from_utf8_lossy(&buf).to_string()
+                } else {
+                    return Ok(0u32);
+                };
 
-            let (supervisor, tomain_id) = {
-                let s = caller.data();
-                (s.supervisor.clone(), s.tomain_id.clone())
-            };
-            let environment = supervisor.perspective.get(&tomain_id).map(|p| p.value().clone()).unwrap_or_else(|| "GREEN".to_string());
+                let (supervisor, tomain_id, capabilities) = {
+                    let s = caller.data();
+                    (s.supervisor.clone(), s.tomain_id.clone(), s.capabilities.clone())
+                };
+                let environment = supervisor.perspective.get(&tomain_id).map(|p| p.value().clone()).unwrap_or_else(|| "GREEN".to_string());
 
-            if environment == "RED" {
-                let audit_entry = format!("DB_EXECUTE {} (Alias: {})", tomain_id, alias);
-                supervisor.audit_log.entry(tomain_id.clone()).or_insert_with(Vec::new).push(audit_entry);
-                info!("🔴 [AUDIT]: Recorded state change: DB EXECUTE on {}", alias);
-            }
+                if !crate::capabilities::allows(&capabilities.db, &alias) {
+                    warn!("🛑 Capability: DB alias '{}' is not granted to {}", alias, tomain_id);
+                    let audit_entry = format!("CAPABILITY_DENIED {} db {}", tomain_id, alias);
+                    supervisor.audit_log.entry(tomain_id.clone()).or_default().push(audit_entry);
+                    let denied = EgressError::PolicyDenied(format!("db alias '{}' not granted in {}'s capabilities", alias, tomain_id));
+                    return Ok(write_wasm_string(&mut caller, &memory, &egress_reply(Err(denied))));
+                }
+
+                if environment == "RED" {
+                    let audit_entry = format!("DB_EXECUTE {} (Alias: {})", tomain_id, alias);
+                    supervisor.audit_log.entry(tomain_id.clone()).or_insert_with(Vec::new).push(audit_entry);
+                    info!("🔴 [AUDIT]: Recorded state change: DB EXECUTE on {}", alias);
+                }
             
-            let query: crate::db::AxiomQuery = serde_json::from_str(&query_json).context("Failed to parse AxiomQuery")?;
+                let query: crate::db::AxiomQuery = serde_json::from_str(&query_json).context("Failed to parse AxiomQuery")?;
 
-            if let Some(provider) = supervisor.db_registry.get(&alias) {
-                match provider.execute_query(query).await {
-                    Ok(resp) => {
-                        let res_json = serde_json::to_string(&resp).unwrap_or_default();
-                        Ok(write_wasm_string(&mut caller, &memory, &res_json))
+                if let Some(provider) = supervisor.db_registry.get(&alias) {
+                    let started = std::time::Instant::now();
+                    let injection = supervisor.chaos.inject(&tomain_id, &environment, &alias);
+                    if let Some(injection) = &injection {
+                        supervisor.record_fault(&tomain_id, &environment, &alias, injection);
+                        tokio::time::sleep(injection.delay).await;
                     }
-                    Err(e) => {
-                        error!("DB Egress call FAILED (Alias: {}): {:?}", alias, e);
-                        Ok(0u32)
+                    let result = match injection.and_then(|i| i.fault) {
+                        Some(crate::chaos::Fault::Error(_)) => Err(anyhow!("query failed (injected)")),
+                        Some(crate::chaos::Fault::Reset) => Err(anyhow!("connection reset (injected)")),
+                        fault => provider.execute_query(query)
+                            .instrument(tracing::info_span!("axiom.db_execute", otel.kind = "client", axiom.alias = %alias))
+                            .await
+                            .map(|mut resp| {
+                                if fault == Some(crate::chaos::Fault::Truncate) {
+                                    resp.rows.truncate(resp.rows.len() / 2);
+                                }
+                                resp
+                            }),
+                    };
+                    supervisor.metrics.observe("axiom_db_query_duration_seconds", &[("alias", &alias)], started.elapsed().as_secs_f64());
+                    let outcome = if result.is_ok() { "ok" } else { "error" };
+                    supervisor.metrics.inc_counter("axiom_db_queries_total", &[("alias", &alias), ("outcome", outcome)], 1.0);
+                    match result {
+                        Ok(resp) => {
+                            let res_json = serde_json::to_string(&resp).unwrap_or_default();
+                            Ok(write_wasm_string(&mut caller, &memory, &res_json))
+                        }
+                        Err(e) => {
+                            error!("DB Egress call FAILED (Alias: {}): {:?}", alias, e);
+                            Ok(0u32)
+                        }
                     }
+                } else {
+                    warn!("🛑 DB Guard: No provider found for alias '{}'", alias);
+                    Ok(0u32)
                 }
-            } else {
-                warn!("🛑 DB Guard: No provider found for alias '{}'", alias);
-                Ok(0u32)
-            }
-        })
-    })?;
+            })
+        })?;
+    }
 
     // Pillar #3: SDK Visibility
     linker.func_wrap_async("axiom", "axiom_health_status", |mut caller: Caller<'_, HostState>, (alias_ptr,): (u32,)| {
@@ -316,7 +411,9 @@ fn create_linker(engine: &Engine) -> Result<Linker<HostState>> {
             let alias = read_wasm_string(&caller, &memory, alias_ptr as usize)?;
             
             let supervisor = caller.data().supervisor.clone();
-            let state = supervisor.resilience.fault.get_status(&alias);
+            let tomain_id = caller.data().tomain_id.clone();
+            let environment = supervisor.get_perspective(&tomain_id);
+            let state = supervisor.resilience.fault.get_status(&(tomain_id, environment, alias));
             
             let state_str = format!("{:?}", state);
             Ok(write_wasm_string(&mut caller, &memory, &state_str))
@@ -324,32 +421,814 @@ fn create_linker(engine: &Engine) -> Result<Linker<HostState>> {
     })?;
 
     // Pillar #3: Native Logging
-    linker.func_wrap("axiom", "axiom_log", |mut caller: Caller<'_, HostState>, ptr: u32, len: u32, level: u32| {
+    if granted("logging") {
+        linker.func_wrap("axiom", "axiom_log", |mut caller: Caller<'_, HostState>, ptr: u32, len: u32, level: u32| {
+            let memory = caller.get_export("memory").and_then(|e| e.into_memory()).context("Failed to get memory")?;
+            let msg = read_wasm_bytes(&caller, &memory, ptr, len).context("Log pointer out of bounds")?;
+            emit_guest_log(caller.data(), level, String::from_utf8_lossy(&msg).to_string(), serde_json::Map::new());
+            Ok(())
+        })?;
+
+        // Structured logging: message plus a JSON object of key/value fields
+        linker.func_wrap("axiom", "axiom_log_kv", |mut caller: Caller<'_, HostState>, ptr: u32, len: u32, level: u32, fields_ptr: u32, fields_len: u32| {
+            let memory = caller.get_export("memory").and_then(|e| e.into_memory()).context("Failed to get memory")?;
+            let msg = read_wasm_bytes(&caller, &memory, ptr, len).context("Log pointer out of bounds")?;
+            let fields_raw = read_wasm_bytes(&caller, &memory, fields_ptr, fields_len).context("Log fields pointer out of bounds")?;
+            let fields = match serde_json::from_slice::<serde_json::Value>(&fields_raw) {
+                Ok(serde_json::Value::Object(map)) => map,
+                _ => serde_json::Map::new(),
+            };
+            emit_guest_log(caller.data(), level, String::from_utf8_lossy(&msg).to_string(), fields);
+            Ok(())
+        })?;
+    }
+
+    // Guest business metrics: kind (0=counter, 1=gauge, 2=histogram), name, JSON label pairs, value
+    linker.func_wrap("axiom", "axiom_metric", |mut caller: Caller<'_, HostState>, kind: u32, name_ptr: u32, name_len: u32, labels_ptr: u32, labels_len: u32, value: f64| {
         let memory = caller.get_export("memory").and_then(|e| e.into_memory()).context("Failed to get memory")?;
-        let data = memory.data(&caller);
-        let start = ptr as usize;
-        let end = start + len as usize;
-        
-        if end > data.len() {
-            return Err(anyhow!("Log pointer out of bounds"));
-        }
-        
-        let msg = String::from_utf8_lossy(&data[start..end]).to_string();
-        let tomain_id = &caller.data().tomain_id;
-        
-        match level {
-            0 => error!(tomain_id = %tomain_id, "{}", msg),
-            1 => warn!(tomain_id = %tomain_id, "{}", msg),
-            2 => info!(tomain_id = %tomain_id, "{}", msg),
-            3 => tracing::debug!(tomain_id = %tomain_id, "{}", msg),
-            _ => tracing::trace!(tomain_id = %tomain_id, "{}", msg),
+        let name = String::from_utf8_lossy(&read_wasm_bytes(&caller, &memory, name_ptr, name_len)?).to_string();
+        let labels: Vec<(String, String)> = if labels_len > 0 {
+            serde_json::from_slice(&read_wasm_bytes(&caller, &memory, labels_ptr, labels_len)?).unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        let state = caller.data();
+        let Some(kind) = crate::metrics::guest_kind(kind) else {
+            warn!("📉 Guest metric '{}' from {} has unknown kind {}", name, state.tomain_id, kind);
+            return Ok(());
+        };
+        if let Err(reason) = state.supervisor.metrics.record_guest(&state.tomain_id, &state.slot, kind, &name, &labels, value) {
+            warn!("📉 Dropped guest metric from {}: {}", state.tomain_id, reason);
         }
         Ok(())
     })?;
 
+    // Event bus: publish to a topic the tomain's manifest grants. 0 = accepted, 1 = not granted, 2 = bus error
+    if granted("events") {
+        linker.func_wrap_async("axiom", "event_publish", |mut caller: Caller<'_, HostState>, (topic_ptr, topic_len, payload_ptr, payload_len): (u32, u32, u32, u32)| {
+            Box::new(async move {
+                let memory = caller.get_export("memory").and_then(|e| e.into_memory()).context("Failed to get memory")?;
+                let topic = String::from_utf8_lossy(&read_wasm_bytes(&caller, &memory, topic_ptr, topic_len)?).to_string();
+                let payload = String::from_utf8_lossy(&read_wasm_bytes(&caller, &memory, payload_ptr, payload_len)?).to_string();
+
+                let (supervisor, tomain_id, slot, capabilities) = {
+                    let state = caller.data();
+                    (state.supervisor.clone(), state.tomain_id.clone(), state.slot.clone(), state.capabilities.clone())
+                };
+                let code: u32 = match supervisor.events.publish(&capabilities, &tomain_id, &slot, &topic, payload).await {
+                    Ok(_) => 0,
+                    Err(crate::events::PublishError::NotGranted) => {
+                        warn!("🛑 {} is not granted publish on topic '{}'", tomain_id, topic);
+                        1
+                    }
+                    Err(crate::events::PublishError::Broker(e)) => {
+                        error!("Event publish to '{}' failed: {}", topic, e);
+                        2
+                    }
+                };
+                let outcome = ["accepted", "denied", "error"][code as usize];
+                supervisor.metrics.inc_counter("axiom_events_published_total", &[("tomain", &tomain_id), ("outcome", outcome)], 1.0);
+                Ok(code)
+            })
+        })?;
+    }
+
+    // Key-value store, scoped to the calling tomain and slot. Each returns a JSON reply:
+    // {"ok":true,"value":...} or {"ok":false,"error":"..."}
+    if granted("kv") {
+        linker.func_wrap_async("axiom", "kv_get", |mut caller: Caller<'_, HostState>, (key_ptr, key_len): (u32, u32)| {
+            Box::new(async move {
+                let memory = caller.get_export("memory").and_then(|e| e.into_memory()).context("Failed to get memory")?;
+                let key = String::from_utf8_lossy(&read_wasm_bytes(&caller, &memory, key_ptr, key_len)?).to_string();
+                let (supervisor, tomain_id, slot) = caller_scope(&caller);
+                let result = supervisor.kv.get(&tomain_id, &slot, &key).await
+                    .map(|v| v.map(serde_json::Value::String).unwrap_or(serde_json::Value::Null));
+                let reply = kv_reply(&supervisor, &tomain_id, "get", result);
+                Ok(write_wasm_string(&mut caller, &memory, &reply))
+            })
+        })?;
+
+        linker.func_wrap_async("axiom", "kv_set", |mut caller: Caller<'_, HostState>, (key_ptr, key_len, value_ptr, value_len, ttl_ms): (u32, u32, u32, u32, u64)| {
+            Box::new(async move {
+                let memory = caller.get_export("memory").and_then(|e| e.into_memory()).context("Failed to get memory")?;
+                let key = String::from_utf8_lossy(&read_wasm_bytes(&caller, &memory, key_ptr, key_len)?).to_string();
+                let value = String::from_utf8_lossy(&read_wasm_bytes(&caller, &memory, value_ptr, value_len)?).to_string();
+                let (supervisor, tomain_id, slot) = caller_scope(&caller);
+                audit_kv_write(&supervisor, &tomain_id, "SET", &key);
+                let result = supervisor.kv.set(&tomain_id, &slot, &key, &value, kv_ttl(ttl_ms)).await
+                    .map(|_| serde_json::Value::Null);
+                let reply = kv_reply(&supervisor, &tomain_id, "set", result);
+                Ok(write_wasm_string(&mut caller, &memory, &reply))
+            })
+        })?;
+
+        linker.func_wrap_async("axiom", "kv_delete", |mut caller: Caller<'_, HostState>, (key_ptr, key_len): (u32, u32)| {
+            Box::new(async move {
+                let memory = caller.get_export("memory").and_then(|e| e.into_memory()).context("Failed to get memory")?;
+                let key = String::from_utf8_lossy(&read_wasm_bytes(&caller, &memory, key_ptr, key_len)?).to_string();
+                let (supervisor, tomain_id, slot) = caller_scope(&caller);
+                audit_kv_write(&supervisor, &tomain_id, "DELETE", &key);
+                let result = supervisor.kv.delete(&tomain_id, &slot, &key).await
+                    .map(serde_json::Value::Bool);
+                let reply = kv_reply(&supervisor, &tomain_id, "delete", result);
+                Ok(write_wasm_string(&mut caller, &memory, &reply))
+            })
+        })?;
+
+        // has_expected = 0 means "only if the key is absent"; value is true when the swap happened
+        linker.func_wrap_async("axiom", "kv_cas", |mut caller: Caller<'_, HostState>, (key_ptr, key_len, expected_ptr, expected_len, has_expected, new_ptr, new_len, ttl_ms): (u32, u32, u32, u32, u32, u32, u32, u64)| {
+            Box::new(async move {
+                let memory = caller.get_export("memory").and_then(|e| e.into_memory()).context("Failed to get memory")?;
+                let key = String::from_utf8_lossy(&read_wasm_bytes(&caller, &memory, key_ptr, key_len)?).to_string();
+                let expected = if has_expected != 0 {
+                    Some(String::from_utf8_lossy(&read_wasm_bytes(&caller, &memory, expected_ptr, expected_len)?).to_string())
+                } else {
+                    None
+                };
+                let new = String::from_utf8_lossy(&read_wasm_bytes(&caller, &memory, new_ptr, new_len)?).to_string();
+                let (supervisor, tomain_id, slot) = caller_scope(&caller);
+                audit_kv_write(&supervisor, &tomain_id, "CAS", &key);
+                let result = supervisor.kv.compare_and_swap(&tomain_id, &slot, &key, expected.as_deref(), &new, kv_ttl(ttl_ms)).await
+                    .map(serde_json::Value::Bool);
+                let reply = kv_reply(&supervisor, &tomain_id, "cas", result);
+                Ok(write_wasm_string(&mut caller, &memory, &reply))
+            })
+        })?;
+
+        // value is an array of [key, value] pairs, sorted by key
+        linker.func_wrap_async("axiom", "kv_scan", |mut caller: Caller<'_, HostState>, (prefix_ptr, prefix_len, limit): (u32, u32, u32)| {
+            Box::new(async move {
+                let memory = caller.get_export("memory").and_then(|e| e.into_memory()).context("Failed to get memory")?;
+                let prefix = String::from_utf8_lossy(&read_wasm_bytes(&caller, &memory, prefix_ptr, prefix_len)?).to_string();
+                let (supervisor, tomain_id, slot) = caller_scope(&caller);
+                let limit = if limit == 0 { crate::kv::MAX_SCAN } else { limit as usize };
+                let result = supervisor.kv.scan(&tomain_id, &slot, &prefix, limit).await
+                    .map(|pairs| serde_json::json!(pairs));
+                let reply = kv_reply(&supervisor, &tomain_id, "scan", result);
+                Ok(write_wasm_string(&mut caller, &memory, &reply))
+            })
+        })?;
+    }
+
+    // Secrets granted to the calling tomain in its current slot. Replies like the KV functions;
+    // every access is audited by key name, and the value itself is never logged.
+    if granted("secrets") {
+        linker.func_wrap_async("axiom", "secret_get", |mut caller: Caller<'_, HostState>, (key_ptr, key_len): (u32, u32)| {
+            Box::new(async move {
+                let memory = caller.get_export("memory").and_then(|e| e.into_memory()).context("Failed to get memory")?;
+                let key = String::from_utf8_lossy(&read_wasm_bytes(&caller, &memory, key_ptr, key_len)?).to_string();
+                let (supervisor, tomain_id, slot) = caller_scope(&caller);
+
+                let result = supervisor.secrets.get(&tomain_id, &slot, &key);
+                let outcome = match &result {
+                    Ok(_) => "granted",
+                    Err(crate::secrets::SecretError::NotGranted) => "denied",
+                    Err(crate::secrets::SecretError::NotFound) => "missing",
+                };
+                let audit_entry = format!("SECRET_READ {} in {} (Key: {}, Outcome: {})", tomain_id, slot, key, outcome);
+                supervisor.audit_log.entry(tomain_id.clone()).or_default().push(audit_entry);
+                supervisor.metrics.inc_counter("axiom_secret_accesses_total", &[("tomain", &tomain_id), ("outcome", outcome)], 1.0);
+
+                let reply = match result {
+                    Ok(value) => serde_json::json!({ "ok": true, "value": value }),
+                    Err(crate::secrets::SecretError::NotGranted) => {
+                        warn!("🛑 {} is not granted secret '{}' in {}", tomain_id, key, slot);
+                        serde_json::json!({ "ok": false, "error": format!("Secret '{}' is not granted to this tomain in {}", key, slot) })
+                    }
+                    Err(crate::secrets::SecretError::NotFound) => {
+                        serde_json::json!({ "ok": false, "error": format!("Secret '{}' has no value", key) })
+                    }
+                };
+                Ok(write_wasm_string(&mut caller, &memory, &reply.to_string()))
+            })
+        })?;
+    }
+
+    // Direct kernel-to-kernel call: {"ok":true,"value":"<callee result>"} or {"ok":false,"error":"..."}
+    if granted("tomains") {
+        linker.func_wrap_async("axiom", "tomain_call", |mut caller: Caller<'_, HostState>, (target_ptr, target_len, func_ptr, func_len, args_ptr, args_len): (u32, u32, u32, u32, u32, u32)| {
+            Box::new(async move {
+                let memory = caller.get_export("memory").and_then(|e| e.into_memory()).context("Failed to get memory")?;
+                let target = String::from_utf8_lossy(&read_wasm_bytes(&caller, &memory, target_ptr, target_len)?).to_string();
+                let func = String::from_utf8_lossy(&read_wasm_bytes(&caller, &memory, func_ptr, func_len)?).to_string();
+                let args = if args_len > 0 {
+                    String::from_utf8_lossy(&read_wasm_bytes(&caller, &memory, args_ptr, args_len)?).to_string()
+                } else {
+                    "{}".to_string()
+                };
+
+                let (supervisor, capabilities) = (caller.data().supervisor.clone(), caller.data().capabilities.clone());
+                let ctx = caller_context(caller.data());
+                if !crate::capabilities::allows(&capabilities.tomains, &target) {
+                    warn!("🛑 Capability: {} may not call {}", ctx.tomain_id, target);
+                    let reply = serde_json::json!({ "ok": false, "error": format!("Capability: tomain '{}' not granted", target) });
+                    return Ok(write_wasm_string(&mut caller, &memory, &reply.to_string()));
+                }
+                if supervisor.get_perspective(&ctx.tomain_id) == "RED" {
+                    let audit_entry = format!("TOMAIN_CALL {} -> {}/{}", ctx.tomain_id, target, func);
+                    supervisor.audit_log.entry(ctx.tomain_id.clone()).or_default().push(audit_entry);
+                    info!("🔴 [AUDIT]: Recorded state change: TOMAIN CALL to {}/{}", target, func);
+                }
+
+                let reply = match supervisor.clone().call_from(&ctx, &target, &func, args).await {
+                    Ok(value) => serde_json::json!({ "ok": true, "value": value }),
+                    Err(e) => {
+                        warn!("🛑 {} -> {}/{} failed: {}", ctx.tomain_id, target, func, e);
+                        serde_json::json!({ "ok": false, "error": e.to_string() })
+                    }
+                };
+                Ok(write_wasm_string(&mut caller, &memory, &reply.to_string()))
+            })
+        })?;
+    }
+
+    // Per-environment config from the store's snapshot: {"ok":true,"value":<json or null>,"version":N}
+    linker.func_wrap("axiom", "config_get", |mut caller: Caller<'_, HostState>, key_ptr: u32, key_len: u32| {
+        let memory = caller.get_export("memory").and_then(|e| e.into_memory()).context("Failed to get memory")?;
+        let key = String::from_utf8_lossy(&read_wasm_bytes(&caller, &memory, key_ptr, key_len)?).to_string();
+        let config = caller.data().config.clone();
+        let reply = serde_json::json!({
+            "ok": true,
+            "value": config.values.get(&key).cloned().unwrap_or(serde_json::Value::Null),
+            "version": config.version,
+        });
+        Ok(write_wasm_string(&mut caller, &memory, &reply.to_string()))
+    })?;
+
     Ok(linker)
 }
 
+/// An outbound call as the guest describes it. `path` and `query` are appended to the bound URL.
+#[derive(Debug, Default, serde::Deserialize)]
+struct EgressRequest {
+    alias: String,
+    #[serde(default)]
+    method: String,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    query: Vec<(String, String)>,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    #[serde(default)]
+    timeout_ms: Option<u64>,
+}
+
+/// Methods a guest may use; anything else is refused.
+const EGRESS_METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+/// Headers the Shell sets itself. Guests can't override the vault token or trace context.
+const RESERVED_HEADERS: &[&str] = &[
+    "authorization", "proxy-authorization", "host", "content-length",
+    "transfer-encoding", "connection", "traceparent", "tracestate",
+];
+/// Upper bound for a guest-chosen timeout.
+const MAX_EGRESS_TIMEOUT_MS: u64 = 60_000;
+
+/// POSTs a JSON document to `alias` on behalf of `ctx`'s tomain, e.g. a finished job to its
+/// callback. Goes through `egress_call` like a guest request, so the same rules apply.
+/// `idempotency_key` is sent in the alias's idempotency header so the caller can resend safely.
+pub async fn egress_post_json(supervisor: Arc<WasmSupervisor>, ctx: crate::runtime::CallerContext, alias: &str, body: Vec<u8>, idempotency_key: &str) -> Result<EgressResponse, EgressError> {
+    let capabilities = supervisor.capabilities.get(&ctx.tomain_id);
+    let environment = supervisor.get_perspective(&ctx.tomain_id);
+    let policy = supervisor.resilience.fault.retry_policy(&ctx.tomain_id, &environment, alias);
+    let request = EgressRequest {
+        alias: alias.to_string(),
+        method: "POST".to_string(),
+        headers: vec![
+            ("Content-Type".to_string(), "application/json".to_string()),
+            (policy.idempotency_header, idempotency_key.to_string()),
+        ],
+        ..Default::default()
+    };
+    egress_call(supervisor, ctx, capabilities, request, Some(body)).await
+}
+
+/// A cacheable call's key, policy and stale entry, between `EgressCache::lookup` and `complete`.
+type PendingCache = (String, crate::egress_cache::CachePolicy, Option<Box<crate::egress_cache::CachedEntry>>);
+
+/// Hands a downstream answer to the egress cache when the call was cacheable. An answer shaped by
+/// an injected fault skips the cache, or it would keep being served after the rule is cleared.
+fn complete_cached(
+    supervisor: &WasmSupervisor,
+    pending: Option<PendingCache>,
+    fault: Option<crate::chaos::Fault>,
+    (tomain_id, environment, alias): (&str, &str, &str),
+    response: EgressResponse,
+) -> EgressResponse {
+    let Some((key, cache_policy, stale)) = pending else {
+        return response;
+    };
+    let (response, result) = if fault.is_some() {
+        (response, "bypassed")
+    } else {
+        let (response, outcome) = supervisor.egress_cache.complete(key, &cache_policy, stale, (tomain_id, environment, alias), response);
+        (response, match outcome {
+            crate::egress_cache::CacheOutcome::Stored => "stored",
+            crate::egress_cache::CacheOutcome::Revalidated => "revalidated",
+            crate::egress_cache::CacheOutcome::Bypassed => "bypassed",
+        })
+    };
+    supervisor.metrics.inc_counter("axiom_egress_cache_total", &[("tomain", tomain_id), ("alias", alias), ("result", result)], 1.0);
+    supervisor.metrics.set_gauge("axiom_egress_cache_bytes", &[], supervisor.egress_cache.stats().bytes as f64);
+    response
+}
+
+/// Runs one egress call through the capability list, security boundary, policy or binding
+/// resolution, rate limiter, circuit breaker and retries. Any status the downstream answers with
+/// is a response; an error means no response came back.
+async fn egress_call(
+    supervisor: Arc<WasmSupervisor>,
+    ctx: crate::runtime::CallerContext,
+    capabilities: Arc<crate::capabilities::Capabilities>,
+    request: EgressRequest,
+    body_bytes: Option<Vec<u8>>,
+) -> Result<EgressResponse, EgressError> {
+    let alias = request.alias.clone();
+    let method_name = if request.method.is_empty() { "GET".to_string() } else { request.method.to_uppercase() };
+    let tomain_id = ctx.tomain_id.clone();
+    let environment = supervisor.perspective.get(&tomain_id).map(|p| p.value().clone()).unwrap_or_else(|| "GREEN".to_string());
+
+    if !EGRESS_METHODS.contains(&method_name.as_str()) {
+        return Err(EgressError::PolicyDenied(format!("unsupported HTTP method '{}'", method_name)));
+    }
+
+    // Raw URLs are governed by the egress policy below rather than the alias list
+    let raw_url = crate::egress::is_raw_url(&alias);
+    if !raw_url && !crate::capabilities::allows(&capabilities.http, &alias) {
+        warn!("🛑 Capability: '{}' is not in {}'s http capability list. Call blocked.", alias, tomain_id);
+        let audit_entry = format!("CAPABILITY_DENIED {} http {}", tomain_id, alias);
+        supervisor.audit_log.entry(tomain_id.clone()).or_default().push(audit_entry);
+        return Err(EgressError::PolicyDenied(format!("http alias '{}' not granted in {}'s capabilities", alias, tomain_id)));
+    }
+
+    // Pillar #4: Audit Mode (RED)
+    if environment == "RED" {
+        let audit_entry = format!("HTTP {} {} (Alias: {})", method_name, tomain_id, alias);
+        supervisor.audit_log.entry(tomain_id.clone()).or_default().push(audit_entry);
+        info!("🔴 [AUDIT]: Recorded state change: HTTP {} to {}", method_name, alias);
+    }
+
+    // Pillar #6: Security Boundary
+    // Ensure target service is promoted to the caller's environment
+    if !raw_url && supervisor.manager.get_tenant(&alias, &environment).await.is_none() {
+        warn!("🛑 Security Boundary: Service '{}' is not promoted to {} environment. Call blocked.", alias, environment);
+        return Err(EgressError::NoBinding(format!("{} not promoted to {}", alias, environment)));
+    }
+
+    // 2. Resolve alias to physical URL, or vet a raw URL against the egress policy
+    let resolved = if raw_url {
+        supervisor.egress.check_raw_url(&tomain_id, &environment, &alias).map(|_| alias.clone())
+    } else {
+        supervisor.egress.resolve(&tomain_id, &alias, &environment).await
+            .map_err(|e| EgressError::NoBinding(e.to_string()))
+    };
+    let url = match resolved.and_then(|base| build_egress_url(&base, request.path.as_deref(), &request.query)) {
+        Ok(url) => url,
+        Err(err) => {
+            warn!("🛑 Egress Guard: Blocking call to '{}' (Tomain: {}): {}", alias, tomain_id, err);
+            let audit_entry = format!("EGRESS_DENIED {} {} {} ({})", tomain_id, method_name, alias, err);
+            supervisor.audit_log.entry(tomain_id.clone()).or_default().push(audit_entry);
+            let kind = match err {
+                EgressError::NoBinding(_) => "no_binding",
+                _ => "policy_denied",
+            };
+            supervisor.metrics.inc_counter("axiom_egress_denied_total", &[("tomain", &tomain_id), ("reason", kind)], 1.0);
+            return Err(err);
+        }
+    };
+    info!("🚀 Egress Guard: Resolved '{}' -> {} (Method: {}, Tomain: {}, Env: {})", alias, url, method_name, tomain_id, environment);
+
+    // The alias points back at a kernel loaded in this Shell: call it in-process instead of over loopback
+    if let Some((target, func, args)) = local_invocation(&supervisor, &ctx.slot, &url, &method_name, body_bytes.as_deref()).await {
+        info!("↪️ Egress Guard: Short-circuiting '{}' to in-process call {}/{}", alias, target, func);
+        return supervisor.clone().call_from(&ctx, &target, &func, args).await
+            .map(|res| EgressResponse {
+                status: 200,
+                headers: vec![("content-type".to_string(), "application/json".to_string())],
+                body: res.into_bytes(),
+            })
+            .map_err(|e| EgressError::Transport(e.to_string()));
+    }
+
+    // Guest headers minus the ones the Shell owns
+    let mut guest_headers = reqwest::header::HeaderMap::new();
+    for (name, value) in &request.headers {
+        if RESERVED_HEADERS.contains(&name.to_lowercase().as_str()) {
+            warn!("🛑 Egress Guard: Dropping reserved header '{}' from {}", name, tomain_id);
+            continue;
+        }
+        match (reqwest::header::HeaderName::from_bytes(name.as_bytes()), reqwest::header::HeaderValue::from_str(value)) {
+            (Ok(name), Ok(value)) => { guest_headers.append(name, value); }
+            _ => return Err(EgressError::PolicyDenied(format!("invalid header '{}'", name))),
+        }
+    }
+    let method = reqwest::Method::from_bytes(method_name.as_bytes()).unwrap_or(reqwest::Method::GET);
+    let timeout = request.timeout_ms.map(|ms| std::time::Duration::from_millis(ms.clamp(1, MAX_EGRESS_TIMEOUT_MS)));
+
+    // Opt-in response cache: a fresh entry is served without reaching the downstream (or its
+    // guards); a stale one is revalidated below, once the rate limit and breaker let the call out
+    let mut cache_pending = None;
+    match supervisor.egress_cache.lookup(&tomain_id, &environment, &alias, &method_name, &url, &guest_headers) {
+        Some(crate::egress_cache::CacheLookup::Fresh(cached)) => {
+            info!("🗄️ Egress cache HIT for '{}' (Tomain: {})", alias, tomain_id);
+            supervisor.metrics.inc_counter("axiom_egress_cache_total", &[("tomain", &tomain_id), ("alias", &alias), ("result", "hit")], 1.0);
+            return Ok(cached);
+        }
+        Some(crate::egress_cache::CacheLookup::Miss { key, policy, stale }) => {
+            supervisor.metrics.inc_counter("axiom_egress_cache_total", &[("tomain", &tomain_id), ("alias", &alias), ("result", "miss")], 1.0);
+            // Guest-supplied validators mean the guest wants the 304 itself
+            let guest_conditional = guest_headers.contains_key("if-none-match") || guest_headers.contains_key("if-modified-since");
+            if !guest_conditional {
+                cache_pending = Some((key, policy, stale));
+            }
+        }
+        None => {}
+    }
+
+    // Per-binding TLS (client certs, private CAs, SNI); insecure profiles never reach PROD
+    let (client, request_url) = match supervisor.tls.client_for(&tomain_id, &environment, &alias, &url, &supervisor.secrets, &supervisor.http_client).await {
+        Ok(pair) => pair,
+        Err(err) => {
+            warn!("🛑 Egress Guard: Blocking call to '{}' (Tomain: {}): {}", alias, tomain_id, err);
+            supervisor.metrics.inc_counter("axiom_egress_denied_total", &[("tomain", &tomain_id), ("reason", "tls")], 1.0);
+            return Err(err);
+        }
+    };
+
+    // 3. Downstream Resilience Guards
+    let resilience = supervisor.resilience.clone();
+
+    // a. Rate Limiting (per alias from `rate_limits.downstream`, 10 req/sec by default)
+    if !resilience.traffic.check_downstream(&alias).allowed {
+        warn!("⏳ Downstream Rate Limit: Throttling '{}'", alias);
+        supervisor.metrics.inc_counter("axiom_egress_rate_limited_total", &[("tomain", &tomain_id), ("alias", &alias)], 1.0);
+        return Err(EgressError::RateLimited(format!("downstream limit for '{}' exceeded", alias)));
+    }
+
+    // b. Bulkhead: cap concurrent calls to this alias, held until the call finishes
+    let _permit = match resilience.bulkheads.acquire_egress(&alias).await {
+        Ok(permit) => permit,
+        Err(shed) => {
+            warn!("🚧 Egress bulkhead for '{}' is full: {}", alias, shed.reason);
+            supervisor.metrics.inc_counter("axiom_load_shed_total", &[("scope", "egress"), ("tomain", &tomain_id), ("reason", shed.reason)], 1.0);
+            return Err(EgressError::Overloaded(format!("'{}' has too many calls in flight ({})", alias, shed.reason)));
+        }
+    };
+
+    // c. Circuit Breaker, scoped to this tomain and environment
+    let breaker_key = (tomain_id.clone(), environment.clone(), alias.clone());
+    let (allowed, transition) = resilience.fault.try_acquire(&breaker_key);
+    if let Some(transition) = transition {
+        supervisor.announce_breaker(transition);
+    }
+    if !allowed {
+        warn!("🚨 Downstream Circuit OPEN: Blocking call to '{}'", alias);
+        supervisor.metrics.inc_counter("axiom_egress_circuit_rejections_total", &[("tomain", &tomain_id), ("alias", &alias)], 1.0);
+        return Err(EgressError::CircuitOpen(format!("circuit for '{}' is open", alias)));
+    }
+
+    // d. Conditional revalidation of a stale cache entry, now that the guards admitted the call
+    let validators: Vec<(&'static str, reqwest::header::HeaderValue)> = cache_pending.iter()
+        .flat_map(|(_, _, stale)| stale.iter())
+        .flat_map(|entry| entry.validators())
+        .filter_map(|(name, value)| reqwest::header::HeaderValue::from_str(&value).ok().map(|v| (name, v)))
+        .collect();
+
+    // OAuth2 client credentials take precedence over a static vault token. The token is fetched
+    // once per call and refreshed only if the downstream rejects it with a 401.
+    let oauth_bound = supervisor.oauth.has_client(&tomain_id, &environment, &alias);
+    let mut bearer = if oauth_bound {
+        Some(oauth_token(&supervisor, &breaker_key).await?)
+    } else {
+        resilience.security.get_vault_token(&alias)
+    };
+    let mut reauthenticated = false;
+
+    // 4. Retries per the alias's policy (Pillar #2)
+    let policy = resilience.fault.retry_policy(&tomain_id, &environment, &alias);
+    let retryable = policy.allows_method(&method_name);
+    // The guest's timeout can only shorten the policy's overall deadline
+    let deadline_budget = std::time::Duration::from_millis(policy.deadline_ms);
+    let deadline = tokio::time::Instant::now() + timeout.map_or(deadline_budget, |t| t.min(deadline_budget));
+    // Non-idempotent retries are only safe if the downstream can de-duplicate them
+    if retryable && !matches!(method_name.as_str(), "GET" | "HEAD" | "OPTIONS" | "PUT" | "DELETE")
+        && !guest_headers.contains_key(policy.idempotency_header.as_str())
+        && let (Ok(name), Ok(value)) = (
+            reqwest::header::HeaderName::from_bytes(policy.idempotency_header.as_bytes()),
+            reqwest::header::HeaderValue::from_str(&uuid::Uuid::new_v4().to_string()),
+        )
+    {
+        guest_headers.insert(name, value);
+    }
+    let max_attempts = if retryable { policy.max_attempts.max(1) } else { 1 };
+    let mut attempts = 0;
+    let mut last_error = EgressError::Transport("request not started".to_string());
+    // The latest answer with a retryable status, handed back if no later attempt does better
+    let mut last_response: Option<EgressResponse> = None;
+
+    while attempts < max_attempts {
+        if attempts > 0 {
+            let delay = policy.backoff(attempts);
+            if tokio::time::Instant::now() + delay >= deadline {
+                info!("⌛ Deadline for '{}' leaves no room for attempt {}/{}", alias, attempts + 1, max_attempts);
+                break;
+            }
+            info!("🔁 Retrying '{}' (Attempt {}/{}) in {}ms...", alias, attempts + 1, max_attempts, delay.as_millis());
+            supervisor.metrics.inc_counter("axiom_egress_retries_total", &[("tomain", &tomain_id), ("alias", &alias)], 1.0);
+            tokio::time::sleep(delay).await;
+        }
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if remaining.is_zero() {
+            last_error = EgressError::Timeout(format!("'{}' exceeded its {}ms deadline", alias, deadline_budget.as_millis()));
+            break;
+        }
+
+        // Chaos: injected faults take the same retry and breaker path as real ones
+        let injection = supervisor.chaos.inject(&tomain_id, &environment, &alias);
+        if let Some(injection) = &injection {
+            supervisor.record_fault(&tomain_id, &environment, &alias, injection);
+            tokio::time::sleep(injection.delay.min(remaining)).await;
+        }
+        let fault = injection.and_then(|i| i.fault);
+        if fault == Some(crate::chaos::Fault::Reset) {
+            supervisor.metrics.inc_counter("axiom_egress_requests_total", &[("tomain", &tomain_id), ("alias", &alias), ("status_class", "error")], 1.0);
+            last_error = EgressError::Transport(format!("'{}': connection reset (injected)", alias));
+            last_response = None;
+            attempts += 1;
+            continue;
+        }
+
+        // We need to clone the request builder for retries
+        // reqwest::RequestBuilder doesn't implement Clone, so we re-create it
+        let mut retry_req = client.request(method.clone(), &request_url)
+            .headers(guest_headers.clone())
+            .timeout(remaining.min(std::time::Duration::from_millis(policy.attempt_timeout_ms)));
+        if let Some(token) = &bearer {
+            retry_req = retry_req.header("Authorization", format!("Bearer {}", token));
+        }
+        if let Some(ref body) = body_bytes {
+            retry_req = retry_req.body(body.clone());
+        }
+        // A faulted attempt bypasses the cache, so it must get a full answer rather than a 304
+        if fault.is_none() {
+            for (name, value) in &validators {
+                retry_req = retry_req.header(*name, value.clone());
+            }
+        }
+
+        // One client span per attempt, propagated downstream as traceparent
+        let attempt_span = tracing::info_span!(
+            "axiom.egress.attempt",
+            otel.kind = "client",
+            axiom.alias = %alias,
+            http.method = %method_name,
+            http.url = %url,
+            attempt = attempts,
+        );
+        let mut trace_headers = reqwest::header::HeaderMap::new();
+        attempt_span.in_scope(|| crate::telemetry::inject_current_context(&mut trace_headers));
+        retry_req = retry_req.headers(trace_headers);
+
+        let sent = match fault {
+            Some(crate::chaos::Fault::Error(status)) => match axum::http::Response::builder().status(status).body("injected fault") {
+                Ok(injected) => Ok(reqwest::Response::from(injected)),
+                Err(e) => {
+                    supervisor.metrics.inc_counter("axiom_egress_requests_total", &[("tomain", &tomain_id), ("alias", &alias), ("status_class", "error")], 1.0);
+                    last_error = EgressError::Transport(format!("'{}': injected status {} is invalid: {}", alias, status, e));
+                    last_response = None;
+                    attempts += 1;
+                    continue;
+                }
+            },
+            _ => retry_req.send().instrument(attempt_span).await,
+        };
+        let status_class = match &sent {
+            Ok(resp) => crate::metrics::status_class(resp.status().as_u16()),
+            Err(_) => "error",
+        };
+        supervisor.metrics.inc_counter("axiom_egress_requests_total", &[("tomain", &tomain_id), ("alias", &alias), ("status_class", status_class)], 1.0);
+
+        match sent {
+            Ok(resp) if retryable && policy.retry_on.contains(&resp.status().as_u16()) => {
+                warn!("⚠️ Retryable status ({}) on '{}'", resp.status(), alias);
+                let (status, headers) = (resp.status().as_u16(), response_headers(&resp));
+                match resp.bytes().await {
+                    Ok(body) => last_response = Some(EgressResponse { status, headers, body: body.to_vec() }),
+                    Err(e) => {
+                        last_error = EgressError::Transport(format!("reading body from '{}': {}", alias, e));
+                        last_response = None;
+                    }
+                }
+            }
+            // The token may have been revoked early: refresh it and resend once
+            Ok(resp) if resp.status() == reqwest::StatusCode::UNAUTHORIZED && oauth_bound && !reauthenticated => {
+                info!("🔑 OAuth2: '{}' rejected the token, refreshing it", alias);
+                supervisor.oauth.invalidate(&tomain_id, &environment, &alias).await;
+                bearer = Some(oauth_token(&supervisor, &breaker_key).await?);
+                reauthenticated = true;
+                continue;
+            }
+            Ok(resp) => {
+                let status = resp.status();
+                // Still rejected with a fresh token; make the next call fetch another one
+                if status == reqwest::StatusCode::UNAUTHORIZED && oauth_bound {
+                    supervisor.oauth.invalidate(&tomain_id, &environment, &alias).await;
+                }
+                // A 304 answers a revalidation, it isn't a failure
+                let healthy = status.is_success() || status == reqwest::StatusCode::NOT_MODIFIED;
+                if let Some(transition) = resilience.fault.record(&breaker_key, healthy) {
+                    supervisor.announce_breaker(transition);
+                }
+                let headers = response_headers(&resp);
+                let mut response = match resp.bytes().await {
+                    Ok(body) => EgressResponse { status: status.as_u16(), headers, body: body.to_vec() },
+                    Err(e) => return Err(EgressError::Transport(format!("reading body from '{}': {}", alias, e))),
+                };
+                if fault == Some(crate::chaos::Fault::Truncate) {
+                    response.body.truncate(response.body.len() / 2);
+                }
+                return Ok(complete_cached(&supervisor, cache_pending.take(), fault, (&tomain_id, &environment, &alias), response));
+            }
+            Err(e) => {
+                warn!("⚠️ Request error on '{}': {:?}", alias, e);
+                last_error = if e.is_timeout() {
+                    EgressError::Timeout(format!("'{}' did not answer in time", alias))
+                } else {
+                    EgressError::Transport(format!("'{}': {}", alias, e))
+                };
+                last_response = None;
+            }
+        }
+        attempts += 1;
+    }
+
+    // Attempts or deadline exhausted
+    if let Some(transition) = resilience.fault.record(&breaker_key, false) {
+        supervisor.announce_breaker(transition);
+    }
+    // The downstream did answer: the guest gets its real status, headers and body
+    if let Some(response) = last_response {
+        warn!("❌ Giving up on '{}' after {} attempt(s), last status {}", alias, attempts, response.status);
+        return Ok(response);
+    }
+    warn!("❌ Giving up on '{}' after {} attempt(s): {}", alias, attempts, last_error);
+    Err(match last_error {
+        EgressError::Transport(detail) if attempts > 1 => EgressError::RetriesExhausted(format!("{} attempts failed, last: {}", attempts, detail)),
+        other => other,
+    })
+}
+
+/// Bearer token from the binding's OAuth2 client. Without one the binding is unusable, so a
+/// failed fetch counts against its breaker.
+async fn oauth_token(supervisor: &Arc<WasmSupervisor>, breaker_key: &(String, String, String)) -> Result<String, EgressError> {
+    let (tomain_id, environment, alias) = breaker_key;
+    let fetched = supervisor.oauth.token(tomain_id, environment, alias, &supervisor.secrets, &supervisor.http_client).await
+        .unwrap_or_else(|| Err("no OAuth2 client is configured".to_string()));
+    fetched.map_err(|e| {
+        warn!("🔑 OAuth2: No token for '{}' (Tomain: {}): {}", alias, tomain_id, e);
+        supervisor.metrics.inc_counter("axiom_oauth_token_failures_total", &[("tomain", tomain_id), ("alias", alias)], 1.0);
+        if let Some(transition) = supervisor.resilience.fault.record(breaker_key, false) {
+            supervisor.announce_breaker(transition);
+        }
+        EgressError::Auth(format!("could not obtain a token for '{}': {}", alias, e))
+    })
+}
+
+fn response_headers(resp: &reqwest::Response) -> Vec<(String, String)> {
+    resp.headers().iter()
+        .map(|(k, v)| (k.to_string(), String::from_utf8_lossy(v.as_bytes()).into_owned()))
+        .collect()
+}
+
+/// JSON reply for `http_request`; the body is base64 so any content type survives.
+fn egress_reply(result: Result<EgressResponse, EgressError>) -> String {
+    use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+    match result {
+        Ok(resp) => serde_json::json!({
+            "ok": true,
+            "status": resp.status,
+            "headers": resp.headers,
+            "body": BASE64.encode(&resp.body),
+        }).to_string(),
+        Err(err) => serde_json::json!({
+            "ok": false,
+            "error": { "kind": err.kind(), "message": err.detail() },
+        }).to_string(),
+    }
+}
+
+/// Appends the guest's path suffix and query pairs to a bound base URL.
+fn build_egress_url(base: &str, path: Option<&str>, query: &[(String, String)]) -> Result<String, EgressError> {
+    let joined = match path.map(|p| p.trim_start_matches('/')).filter(|p| !p.is_empty()) {
+        Some(path) => format!("{}/{}", base.trim_end_matches('/'), path),
+        None => base.to_string(),
+    };
+    let mut url = url::Url::parse(&joined)
+        .map_err(|e| EgressError::PolicyDenied(format!("malformed URL '{}': {}", joined, e)))?;
+    if !query.is_empty() {
+        url.query_pairs_mut().extend_pairs(query);
+    }
+    Ok(url.to_string())
+}
+
+fn caller_context(state: &HostState) -> crate::runtime::CallerContext {
+    crate::runtime::CallerContext {
+        tomain_id: state.tomain_id.clone(),
+        slot: state.slot.clone(),
+        request_id: state.request_id.clone(),
+        depth: state.call_depth,
+    }
+}
+
+/// Recognizes a resolved URL of the form `http://localhost:<shell port>/{tomain}/{func}` whose
+/// tomain is loaded in `slot`, and returns the target, function and JSON arguments.
+async fn local_invocation(supervisor: &WasmSupervisor, slot: &str, url: &str, method: &str, body: Option<&[u8]>) -> Option<(String, String, String)> {
+    let parsed = url::Url::parse(url).ok()?;
+    let shell_port = crate::HTTP_PORT.rsplit(':').next()?.parse::<u16>().ok()?;
+    let is_local = matches!(parsed.host_str(), Some("localhost" | "127.0.0.1" | "0.0.0.0"));
+    if !is_local || parsed.port_or_known_default() != Some(shell_port) {
+        return None;
+    }
+    let segments: Vec<&str> = parsed.path_segments()?.filter(|s| !s.is_empty()).collect();
+    let [target, func] = segments.as_slice() else { return None };
+    supervisor.manager.get_tenant(target, slot).await?;
+
+    let method = axum::http::Method::from_bytes(method.as_bytes()).ok()?;
+    let uri: axum::http::Uri = url.parse().ok()?;
+    let args = crate::invocation_args(&method, &uri, body.unwrap_or_default());
+    Some((target.to_string(), func.to_string(), args))
+}
+
+fn caller_scope(caller: &Caller<'_, HostState>) -> (Arc<WasmSupervisor>, String, String) {
+    let state = caller.data();
+    (state.supervisor.clone(), state.tomain_id.clone(), state.slot.clone())
+}
+
+fn kv_ttl(ttl_ms: u64) -> Option<std::time::Duration> {
+    (ttl_ms > 0).then(|| std::time::Duration::from_millis(ttl_ms))
+}
+
+/// Mutations made while a tomain's perspective is RED land in the audit log, like DB writes.
+fn audit_kv_write(supervisor: &WasmSupervisor, tomain_id: &str, op: &str, key: &str) {
+    if supervisor.get_perspective(tomain_id) == "RED" {
+        let audit_entry = format!("KV_{} {} (Key: {})", op, tomain_id, key);
+        supervisor.audit_log.entry(tomain_id.to_string()).or_default().push(audit_entry);
+        info!("🔴 [AUDIT]: Recorded state change: KV {} on {}", op, key);
+    }
+}
+
+fn kv_reply(supervisor: &WasmSupervisor, tomain_id: &str, op: &str, result: Result<serde_json::Value>) -> String {
+    let outcome = if result.is_ok() { "ok" } else { "error" };
+    supervisor.metrics.inc_counter("axiom_kv_operations_total", &[("tomain", tomain_id), ("op", op), ("outcome", outcome)], 1.0);
+    match result {
+        Ok(value) => serde_json::json!({ "ok": true, "value": value }).to_string(),
+        Err(e) => {
+            error!("KV {} FAILED for {}: {}", op, tomain_id, e);
+            serde_json::json!({ "ok": false, "error": e.to_string() }).to_string()
+        }
+    }
+}
+
+/// Fans a guest log line out to the shell's tracing output, the active span and the log store.
+fn emit_guest_log(state: &HostState, level: u32, msg: String, fields: serde_json::Map<String, serde_json::Value>) {
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    let (tomain_id, request_id) = (&state.tomain_id, &state.request_id);
+    let guest_fields = serde_json::Value::Object(fields.clone());
+
+    match level {
+        0 => error!(tomain_id = %tomain_id, request_id = %request_id, guest_fields = %guest_fields, "{}", msg),
+        1 => warn!(tomain_id = %tomain_id, request_id = %request_id, guest_fields = %guest_fields, "{}", msg),
+        2 => info!(tomain_id = %tomain_id, request_id = %request_id, guest_fields = %guest_fields, "{}", msg),
+        3 => tracing::debug!(tomain_id = %tomain_id, request_id = %request_id, guest_fields = %guest_fields, "{}", msg),
+        _ => tracing::trace!(tomain_id = %tomain_id, request_id = %request_id, guest_fields = %guest_fields, "{}", msg),
+    }
+
+    // Searchable attributes on the guest's span, namespaced so they can't clobber ours
+    let span = tracing::Span::current();
+    for (key, value) in &fields {
+        let value = value.as_str().map(|s| s.to_string()).unwrap_or_else(|| value.to_string());
+        span.set_attribute(format!("guest.{}", key), value);
+    }
+
+    state.supervisor.logs.push(crate::logs::LogEntry {
+        tomain_id: state.tomain_id.clone(),
+        slot: state.slot.clone(),
+        level: crate::logs::level_name(level).to_string(),
+        timestamp: chrono::Utc::now(),
+        request_id: state.request_id.clone(),
+        message: msg,
+        fields,
+    });
+}
+
+fn read_wasm_bytes(caller: &impl AsContext, memory: &Memory, ptr: u32, len: u32) -> Result<Vec<u8>> {
+    let mut buf = vec![0u8; len as usize];
+    memory.read(caller, ptr as usize, &mut buf)?;
+    Ok(buf)
+}
+
 fn write_wasm_string(caller: &mut Caller<'_, HostState>, memory: &Memory, text: &str) -> u32 {
     let res_bytes = format!("{}\0", text).into_bytes();
     let write_offset = memory.data_size(&mut *caller);
@@ -372,3 +1251,67 @@ fn read_wasm_string(caller: &impl AsContext, memory: &Memory, ptr: usize) -> Res
     }
     Ok(String::from_utf8_lossy(&data[..end]).to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capabilities::Capabilities;
+
+    const KV_KERNEL: &str = r#"(module (import "axiom" "kv_get" (func (param i32 i32) (result i32))))"#;
+    const LOG_KERNEL: &str = r#"(module (import "axiom" "axiom_log" (func (param i32 i32 i32))))"#;
+    const UNGATED_KERNEL: &str = r#"(module (import "axiom" "get_family_token" (func (result i32))))"#;
+
+    /// Resolves a module's imports against the linker without needing a store.
+    fn links(source: &str, caps: &Capabilities) -> bool {
+        let engine = crate::supervisor::TenantManager::new().create_engine().unwrap();
+        let module = Module::new(&engine, source).unwrap();
+        create_linker(&engine, caps).unwrap().instantiate_pre(&module).is_ok()
+    }
+
+    #[test]
+    fn ungranted_host_functions_are_not_linked() {
+        let caps = Capabilities::default();
+        assert!(!links(KV_KERNEL, &caps));
+        assert!(!links(LOG_KERNEL, &caps));
+        assert!(links(UNGATED_KERNEL, &caps));
+    }
+
+    #[test]
+    fn granted_host_functions_are_linked() {
+        let caps = Capabilities { kv: true, logging: true, ..Default::default() };
+        assert!(links(KV_KERNEL, &caps));
+        assert!(links(LOG_KERNEL, &caps));
+    }
+
+    fn pending() -> Option<PendingCache> {
+        Some(("acme|BLUE|catalog|GET|https://catalog/items|".to_string(), Default::default(), None))
+    }
+
+    fn cacheable(body: &str) -> EgressResponse {
+        EgressResponse {
+            status: 200,
+            headers: vec![("Cache-Control".into(), "max-age=60".into()), ("ETag".into(), "\"v1\"".into())],
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[tokio::test]
+    async fn faulted_answers_are_not_cached() {
+        let supervisor = WasmSupervisor::new(Arc::new(crate::telemetry::SamplingRatios::new())).await.unwrap();
+        let scope = ("acme", "BLUE", "catalog");
+
+        let served = complete_cached(&supervisor, pending(), Some(crate::chaos::Fault::Truncate), scope, cacheable("ite"));
+        assert_eq!(served.body, b"ite");
+        assert_eq!(supervisor.egress_cache.stats().entries, 0);
+
+        complete_cached(&supervisor, pending(), None, scope, cacheable("items"));
+        assert_eq!(supervisor.egress_cache.stats().entries, 1);
+    }
+
+    #[test]
+    fn a_grant_does_not_leak_into_other_capabilities() {
+        let caps = Capabilities { logging: true, ..Default::default() };
+        assert!(links(LOG_KERNEL, &caps));
+        assert!(!links(KV_KERNEL, &caps));
+    }
+}
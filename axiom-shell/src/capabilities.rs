@@ -0,0 +1,227 @@
+/// Capability Manifest — what each tomain declared in axiom.toml `[capabilities]`, via the CCP.
+/// `create_linker` only links host functions for granted capabilities, deploys are rejected when
+/// a kernel imports anything else, and alias lists are checked again on every call.
+use anyhow::{Result, anyhow};
+use dashmap::DashMap;
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+use wasmtime::Module;
+
+/// `axiom` host imports that need a capability, and which one. Anything else is always linked.
+const GATED_IMPORTS: &[(&str, &str)] = &[
+    ("http_call", "http"),
+    ("http_request", "http"),
+    ("db_execute", "db"),
+    ("tomain_call", "tomains"),
+    ("kv_get", "kv"),
+    ("kv_set", "kv"),
+    ("kv_delete", "kv"),
+    ("kv_cas", "kv"),
+    ("kv_scan", "kv"),
+    ("secret_get", "secrets"),
+    ("event_publish", "events"),
+    ("axiom_log", "logging"),
+    ("axiom_log_kv", "logging"),
+];
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Capabilities {
+    /// Egress aliases the kernel may call ("*" for any bound alias)
+    #[serde(default)]
+    pub http: Vec<String>,
+    /// Database aliases the kernel may query
+    #[serde(default)]
+    pub db: Vec<String>,
+    /// Tomains the kernel may call in-process
+    #[serde(default)]
+    pub tomains: Vec<String>,
+    #[serde(default)]
+    pub kv: bool,
+    #[serde(default)]
+    pub secrets: bool,
+    /// Event topics the kernel may publish and subscribe to
+    #[serde(default)]
+    pub events: EventCapabilities,
+    #[serde(default)]
+    pub logging: bool,
+}
+
+/// Topic patterns, where `*` matches one dot-separated token and `>` the rest.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EventCapabilities {
+    #[serde(default)]
+    pub publish: Vec<String>,
+    /// Topics the kernel's `#[axiom_subscribe]` handlers may listen on
+    #[serde(default)]
+    pub subscribe: Vec<String>,
+}
+
+impl Capabilities {
+    pub fn grants(&self, capability: &str) -> bool {
+        match capability {
+            "http" => !self.http.is_empty(),
+            "db" => !self.db.is_empty(),
+            "tomains" => !self.tomains.is_empty(),
+            "kv" => self.kv,
+            "secrets" => self.secrets,
+            "events" => !self.events.publish.is_empty(),
+            "logging" => self.logging,
+            _ => false,
+        }
+    }
+
+    pub fn allows_import(&self, name: &str) -> bool {
+        capability_for(name).is_none_or(|cap| self.grants(cap))
+    }
+
+    pub fn allows_topic(&self, topic: &str, publish: bool) -> bool {
+        let patterns = if publish { &self.events.publish } else { &self.events.subscribe };
+        patterns.iter().any(|p| crate::events::topic_matches(p, topic))
+    }
+}
+
+/// Whether `name` is in an alias list from the manifest.
+pub fn allows(list: &[String], name: &str) -> bool {
+    list.iter().any(|a| a == "*" || a == name)
+}
+
+pub fn capability_for(import: &str) -> Option<&'static str> {
+    GATED_IMPORTS.iter().find(|(name, _)| *name == import).map(|(_, cap)| *cap)
+}
+
+/// Rejects a module that imports host functions its tomain hasn't been granted.
+pub fn validate_imports(tomain_id: &str, module: &Module, caps: &Capabilities) -> Result<()> {
+    let denied: Vec<String> = module.imports()
+        .filter(|i| i.module() == "axiom" && !caps.allows_import(i.name()))
+        .map(|i| format!("axiom::{} (needs '{}')", i.name(), capability_for(i.name()).unwrap_or("unknown")))
+        .collect();
+    if denied.is_empty() {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "Kernel for {} imports capabilities not granted in axiom.toml [capabilities]: {}",
+        tomain_id,
+        denied.join(", ")
+    ))
+}
+
+pub struct CapabilityStore {
+    /// tomain_id -> declared capabilities
+    grants: DashMap<String, Arc<Capabilities>>,
+}
+
+impl CapabilityStore {
+    pub fn new() -> Self {
+        Self { grants: DashMap::new() }
+    }
+
+    /// Load the `capabilities` map from ~/.axiom/session.json.
+    pub fn reload_from_registry(&self) {
+        let path = dirs::home_dir()
+            .unwrap_or_default()
+            .join(".axiom")
+            .join("session.json");
+
+        let Ok(content) = std::fs::read_to_string(&path) else { return };
+        let Ok(json) = serde_json::from_str::<Value>(&content) else { return };
+
+        self.grants.clear();
+        if let Some(all) = json.get("capabilities").and_then(|c| c.as_object()) {
+            for (tomain_id, caps) in all {
+                match serde_json::from_value::<Capabilities>(caps.clone()) {
+                    Ok(caps) => { self.grants.insert(tomain_id.clone(), Arc::new(caps)); }
+                    Err(e) => warn!("Invalid capabilities for {}: {}", tomain_id, e),
+                }
+            }
+        }
+        info!("🧱 Capabilities: Loaded manifests for {} tomains", self.grants.len());
+    }
+
+    /// A tomain that never declared capabilities is granted nothing.
+    pub fn get(&self, tomain_id: &str) -> Arc<Capabilities> {
+        self.grants.get(tomain_id).map(|c| c.value().clone()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module(imports: &str) -> Module {
+        let engine = crate::supervisor::TenantManager::new().create_engine().unwrap();
+        Module::new(&engine, format!("(module {})", imports)).unwrap()
+    }
+
+    #[test]
+    fn empty_manifest_grants_nothing() {
+        let caps = Capabilities::default();
+        for (import, _) in GATED_IMPORTS {
+            assert!(!caps.allows_import(import), "{} should be gated", import);
+        }
+        assert!(caps.allows_import("get_family_token"));
+        assert!(caps.allows_import("config_get"));
+    }
+
+    #[test]
+    fn each_grant_opens_only_its_imports() {
+        let caps = Capabilities { kv: true, db: vec!["main".into()], ..Default::default() };
+        assert!(caps.allows_import("kv_get"));
+        assert!(caps.allows_import("kv_scan"));
+        assert!(caps.allows_import("db_execute"));
+        assert!(!caps.allows_import("http_request"));
+        assert!(!caps.allows_import("secret_get"));
+    }
+
+    #[test]
+    fn events_grant_follows_publish_patterns() {
+        let mut caps = Capabilities::default();
+        caps.events.subscribe = vec!["orders.*".into()];
+        assert!(!caps.grants("events"));
+        assert!(caps.allows_topic("orders.created", false));
+        assert!(!caps.allows_topic("orders.created", true));
+
+        caps.events.publish = vec!["orders.>".into()];
+        assert!(caps.grants("events"));
+        assert!(caps.allows_topic("orders.eu.created", true));
+        assert!(!caps.allows_topic("billing.paid", true));
+    }
+
+    #[test]
+    fn alias_lists_accept_exact_names_and_wildcard() {
+        assert!(allows(&["stripe".into()], "stripe"));
+        assert!(!allows(&["stripe".into()], "github"));
+        assert!(allows(&["*".into()], "github"));
+        assert!(!allows(&[], "github"));
+    }
+
+    #[test]
+    fn validate_imports_names_every_ungranted_import() {
+        let kernel = module(r#"
+            (import "axiom" "kv_get" (func (param i32 i32) (result i32)))
+            (import "axiom" "http_request" (func (param i32 i32 i32 i32) (result i32)))
+            (import "axiom" "config_get" (func (param i32 i32) (result i32)))
+        "#);
+
+        let err = validate_imports("acme", &kernel, &Capabilities::default()).unwrap_err().to_string();
+        assert!(err.contains("axiom::kv_get (needs 'kv')"), "{}", err);
+        assert!(err.contains("axiom::http_request (needs 'http')"), "{}", err);
+        assert!(!err.contains("config_get"), "{}", err);
+
+        let caps = Capabilities { kv: true, http: vec!["*".into()], ..Default::default() };
+        assert!(validate_imports("acme", &kernel, &caps).is_ok());
+    }
+
+    #[test]
+    fn validate_imports_ignores_other_modules() {
+        let kernel = module(r#"(import "wasi_snapshot_preview1" "proc_exit" (func (param i32)))"#);
+        assert!(validate_imports("acme", &kernel, &Capabilities::default()).is_ok());
+    }
+
+    #[test]
+    fn store_treats_unknown_tomains_as_empty() {
+        let store = CapabilityStore::new();
+        assert!(!store.get("nobody").grants("kv"));
+    }
+}
@@ -0,0 +1,332 @@
+/// Chaos — fault injection on egress bindings, so teams can watch kernels cope with a misbehaving
+/// dependency without touching the real one. Rules come from session.json `fault_injection`
+/// (tomain → env → alias) and apply inside `http_call`/`http_request` and `db_execute`.
+/// PROD traffic (the live GREEN and RED perspectives) is never affected unless the rule sets `force`.
+use dashmap::DashMap;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FaultRule {
+    pub enabled: bool,
+    /// Added before every call
+    pub latency_ms: u64,
+    /// Extra random latency on top, 0..=latency_jitter_ms
+    pub latency_jitter_ms: u64,
+    /// Share of calls (0.0-1.0) answered with one of `error_statuses` instead of reaching the downstream
+    pub error_rate: f64,
+    pub error_statuses: Vec<u16>,
+    /// Share of calls failed as a dropped connection
+    pub reset_rate: f64,
+    /// Share of calls whose body (or result rows) is cut in half
+    pub truncate_rate: f64,
+    /// Required for the rule to apply in PROD
+    pub force: bool,
+}
+
+impl Default for FaultRule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            latency_ms: 0,
+            latency_jitter_ms: 0,
+            error_rate: 0.0,
+            error_statuses: vec![503],
+            reset_rate: 0.0,
+            truncate_rate: 0.0,
+            force: false,
+        }
+    }
+}
+
+impl FaultRule {
+    /// The checks the CCP applies on write, repeated because session.json can be edited by hand.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(status) = self.error_statuses.iter().find(|s| !(100..=599).contains(*s)) {
+            return Err(format!("error_statuses must be HTTP status codes, got {}", status));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fault {
+    Error(u16),
+    Reset,
+    Truncate,
+}
+
+impl Fault {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Fault::Error(_) => "error",
+            Fault::Reset => "reset",
+            Fault::Truncate => "truncate",
+        }
+    }
+}
+
+/// What to do to one call: wait `delay`, then misbehave per `fault` (if any).
+#[derive(Debug, Clone)]
+pub struct Injection {
+    pub delay: Duration,
+    pub fault: Option<Fault>,
+}
+
+impl std::fmt::Display for Injection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.fault {
+            Some(Fault::Error(status)) => write!(f, "+{}ms, status {}", self.delay.as_millis(), status),
+            Some(fault) => write!(f, "+{}ms, {}", self.delay.as_millis(), fault.label()),
+            None => write!(f, "+{}ms", self.delay.as_millis()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FaultRuleStatus {
+    pub tomain_id: String,
+    pub environment: String,
+    pub alias: String,
+    pub rule: FaultRule,
+    /// False when the rule targets PROD without `force`
+    pub active: bool,
+    /// True when the rule injects into PROD traffic (a forced PROD or GLOBAL rule)
+    pub prod: bool,
+}
+
+pub struct FaultInjector {
+    /// (tomain_id, registry env, alias) -> rule; "GLOBAL" applies to every environment without its own
+    rules: DashMap<(String, String, String), FaultRule>,
+}
+
+impl FaultInjector {
+    pub fn new() -> Self {
+        Self { rules: DashMap::new() }
+    }
+
+    /// Load `fault_injection` from ~/.axiom/session.json. Returns the rules that were added,
+    /// changed or removed as (tomain_id, description) so the caller can audit them.
+    pub fn reload_from_registry(&self) -> Vec<(String, String)> {
+        let path = dirs::home_dir()
+            .unwrap_or_default()
+            .join(".axiom")
+            .join("session.json");
+
+        let Ok(content) = std::fs::read_to_string(&path) else { return Vec::new() };
+        let Ok(json) = serde_json::from_str::<Value>(&content) else { return Vec::new() };
+
+        let mut loaded = HashMap::new();
+        if let Some(all) = json.get("fault_injection").and_then(|c| c.as_object()) {
+            for (tomain_id, envs) in all {
+                for (env, aliases) in envs.as_object().into_iter().flatten() {
+                    for (alias, rule) in aliases.as_object().into_iter().flatten() {
+                        let parsed = serde_json::from_value::<FaultRule>(rule.clone())
+                            .map_err(|e| e.to_string())
+                            .and_then(|rule| rule.validate().map(|_| rule));
+                        match parsed {
+                            Ok(rule) => { loaded.insert((tomain_id.clone(), crate::runtime::registry_environment(env), alias.clone()), rule); }
+                            Err(e) => warn!("Invalid fault injection rule for {} ({}/{}): {}", tomain_id, env, alias, e),
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut changes = Vec::new();
+        for (key, rule) in &loaded {
+            let (tomain_id, env, alias) = key;
+            if self.rules.get(key).is_none_or(|old| *old != *rule) {
+                changes.push((tomain_id.clone(), format!("CHAOS_RULE_SET {} {} {:?}", env, alias, rule)));
+            }
+        }
+        for entry in self.rules.iter() {
+            let (tomain_id, env, alias) = entry.key();
+            if !loaded.contains_key(entry.key()) {
+                changes.push((tomain_id.clone(), format!("CHAOS_RULE_CLEARED {} {}", env, alias)));
+            }
+        }
+
+        self.rules.clear();
+        for (key, rule) in loaded {
+            self.rules.insert(key, rule);
+        }
+        if !self.rules.is_empty() {
+            info!("🐒 Chaos: Loaded {} fault injection rules", self.rules.len());
+        }
+        changes
+    }
+
+    /// Rolls the dice for one call. None when no enabled rule covers it. `environment` may be
+    /// a perspective; it is checked as the registry environment it serves.
+    pub fn inject(&self, tomain_id: &str, environment: &str, alias: &str) -> Option<Injection> {
+        let env = crate::runtime::registry_environment(environment);
+        let key = |env: &str| (tomain_id.to_string(), env.to_string(), alias.to_string());
+        let rule = self.rules.get(&key(&env))
+            .or_else(|| self.rules.get(&key("GLOBAL")))
+            .map(|r| r.value().clone())?;
+        if !rule.enabled || (env == "PROD" && !rule.force) {
+            return None;
+        }
+
+        let mut rng = rand::thread_rng();
+        let jitter = if rule.latency_jitter_ms > 0 { rng.gen_range(0..=rule.latency_jitter_ms) } else { 0 };
+        let roll = rand::random::<f64>();
+        let error_rate = rule.error_rate.clamp(0.0, 1.0);
+        let reset_rate = rule.reset_rate.clamp(0.0, 1.0);
+        let fault = if roll < error_rate {
+            let statuses = if rule.error_statuses.is_empty() { vec![503] } else { rule.error_statuses.clone() };
+            Some(Fault::Error(statuses[rng.gen_range(0..statuses.len())]))
+        } else if roll < error_rate + reset_rate {
+            Some(Fault::Reset)
+        } else if rand::random::<f64>() < rule.truncate_rate {
+            Some(Fault::Truncate)
+        } else {
+            None
+        };
+
+        let delay = Duration::from_millis(rule.latency_ms + jitter);
+        if delay.is_zero() && fault.is_none() {
+            return None;
+        }
+        Some(Injection { delay, fault })
+    }
+
+    pub fn statuses(&self) -> Vec<FaultRuleStatus> {
+        self.rules.iter().map(|entry| {
+            let (tomain_id, environment, alias) = entry.key().clone();
+            let rule = entry.value().clone();
+            FaultRuleStatus {
+                active: rule.enabled && (environment != "PROD" || rule.force),
+                prod: rule.enabled && rule.force && (environment == "PROD" || environment == "GLOBAL"),
+                tomain_id,
+                environment,
+                alias,
+                rule,
+            }
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_rules(rules: &[(&str, FaultRule)]) -> FaultInjector {
+        let injector = FaultInjector::new();
+        for (env, rule) in rules {
+            injector.rules.insert(("acme".into(), env.to_string(), "stripe".into()), rule.clone());
+        }
+        injector
+    }
+
+    fn always_failing(force: bool) -> FaultRule {
+        FaultRule { error_rate: 1.0, error_statuses: vec![502], force, ..Default::default() }
+    }
+
+    fn fault(injector: &FaultInjector, env: &str) -> Option<Fault> {
+        injector.inject("acme", env, "stripe").and_then(|i| i.fault)
+    }
+
+    #[test]
+    fn global_rule_covers_environments_without_their_own() {
+        let injector = with_rules(&[("GLOBAL", always_failing(false))]);
+        assert_eq!(fault(&injector, "dev"), Some(Fault::Error(502)));
+        assert_eq!(fault(&injector, "STAGING"), Some(Fault::Error(502)));
+        assert!(injector.inject("acme", "dev", "github").is_none());
+        assert!(injector.inject("globex", "dev", "stripe").is_none());
+    }
+
+    #[test]
+    fn environment_rule_takes_precedence_over_global() {
+        let reset = FaultRule { reset_rate: 1.0, ..Default::default() };
+        let injector = with_rules(&[("GLOBAL", always_failing(false)), ("DEV", reset)]);
+        assert_eq!(fault(&injector, "dev"), Some(Fault::Reset));
+        assert_eq!(fault(&injector, "staging"), Some(Fault::Error(502)));
+    }
+
+    #[test]
+    fn disabled_environment_rule_shadows_global() {
+        let off = FaultRule { enabled: false, ..always_failing(false) };
+        let injector = with_rules(&[("GLOBAL", always_failing(false)), ("DEV", off)]);
+        assert!(injector.inject("acme", "dev", "stripe").is_none());
+    }
+
+    #[test]
+    fn prod_is_untouched_without_force() {
+        let injector = with_rules(&[("GLOBAL", always_failing(false))]);
+        assert!(injector.inject("acme", "prod", "stripe").is_none());
+
+        let injector = with_rules(&[("PROD", always_failing(false))]);
+        assert!(injector.inject("acme", "PROD", "stripe").is_none());
+    }
+
+    #[test]
+    fn forced_rules_apply_in_prod() {
+        let injector = with_rules(&[("PROD", always_failing(true))]);
+        assert_eq!(fault(&injector, "prod"), Some(Fault::Error(502)));
+
+        // A forced GLOBAL rule reaches PROD too, unless PROD has its own unforced rule
+        let injector = with_rules(&[("GLOBAL", always_failing(true))]);
+        assert_eq!(fault(&injector, "prod"), Some(Fault::Error(502)));
+        let injector = with_rules(&[("GLOBAL", always_failing(true)), ("PROD", always_failing(false))]);
+        assert!(injector.inject("acme", "prod", "stripe").is_none());
+    }
+
+    #[test]
+    fn live_perspectives_are_guarded_as_prod() {
+        // egress_call passes the tomain's perspective, GREEN unless switched
+        let injector = with_rules(&[("GLOBAL", always_failing(false))]);
+        assert!(injector.inject("acme", "GREEN", "stripe").is_none());
+        assert!(injector.inject("acme", "RED", "stripe").is_none());
+        assert_eq!(fault(&injector, "BLUE"), Some(Fault::Error(502)));
+
+        let injector = with_rules(&[("PROD", always_failing(true))]);
+        assert_eq!(fault(&injector, "GREEN"), Some(Fault::Error(502)));
+    }
+
+    #[test]
+    fn pre_release_perspective_uses_staging_rules() {
+        let reset = FaultRule { reset_rate: 1.0, ..Default::default() };
+        let injector = with_rules(&[("GLOBAL", always_failing(false)), ("STAGING", reset)]);
+        assert_eq!(fault(&injector, "BLUE"), Some(Fault::Reset));
+    }
+
+    #[test]
+    fn rules_with_out_of_range_statuses_are_invalid() {
+        assert!(always_failing(false).validate().is_ok());
+        for status in [0, 99, 600, 1000] {
+            let rule = FaultRule { error_statuses: vec![503, status], ..Default::default() };
+            assert!(rule.validate().is_err(), "status {} accepted", status);
+        }
+    }
+
+    #[test]
+    fn latency_only_rules_delay_without_a_fault() {
+        let injector = with_rules(&[("DEV", FaultRule { latency_ms: 250, ..Default::default() })]);
+        let injection = injector.inject("acme", "dev", "stripe").unwrap();
+        assert_eq!(injection.delay, Duration::from_millis(250));
+        assert!(injection.fault.is_none());
+    }
+
+    #[test]
+    fn statuses_flag_unforced_prod_rules_inactive() {
+        let injector = with_rules(&[("PROD", always_failing(false)), ("DEV", always_failing(false))]);
+        let mut statuses = injector.statuses();
+        statuses.sort_by(|a, b| a.environment.cmp(&b.environment));
+        assert_eq!(statuses.iter().map(|s| (s.environment.as_str(), s.active)).collect::<Vec<_>>(), vec![("DEV", true), ("PROD", false)]);
+        assert!(statuses.iter().all(|s| !s.prod));
+    }
+
+    #[test]
+    fn statuses_flag_forced_global_rules_as_prod() {
+        let injector = with_rules(&[("GLOBAL", always_failing(true))]);
+        let status = &injector.statuses()[0];
+        assert!(status.active && status.prod);
+    }
+}
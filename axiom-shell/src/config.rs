@@ -0,0 +1,73 @@
+/// Config Store — non-secret, per-environment configuration for kernels (tomain → env → values).
+/// Each invocation snapshots its slot's set when the store is created, so a config change applies
+/// to the next call without a redeploy and a single call never sees two versions.
+use dashmap::DashMap;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConfigSet {
+    /// Bumped by the CCP on every change
+    #[serde(default)]
+    pub version: u64,
+    #[serde(default)]
+    pub values: HashMap<String, Value>,
+}
+
+impl ConfigSet {
+    /// WASI environment variables: strings as-is, everything else as JSON.
+    pub fn env_vars(&self) -> Vec<(String, String)> {
+        let mut vars: Vec<(String, String)> = self.values.iter()
+            .map(|(k, v)| (k.clone(), v.as_str().map(|s| s.to_string()).unwrap_or_else(|| v.to_string())))
+            .collect();
+        vars.push(("AXIOM_CONFIG_VERSION".to_string(), self.version.to_string()));
+        vars
+    }
+}
+
+pub struct ConfigStore {
+    /// (tomain_id, environment) -> config set
+    sets: DashMap<(String, String), Arc<ConfigSet>>,
+}
+
+impl ConfigStore {
+    pub fn new() -> Self {
+        Self { sets: DashMap::new() }
+    }
+
+    /// Load the `config` map from ~/.axiom/session.json.
+    pub fn reload_from_registry(&self) {
+        let path = dirs::home_dir()
+            .unwrap_or_default()
+            .join(".axiom")
+            .join("session.json");
+
+        let Ok(content) = std::fs::read_to_string(&path) else { return };
+        let Ok(json) = serde_json::from_str::<Value>(&content) else { return };
+
+        self.sets.clear();
+        if let Some(all) = json.get("config").and_then(|c| c.as_object()) {
+            for (tomain_id, envs) in all {
+                for (env, set) in envs.as_object().into_iter().flatten() {
+                    match serde_json::from_value::<ConfigSet>(set.clone()) {
+                        Ok(set) => { self.sets.insert((tomain_id.clone(), env.to_uppercase()), Arc::new(set)); }
+                        Err(e) => warn!("Invalid config for {} ({}): {}", tomain_id, env, e),
+                    }
+                }
+            }
+        }
+        info!("🎛️ Config: Loaded {} environment config sets", self.sets.len());
+    }
+
+    pub fn get(&self, tomain_id: &str, environment: &str) -> Option<Arc<ConfigSet>> {
+        self.sets.get(&(tomain_id.to_string(), environment.to_uppercase())).map(|s| s.value().clone())
+    }
+
+    /// Version new invocations in this slot will see; 0 when the slot has no config.
+    pub fn version(&self, tomain_id: &str, environment: &str) -> u64 {
+        self.get(tomain_id, environment).map(|s| s.version).unwrap_or(0)
+    }
+}
@@ -2,15 +2,149 @@
 /// Updated by CCP via POST /admin/reload-bindings without any restart.
 use anyhow::{Result, anyhow};
 use dashmap::DashMap;
+use serde::Deserialize;
 use std::sync::Arc;
 use serde_json::Value;
 use tracing::{info, warn};
 
+/// Why an egress call produced no response. Sent to the guest as `{"kind": "<Kind>", "message": ...}`,
+/// and rendered as `Error: <Kind>: <detail>` on the legacy `http_call` ABI.
+#[derive(Debug, Clone)]
+pub enum EgressError {
+    /// The alias has no binding in the caller's environment
+    NoBinding(String),
+    /// A raw URL, alias or request shape was refused by policy
+    PolicyDenied(String),
+    /// The downstream rate limiter rejected the call
+    RateLimited(String),
+    /// The alias's circuit breaker is open
+    CircuitOpen(String),
+    /// The alias's egress bulkhead is full
+    Overloaded(String),
+    /// The last attempt exceeded its deadline
+    Timeout(String),
+    /// The last attempt failed below HTTP (DNS, connect, TLS, reset)
+    Transport(String),
+    /// The Shell couldn't obtain credentials for the binding (e.g. its OAuth2 token endpoint failed)
+    Auth(String),
+    /// Several attempts were made and none got a response; a retryable status that is still
+    /// there on the last attempt is returned as the response instead
+    RetriesExhausted(String),
+}
+
+impl EgressError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            EgressError::NoBinding(_) => "NoBinding",
+            EgressError::PolicyDenied(_) => "PolicyDenied",
+            EgressError::RateLimited(_) => "RateLimited",
+            EgressError::CircuitOpen(_) => "CircuitOpen",
+            EgressError::Overloaded(_) => "Overloaded",
+            EgressError::Timeout(_) => "Timeout",
+            EgressError::Transport(_) => "Transport",
+            EgressError::Auth(_) => "Auth",
+            EgressError::RetriesExhausted(_) => "RetriesExhausted",
+        }
+    }
+
+    pub fn detail(&self) -> &str {
+        match self {
+            EgressError::NoBinding(d)
+            | EgressError::PolicyDenied(d)
+            | EgressError::RateLimited(d)
+            | EgressError::CircuitOpen(d)
+            | EgressError::Overloaded(d)
+            | EgressError::Timeout(d)
+            | EgressError::Transport(d)
+            | EgressError::Auth(d)
+            | EgressError::RetriesExhausted(d) => d,
+        }
+    }
+}
+
+impl std::fmt::Display for EgressError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.kind(), self.detail())
+    }
+}
+
+/// What a downstream answered, whatever the status.
+#[derive(Debug, Clone)]
+pub struct EgressResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+/// Which raw URLs (as opposed to bound aliases) a tomain may call in one environment.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EgressPolicy {
+    /// Raw URLs are refused outright unless this is set
+    #[serde(default)]
+    pub allow_raw_urls: bool,
+    /// Exact hostnames, "*.example.com" for subdomains, or "*"
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    /// Allowed explicit ports; empty = only the scheme's default port
+    #[serde(default)]
+    pub ports: Vec<u16>,
+    /// Allowed schemes; empty = https only
+    #[serde(default)]
+    pub schemes: Vec<String>,
+}
+
+impl EgressPolicy {
+    pub fn check(&self, raw_url: &str) -> Result<(), String> {
+        if !self.allow_raw_urls {
+            return Err("raw URLs are not allowed".to_string());
+        }
+        let url = url::Url::parse(raw_url).map_err(|e| format!("malformed URL: {}", e))?;
+
+        let scheme_ok = if self.schemes.is_empty() {
+            url.scheme() == "https"
+        } else {
+            self.schemes.iter().any(|s| s.eq_ignore_ascii_case(url.scheme()))
+        };
+        if !scheme_ok {
+            return Err(format!("scheme '{}' is not allowed", url.scheme()));
+        }
+
+        let host = url.host_str().ok_or_else(|| "URL has no host".to_string())?.to_lowercase();
+        if !self.hosts.iter().any(|pattern| host_matches(pattern, &host)) {
+            return Err(format!("host '{}' is not on the allowlist", host));
+        }
+
+        // `Url::port` is None when the port is absent or the scheme's default
+        if let Some(port) = url.port() && !self.ports.contains(&port) {
+            return Err(format!("port {} is not allowed", port));
+        }
+        Ok(())
+    }
+}
+
+fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    if pattern == "*" {
+        return true;
+    }
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => host.ends_with(&format!(".{}", suffix)),
+        None => host == pattern,
+    }
+}
+
+/// Aliases are logical names; anything with a scheme is a raw URL subject to the egress policy.
+pub fn is_raw_url(alias: &str) -> bool {
+    alias.contains("://")
+}
+
 pub struct EgressResolver {
     /// (tomain_id, env, alias) → physical_url — hot-updated by CCP
     pub bindings: Arc<DashMap<(String, String, String), String>>,
     /// tomain_id → { logical_name → alias_name (@main-db) }
     pub manifests: Arc<DashMap<(String, String), String>>,
+    /// (tomain_id, env) → raw URL policy; "GLOBAL" applies to every environment without its own
+    pub policies: Arc<DashMap<(String, String), EgressPolicy>>,
 }
 
 impl EgressResolver {
@@ -18,6 +152,7 @@ impl EgressResolver {
         Self {
             bindings: Arc::new(DashMap::new()),
             manifests: Arc::new(DashMap::new()),
+            policies: Arc::new(DashMap::new()),
         }
     }
 
@@ -66,7 +201,18 @@ impl EgressResolver {
                                 }
                             }
                         }
-                        info!("🔄 Egress: Reloaded {} bindings and {} manifests from session registry", self.bindings.len(), self.manifests.len());
+                        self.policies.clear();
+                        if let Some(all_policies) = json.get("egress_policies").and_then(|p| p.as_object()) {
+                            for (tomain_id, env_map) in all_policies {
+                                for (env, policy) in env_map.as_object().into_iter().flatten() {
+                                    match serde_json::from_value::<EgressPolicy>(policy.clone()) {
+                                        Ok(policy) => { self.policies.insert((tomain_id.clone(), env.to_uppercase()), policy); }
+                                        Err(e) => warn!("Invalid egress policy for {} ({}): {}", tomain_id, env, e),
+                                    }
+                                }
+                            }
+                        }
+                        info!("🔄 Egress: Reloaded {} bindings, {} manifests and {} policies from session registry", self.bindings.len(), self.manifests.len(), self.policies.len());
                     }
                     Err(e) => warn!("Failed to parse session.json: {}", e),
                 }
@@ -103,4 +249,16 @@ impl EgressResolver {
             }
         }
     }
+
+    /// Checks a raw URL against the tomain's policy for `environment`, falling back to GLOBAL.
+    /// Without any policy raw URLs are denied.
+    pub fn check_raw_url(&self, tomain_id: &str, environment: &str, raw_url: &str) -> Result<(), EgressError> {
+        let policy = self.policies.get(&(tomain_id.to_string(), environment.to_uppercase()))
+            .or_else(|| self.policies.get(&(tomain_id.to_string(), "GLOBAL".to_string())));
+        let verdict = match policy {
+            Some(policy) => policy.check(raw_url),
+            None => Err("no egress policy allows raw URLs".to_string()),
+        };
+        verdict.map_err(|reason| EgressError::PolicyDenied(format!("{} for {} in {}", reason, tomain_id, environment)))
+    }
 }
@@ -0,0 +1,445 @@
+/// Egress Cache — opt-in response cache for GET/HEAD calls through the Egress Guard.
+/// Only aliases with a policy in session.json `egress_cache` (tomain → env → alias) are cached.
+/// Freshness follows the downstream's `Cache-Control` unless the policy sets a TTL; stale entries
+/// with an `ETag` or `Last-Modified` are revalidated with a conditional request. Keys are scoped by
+/// tomain, environment, method, URL and the policy's vary headers, and the whole cache is size-bounded.
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracing::{info, warn};
+use crate::egress::EgressResponse;
+
+/// Bytes of response bodies kept across all entries before the least recently used are evicted.
+const MAX_CACHE_BYTES: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CachePolicy {
+    /// Replaces the downstream's max-age; `no-store` is still honored
+    pub ttl_secs: Option<u64>,
+    /// Upper bound on any freshness lifetime
+    pub max_ttl_secs: u64,
+    /// Request headers whose values are part of the cache key
+    pub vary_headers: Vec<String>,
+    /// Larger bodies are passed through uncached
+    pub max_entry_bytes: usize,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self {
+            ttl_secs: None,
+            max_ttl_secs: 3600,
+            vary_headers: Vec::new(),
+            max_entry_bytes: 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CachedEntry {
+    pub tomain_id: String,
+    pub environment: String,
+    pub alias: String,
+    pub response: EgressResponse,
+    pub expires_at: DateTime<Utc>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub last_used: DateTime<Utc>,
+}
+
+impl CachedEntry {
+    pub fn is_fresh(&self) -> bool {
+        Utc::now() < self.expires_at
+    }
+
+    /// `If-None-Match` / `If-Modified-Since` for revalidating a stale entry.
+    pub fn validators(&self) -> Vec<(&'static str, String)> {
+        let mut headers = Vec::new();
+        if let Some(etag) = &self.etag {
+            headers.push(("If-None-Match", etag.clone()));
+        }
+        if let Some(modified) = &self.last_modified {
+            headers.push(("If-Modified-Since", modified.clone()));
+        }
+        headers
+    }
+}
+
+/// Where a cacheable call stands before it goes out.
+pub enum CacheLookup {
+    Fresh(EgressResponse),
+    /// Stale (or absent) entry; send the validators, if any, and finish with `EgressCache::complete`
+    Miss { key: String, policy: CachePolicy, stale: Option<Box<CachedEntry>> },
+}
+
+/// What `complete` did with a downstream response, for metrics.
+pub enum CacheOutcome {
+    Stored,
+    Revalidated,
+    Bypassed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub bytes: usize,
+    pub max_bytes: usize,
+}
+
+pub struct EgressCache {
+    /// (tomain_id, env, alias) -> policy; "GLOBAL" applies to every environment without its own
+    policies: DashMap<(String, String, String), CachePolicy>,
+    entries: DashMap<String, CachedEntry>,
+    bytes: AtomicUsize,
+}
+
+impl EgressCache {
+    pub fn new() -> Self {
+        Self {
+            policies: DashMap::new(),
+            entries: DashMap::new(),
+            bytes: AtomicUsize::new(0),
+        }
+    }
+
+    /// Load the `egress_cache` map from ~/.axiom/session.json. Cached entries survive a reload.
+    pub fn reload_from_registry(&self) {
+        let path = dirs::home_dir()
+            .unwrap_or_default()
+            .join(".axiom")
+            .join("session.json");
+
+        let Ok(content) = std::fs::read_to_string(&path) else { return };
+        let Ok(json) = serde_json::from_str::<Value>(&content) else { return };
+
+        self.policies.clear();
+        if let Some(all) = json.get("egress_cache").and_then(|c| c.as_object()) {
+            for (tomain_id, envs) in all {
+                for (env, aliases) in envs.as_object().into_iter().flatten() {
+                    for (alias, policy) in aliases.as_object().into_iter().flatten() {
+                        match serde_json::from_value::<CachePolicy>(policy.clone()) {
+                            Ok(policy) => { self.policies.insert((tomain_id.clone(), env.to_uppercase(), alias.clone()), policy); }
+                            Err(e) => warn!("Invalid egress cache policy for {} ({}/{}): {}", tomain_id, env, alias, e),
+                        }
+                    }
+                }
+            }
+        }
+        info!("🗄️ Egress cache: Loaded {} alias policies", self.policies.len());
+    }
+
+    fn policy(&self, tomain_id: &str, environment: &str, alias: &str) -> Option<CachePolicy> {
+        let key = |env: &str| (tomain_id.to_string(), env.to_string(), alias.to_string());
+        self.policies.get(&key(&environment.to_uppercase()))
+            .or_else(|| self.policies.get(&key("GLOBAL")))
+            .map(|p| p.value().clone())
+    }
+
+    /// None when the call isn't cacheable: the alias has no policy or the method isn't GET/HEAD.
+    pub fn lookup(
+        &self,
+        tomain_id: &str,
+        environment: &str,
+        alias: &str,
+        method: &str,
+        url: &str,
+        headers: &reqwest::header::HeaderMap,
+    ) -> Option<CacheLookup> {
+        if method != "GET" && method != "HEAD" {
+            return None;
+        }
+        let policy = self.policy(tomain_id, environment, alias)?;
+        let vary: Vec<String> = policy.vary_headers.iter()
+            .map(|h| format!("{}={}", h.to_lowercase(), headers.get(h.as_str()).and_then(|v| v.to_str().ok()).unwrap_or("")))
+            .collect();
+        let key = format!("{}|{}|{}|{}|{}|{}", tomain_id, environment, alias, method, url, vary.join("&"));
+
+        let stale = match self.entries.get_mut(&key) {
+            Some(mut entry) => {
+                entry.last_used = Utc::now();
+                if entry.is_fresh() {
+                    return Some(CacheLookup::Fresh(entry.response.clone()));
+                }
+                Some(Box::new(entry.clone()))
+            }
+            None => None,
+        };
+        Some(CacheLookup::Miss { key, policy, stale })
+    }
+
+    /// Finishes a cacheable call: a 304 refreshes and serves the stale entry, a cacheable 2xx is stored.
+    pub fn complete(
+        &self,
+        key: String,
+        policy: &CachePolicy,
+        stale: Option<Box<CachedEntry>>,
+        (tomain_id, environment, alias): (&str, &str, &str),
+        response: EgressResponse,
+    ) -> (EgressResponse, CacheOutcome) {
+        let header = |name: &str| response.headers.iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.clone());
+        let ttl = freshness(header("cache-control").as_deref(), policy);
+
+        if response.status == 304 && let Some(mut entry) = stale {
+            entry.expires_at = Utc::now() + chrono::Duration::seconds(ttl.unwrap_or(0) as i64);
+            entry.last_used = Utc::now();
+            let served = entry.response.clone();
+            self.entries.insert(key, *entry);
+            return (served, CacheOutcome::Revalidated);
+        }
+
+        let (etag, last_modified) = (header("etag"), header("last-modified"));
+        let storable = (200..300).contains(&response.status)
+            && response.body.len() <= policy.max_entry_bytes
+            // Nothing to serve fresh and nothing to revalidate with
+            && (ttl.is_some_and(|t| t > 0) || etag.is_some() || last_modified.is_some());
+        let Some(ttl) = ttl.filter(|_| storable) else {
+            return (response, CacheOutcome::Bypassed);
+        };
+
+        let size = response.body.len();
+        if let Some(old) = self.entries.insert(key, CachedEntry {
+            tomain_id: tomain_id.to_string(),
+            environment: environment.to_string(),
+            alias: alias.to_string(),
+            response: response.clone(),
+            expires_at: Utc::now() + chrono::Duration::seconds(ttl as i64),
+            etag,
+            last_modified,
+            last_used: Utc::now(),
+        }) {
+            self.bytes.fetch_sub(old.response.body.len(), Ordering::Relaxed);
+        }
+        self.bytes.fetch_add(size, Ordering::Relaxed);
+        self.evict();
+        (response, CacheOutcome::Stored)
+    }
+
+    /// Drops least recently used entries until the cache fits in `MAX_CACHE_BYTES`.
+    fn evict(&self) {
+        while self.bytes.load(Ordering::Relaxed) > MAX_CACHE_BYTES {
+            let oldest = self.entries.iter()
+                .min_by_key(|e| e.last_used)
+                .map(|e| e.key().clone());
+            let Some(key) = oldest else { break };
+            if let Some((_, entry)) = self.entries.remove(&key) {
+                self.bytes.fetch_sub(entry.response.body.len(), Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Removes entries matching every filter given; returns how many were purged.
+    pub fn purge(&self, tomain_id: Option<&str>, environment: Option<&str>, alias: Option<&str>) -> usize {
+        let mut purged = 0;
+        self.entries.retain(|_, entry| {
+            let matches = tomain_id.is_none_or(|t| entry.tomain_id == t)
+                && environment.is_none_or(|e| entry.environment.eq_ignore_ascii_case(e))
+                && alias.is_none_or(|a| entry.alias == a);
+            if matches {
+                self.bytes.fetch_sub(entry.response.body.len(), Ordering::Relaxed);
+                purged += 1;
+            }
+            !matches
+        });
+        purged
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.len(),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            max_bytes: MAX_CACHE_BYTES,
+        }
+    }
+}
+
+/// Freshness lifetime in seconds, or None when the response must not be stored.
+/// `no-store` and `private` win; `no-cache` stores for revalidation only.
+fn freshness(cache_control: Option<&str>, policy: &CachePolicy) -> Option<u64> {
+    let directives: Vec<String> = cache_control.unwrap_or("")
+        .split(',')
+        .map(|d| d.trim().to_lowercase())
+        .collect();
+    if directives.iter().any(|d| d == "no-store" || d == "private") {
+        return None;
+    }
+    let max_age = |name: &str| directives.iter()
+        .find_map(|d| d.strip_prefix(name).and_then(|v| v.strip_prefix('=')).and_then(|v| v.parse::<u64>().ok()));
+
+    let ttl = if directives.iter().any(|d| d == "no-cache") {
+        0
+    } else {
+        policy.ttl_secs
+            .or_else(|| max_age("s-maxage"))
+            .or_else(|| max_age("max-age"))
+            .unwrap_or(0)
+    };
+    Some(ttl.min(policy.max_ttl_secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderMap;
+
+    const SCOPE: (&str, &str, &str) = ("acme", "DEV", "catalog");
+
+    fn response(status: u16, headers: &[(&str, &str)], body: &str) -> EgressResponse {
+        EgressResponse {
+            status,
+            headers: headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    fn cache_with(policy: CachePolicy) -> EgressCache {
+        let cache = EgressCache::new();
+        cache.policies.insert(("acme".into(), "GLOBAL".into(), "catalog".into()), policy);
+        cache
+    }
+
+    fn miss(cache: &EgressCache) -> (String, CachePolicy, Option<Box<CachedEntry>>) {
+        match cache.lookup("acme", "DEV", "catalog", "GET", "https://catalog/items", &HeaderMap::new()) {
+            Some(CacheLookup::Miss { key, policy, stale }) => (key, policy, stale),
+            Some(CacheLookup::Fresh(_)) => panic!("expected a miss, got a fresh hit"),
+            None => panic!("expected the call to be cacheable"),
+        }
+    }
+
+    fn is_fresh_hit(cache: &EgressCache) -> bool {
+        matches!(
+            cache.lookup("acme", "DEV", "catalog", "GET", "https://catalog/items", &HeaderMap::new()),
+            Some(CacheLookup::Fresh(_))
+        )
+    }
+
+    #[test]
+    fn freshness_prefers_policy_ttl_then_s_maxage_then_max_age() {
+        let policy = CachePolicy::default();
+        assert_eq!(freshness(Some("max-age=60"), &policy), Some(60));
+        assert_eq!(freshness(Some("public, max-age=60, s-maxage=120"), &policy), Some(120));
+        assert_eq!(freshness(None, &policy), Some(0));
+
+        let pinned = CachePolicy { ttl_secs: Some(30), ..Default::default() };
+        assert_eq!(freshness(Some("max-age=600"), &pinned), Some(30));
+    }
+
+    #[test]
+    fn freshness_is_capped_by_max_ttl() {
+        let policy = CachePolicy { max_ttl_secs: 100, ..Default::default() };
+        assert_eq!(freshness(Some("max-age=86400"), &policy), Some(100));
+        let pinned = CachePolicy { ttl_secs: Some(500), max_ttl_secs: 100, ..Default::default() };
+        assert_eq!(freshness(None, &pinned), Some(100));
+    }
+
+    #[test]
+    fn no_store_and_private_are_never_stored() {
+        let pinned = CachePolicy { ttl_secs: Some(30), ..Default::default() };
+        assert_eq!(freshness(Some("no-store"), &pinned), None);
+        assert_eq!(freshness(Some("Private, max-age=60"), &pinned), None);
+    }
+
+    #[test]
+    fn no_cache_stores_for_revalidation_only() {
+        let pinned = CachePolicy { ttl_secs: Some(30), ..Default::default() };
+        assert_eq!(freshness(Some("no-cache"), &pinned), Some(0));
+    }
+
+    #[test]
+    fn only_get_and_head_on_aliases_with_a_policy_are_cacheable() {
+        let cache = cache_with(CachePolicy::default());
+        let headers = HeaderMap::new();
+        assert!(cache.lookup("acme", "DEV", "catalog", "POST", "https://catalog/items", &headers).is_none());
+        assert!(cache.lookup("acme", "DEV", "billing", "GET", "https://billing/x", &headers).is_none());
+        assert!(cache.lookup("acme", "PROD", "catalog", "HEAD", "https://catalog/items", &headers).is_some());
+    }
+
+    #[test]
+    fn fresh_responses_are_served_from_cache() {
+        let cache = cache_with(CachePolicy::default());
+        let (key, policy, stale) = miss(&cache);
+        let (_, outcome) = cache.complete(key, &policy, stale, SCOPE, response(200, &[("Cache-Control", "max-age=60")], "items"));
+        assert!(matches!(outcome, CacheOutcome::Stored));
+        assert!(is_fresh_hit(&cache));
+        assert_eq!(cache.stats().bytes, 5);
+    }
+
+    #[test]
+    fn uncacheable_responses_bypass_the_cache() {
+        let cache = cache_with(CachePolicy::default());
+        for rejected in [
+            response(200, &[("Cache-Control", "no-store"), ("ETag", "\"v1\"")], "items"),
+            response(500, &[("Cache-Control", "max-age=60")], "oops"),
+            // Zero lifetime and no validators: nothing to serve or revalidate
+            response(200, &[], "items"),
+        ] {
+            let (key, policy, stale) = miss(&cache);
+            let (_, outcome) = cache.complete(key, &policy, stale, SCOPE, rejected);
+            assert!(matches!(outcome, CacheOutcome::Bypassed));
+        }
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn oversized_bodies_are_not_stored() {
+        let cache = cache_with(CachePolicy { max_entry_bytes: 4, ..Default::default() });
+        let (key, policy, stale) = miss(&cache);
+        let (_, outcome) = cache.complete(key, &policy, stale, SCOPE, response(200, &[("Cache-Control", "max-age=60")], "too big"));
+        assert!(matches!(outcome, CacheOutcome::Bypassed));
+    }
+
+    #[test]
+    fn stale_entries_revalidate_with_their_validators() {
+        let cache = cache_with(CachePolicy::default());
+        let (key, policy, stale) = miss(&cache);
+        let headers = [("Cache-Control", "no-cache"), ("ETag", "\"v1\""), ("Last-Modified", "Tue, 01 Sep 2026 10:00:00 GMT")];
+        let (_, outcome) = cache.complete(key, &policy, stale, SCOPE, response(200, &headers, "items"));
+        assert!(matches!(outcome, CacheOutcome::Stored));
+
+        let (key, policy, stale) = miss(&cache);
+        let entry = stale.as_ref().expect("stored entry comes back stale");
+        assert_eq!(entry.validators(), vec![
+            ("If-None-Match", "\"v1\"".to_string()),
+            ("If-Modified-Since", "Tue, 01 Sep 2026 10:00:00 GMT".to_string()),
+        ]);
+
+        let (served, outcome) = cache.complete(key, &policy, stale, SCOPE, response(304, &[("Cache-Control", "max-age=60")], ""));
+        assert!(matches!(outcome, CacheOutcome::Revalidated));
+        assert_eq!(served.status, 200);
+        assert_eq!(served.body, b"items");
+        assert!(is_fresh_hit(&cache));
+    }
+
+    #[test]
+    fn vary_headers_split_the_cache_key() {
+        let cache = cache_with(CachePolicy { vary_headers: vec!["Accept-Language".into()], ..Default::default() });
+        let mut english = HeaderMap::new();
+        english.insert("Accept-Language", "en".parse().unwrap());
+        let mut french = HeaderMap::new();
+        french.insert("Accept-Language", "fr".parse().unwrap());
+
+        let Some(CacheLookup::Miss { key, policy, stale }) = cache.lookup("acme", "DEV", "catalog", "GET", "https://catalog/items", &english) else {
+            panic!("expected a miss");
+        };
+        cache.complete(key, &policy, stale, SCOPE, response(200, &[("Cache-Control", "max-age=60")], "hello"));
+
+        assert!(matches!(cache.lookup("acme", "DEV", "catalog", "GET", "https://catalog/items", &english), Some(CacheLookup::Fresh(_))));
+        assert!(matches!(cache.lookup("acme", "DEV", "catalog", "GET", "https://catalog/items", &french), Some(CacheLookup::Miss { .. })));
+    }
+
+    #[test]
+    fn purge_filters_by_scope_and_frees_bytes() {
+        let cache = cache_with(CachePolicy::default());
+        let (key, policy, stale) = miss(&cache);
+        cache.complete(key, &policy, stale, SCOPE, response(200, &[("Cache-Control", "max-age=60")], "items"));
+
+        assert_eq!(cache.purge(Some("globex"), None, None), 0);
+        assert_eq!(cache.purge(Some("acme"), Some("dev"), Some("catalog")), 1);
+        assert_eq!(cache.stats().entries, 0);
+        assert_eq!(cache.stats().bytes, 0);
+    }
+}
@@ -0,0 +1,297 @@
+/// Event Bus — in-process publish/subscribe between kernels.
+/// Kernels publish with `axiom_sdk::events::publish` and subscribe with `#[axiom_subscribe("topic")]`.
+/// Events stay inside the publisher's slot (a BLUE publish only reaches BLUE subscribers), each
+/// subscriber drains its own queue in order, and a delivery is retried until the handler acks it or
+/// the attempts run out, at which point it is dead-lettered. Publish and subscribe are governed by the
+/// `events` topic lists in the tomain's capability manifest. Queues live in memory, so delivery is
+/// at-least-once per shell process.
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex, RwLock};
+use tracing::{info, warn};
+use crate::capabilities::Capabilities;
+use crate::runtime::WasmSupervisor;
+use crate::supervisor::TenantInstance;
+
+/// Deliveries attempted per event and subscriber before dead-lettering.
+const MAX_ATTEMPTS: u32 = 5;
+const BASE_BACKOFF: Duration = Duration::from_millis(200);
+/// Events buffered per subscriber; a full queue dead-letters new events for that subscriber.
+const QUEUE_CAPACITY: usize = 1000;
+const DEAD_LETTER_CAPACITY: usize = 1000;
+/// `source_tomain` of events the Shell publishes itself.
+pub const SYSTEM_SOURCE: &str = "axiom";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventEnvelope {
+    pub id: String,
+    pub topic: String,
+    pub source_tomain: String,
+    pub slot: String,
+    pub payload: String,
+    pub published_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Subscription {
+    pub tomain_id: String,
+    pub slot: String,
+    /// Topic pattern: `*` matches one dot-separated token, `>` matches the rest
+    pub topic: String,
+    /// Exported `__axiom_event_*` function that receives the payload
+    pub handler: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadEvent {
+    pub id: String,
+    pub event: EventEnvelope,
+    pub subscriber: Subscription,
+    pub reason: String,
+    pub dead_lettered_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+pub enum PublishError {
+    /// The tomain's manifest does not grant publish on this topic
+    NotGranted,
+    Broker(anyhow::Error),
+}
+
+/// Transport behind the bus. The in-process broker loops events straight back; a NATS adapter
+/// would publish to the server and feed its subscription messages into the same inbound channel.
+#[async_trait]
+pub trait BrokerAdapter: Send + Sync {
+    async fn publish(&self, event: EventEnvelope) -> Result<()>;
+    fn provider_name(&self) -> &'static str;
+}
+
+pub struct InProcessBroker {
+    inbound: mpsc::UnboundedSender<EventEnvelope>,
+}
+
+#[async_trait]
+impl BrokerAdapter for InProcessBroker {
+    async fn publish(&self, event: EventEnvelope) -> Result<()> {
+        self.inbound.send(event).map_err(|_| anyhow!("Event router is not running"))
+    }
+
+    fn provider_name(&self) -> &'static str {
+        "in-process"
+    }
+}
+
+struct SubscriberQueue {
+    subscription: Subscription,
+    sender: mpsc::Sender<EventEnvelope>,
+}
+
+pub struct EventBus {
+    broker: Arc<dyn BrokerAdapter>,
+    /// Events coming back from the broker; taken once by the router loop
+    inbound: Mutex<Option<mpsc::UnboundedReceiver<EventEnvelope>>>,
+    /// (tomain_id, slot, handler) -> subscriber queue
+    queues: Arc<DashMap<(String, String, String), SubscriberQueue>>,
+    pub dead_letter: Arc<RwLock<VecDeque<DeadEvent>>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            broker: Arc::new(InProcessBroker { inbound: tx }),
+            inbound: Mutex::new(Some(rx)),
+            queues: Arc::new(DashMap::new()),
+            dead_letter: Arc::new(RwLock::new(VecDeque::new())),
+        }
+    }
+
+    pub async fn publish(&self, capabilities: &Capabilities, tomain_id: &str, slot: &str, topic: &str, payload: String) -> Result<(), PublishError> {
+        if !capabilities.allows_topic(topic, true) {
+            return Err(PublishError::NotGranted);
+        }
+        self.send(tomain_id, slot, topic, payload).await.map_err(PublishError::Broker)
+    }
+
+    /// Publishes a Shell-originated event (source `axiom`), e.g. `axiom.circuit.open`. No publish
+    /// grant is needed; subscribers still need a subscribe grant for the topic.
+    pub async fn publish_system(&self, slot: &str, topic: &str, payload: String) -> Result<()> {
+        self.send(SYSTEM_SOURCE, slot, topic, payload).await
+    }
+
+    async fn send(&self, tomain_id: &str, slot: &str, topic: &str, payload: String) -> Result<()> {
+        let event = EventEnvelope {
+            id: uuid::Uuid::new_v4().to_string(),
+            topic: topic.to_string(),
+            source_tomain: tomain_id.to_string(),
+            slot: slot.to_string(),
+            payload,
+            published_at: Utc::now(),
+        };
+        info!("📣 {} published '{}' ({}) in {} slot", tomain_id, topic, event.id, slot);
+        self.broker.publish(event).await
+    }
+
+    /// Router loop: fans each inbound event out to the queues of matching subscribers in its slot.
+    pub async fn run(sv: Arc<WasmSupervisor>) {
+        let Some(mut inbound) = sv.events.inbound.lock().await.take() else { return };
+        info!("📣 Event bus active ({} broker)", sv.events.broker.provider_name());
+
+        while let Some(event) = inbound.recv().await {
+            let mut overflowed = Vec::new();
+            for queue in sv.events.queues.iter() {
+                let sub = &queue.subscription;
+                if sub.slot != event.slot || !topic_matches(&sub.topic, &event.topic) {
+                    continue;
+                }
+                if let Err(mpsc::error::TrySendError::Full(event)) = queue.sender.try_send(event.clone()) {
+                    overflowed.push((event, sub.clone()));
+                }
+            }
+            for (event, sub) in overflowed {
+                sv.metrics.inc_counter("axiom_event_deliveries_total", &[("tomain", &sub.tomain_id), ("outcome", "dead_lettered")], 1.0);
+                sv.events.push_dead_letter(event, sub, "Subscriber queue full".to_string()).await;
+            }
+        }
+    }
+
+    /// Registers the `#[axiom_subscribe]` handlers of a freshly activated instance. Queues for
+    /// handlers that still exist are kept, so pending events survive a redeploy.
+    pub async fn sync_subscriptions(&self, sv: Arc<WasmSupervisor>, tenant: Arc<TenantInstance>) {
+        let declared = match crate::bridge::invoke_subscriptions(sv.clone(), tenant.clone()).await {
+            Ok(declared) => declared,
+            Err(e) => {
+                warn!("📣 Could not read subscriptions for {}: {}", tenant.id, e);
+                return;
+            }
+        };
+
+        let capabilities = sv.capabilities.get(&tenant.id);
+        let mut wanted = Vec::new();
+        for decl in declared {
+            let (Some(topic), Some(handler)) = (decl["topic"].as_str(), decl["handler"].as_str()) else { continue };
+            if !capabilities.allows_topic(topic, false) {
+                warn!("🛑 {} subscribes to '{}' without a grant, skipping {}", tenant.id, topic, handler);
+                continue;
+            }
+            wanted.push(Subscription {
+                tomain_id: tenant.id.clone(),
+                slot: tenant.env.clone(),
+                topic: topic.to_string(),
+                handler: handler.to_string(),
+            });
+        }
+
+        self.queues.retain(|(id, slot, handler), q| {
+            id != &tenant.id || slot != &tenant.env
+                || wanted.iter().any(|w| &w.handler == handler && w.topic == q.subscription.topic)
+        });
+        for sub in wanted {
+            let key = (sub.tomain_id.clone(), sub.slot.clone(), sub.handler.clone());
+            if self.queues.contains_key(&key) {
+                continue;
+            }
+            let (sender, receiver) = mpsc::channel(QUEUE_CAPACITY);
+            info!("📣 {} ({} slot) subscribed to '{}' via {}", sub.tomain_id, sub.slot, sub.topic, sub.handler);
+            tokio::spawn(Self::deliver_loop(sv.clone(), sub.clone(), receiver));
+            self.queues.insert(key, SubscriberQueue { subscription: sub, sender });
+        }
+    }
+
+    /// Drops a slot's subscriptions. Workers finish what is already queued and exit.
+    pub fn remove_subscriptions(&self, tomain_id: &str, slot: &str) {
+        self.queues.retain(|(id, s, _), _| id != tomain_id || s != slot);
+    }
+
+    pub fn subscriptions(&self) -> Vec<Subscription> {
+        self.queues.iter().map(|q| q.subscription.clone()).collect()
+    }
+
+    async fn deliver_loop(sv: Arc<WasmSupervisor>, sub: Subscription, mut receiver: mpsc::Receiver<EventEnvelope>) {
+        while let Some(event) = receiver.recv().await {
+            Self::deliver(&sv, &sub, event).await;
+        }
+    }
+
+    async fn deliver(sv: &Arc<WasmSupervisor>, sub: &Subscription, event: EventEnvelope) {
+        let mut last_error = String::new();
+        for attempt in 1..=MAX_ATTEMPTS {
+            let result = match sv.manager.checkout_tenant(&sub.tomain_id, &sub.slot).await {
+                Some(tenant) => crate::bridge::invoke_event(sv.clone(), tenant.tenant(), &sub.handler, &event.payload).await,
+                None => Err(anyhow!("{} has no instance in {} slot", sub.tomain_id, sub.slot)),
+            };
+            match result {
+                Ok(_) => {
+                    sv.metrics.inc_counter("axiom_event_deliveries_total", &[("tomain", &sub.tomain_id), ("outcome", "acked")], 1.0);
+                    return;
+                }
+                Err(e) => last_error = e.to_string(),
+            }
+            if attempt < MAX_ATTEMPTS {
+                sv.metrics.inc_counter("axiom_event_deliveries_total", &[("tomain", &sub.tomain_id), ("outcome", "retried")], 1.0);
+                let backoff = BASE_BACKOFF * 2u32.pow(attempt - 1);
+                warn!("🔁 Event {} to {}::{} attempt {}/{} failed: {}", event.id, sub.tomain_id, sub.handler, attempt, MAX_ATTEMPTS, last_error);
+                tokio::time::sleep(backoff).await;
+            }
+        }
+        let reason = format!("Delivery failed after {} attempts: {}", MAX_ATTEMPTS, last_error);
+        sv.metrics.inc_counter("axiom_event_deliveries_total", &[("tomain", &sub.tomain_id), ("outcome", "dead_lettered")], 1.0);
+        sv.events.push_dead_letter(event, sub.clone(), reason).await;
+    }
+
+    async fn push_dead_letter(&self, event: EventEnvelope, subscriber: Subscription, reason: String) {
+        warn!("🪦 Event {} for {}::{} dead-lettered: {}", event.id, subscriber.tomain_id, subscriber.handler, reason);
+        let mut dead_letter = self.dead_letter.write().await;
+        if dead_letter.len() >= DEAD_LETTER_CAPACITY {
+            dead_letter.pop_front();
+        }
+        dead_letter.push_back(DeadEvent {
+            id: uuid::Uuid::new_v4().to_string(),
+            event,
+            subscriber,
+            reason,
+            dead_lettered_at: Utc::now(),
+        });
+    }
+
+    pub async fn dead_letters(&self) -> Vec<DeadEvent> {
+        self.dead_letter.read().await.iter().cloned().collect()
+    }
+
+    /// Puts a dead-lettered event back on its subscriber's queue.
+    pub async fn redeliver(&self, id: &str) -> Result<()> {
+        let mut dead_letter = self.dead_letter.write().await;
+        let pos = dead_letter.iter().position(|d| d.id == id)
+            .ok_or_else(|| anyhow!("No dead-lettered event '{}'", id))?;
+
+        let sub = &dead_letter[pos].subscriber;
+        let key = (sub.tomain_id.clone(), sub.slot.clone(), sub.handler.clone());
+        let sender = self.queues.get(&key)
+            .map(|q| q.sender.clone())
+            .ok_or_else(|| anyhow!("{}::{} is no longer subscribed in {} slot", sub.tomain_id, sub.handler, sub.slot))?;
+        sender.try_send(dead_letter[pos].event.clone()).map_err(|_| anyhow!("Subscriber queue is full or closed"))?;
+        dead_letter.remove(pos);
+        Ok(())
+    }
+}
+
+/// NATS-style subject matching on dot-separated tokens.
+pub fn topic_matches(pattern: &str, topic: &str) -> bool {
+    let mut pattern_tokens = pattern.split('.');
+    let mut topic_tokens = topic.split('.');
+    loop {
+        match (pattern_tokens.next(), topic_tokens.next()) {
+            (Some(">"), Some(_)) => return true,
+            (Some("*"), Some(_)) => continue,
+            (Some(p), Some(t)) if p == t => continue,
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
@@ -0,0 +1,409 @@
+/// Async Jobs — `POST /async/{tomain}/{func}` queues an invocation and returns a job id at once.
+/// The call runs in the background through `WasmSupervisor::call`, transient failures are retried
+/// with backoff, the result is kept for a TTL for `GET /jobs/{id}`, and the finished job is POSTed
+/// to an optional callback alias through the Egress Guard, like any outbound call of the tomain.
+/// Callbacks are retried with the same backoff and carry the job id as their idempotency key.
+/// Failed jobs and undeliverable callbacks are dead-lettered.
+/// Submissions past the job limit are shed so callers back off instead of piling up work.
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{info, warn, Instrument};
+use crate::egress::EgressError;
+use crate::resilience::Shed;
+use crate::runtime::{CallerContext, UnknownTarget, WasmSupervisor};
+
+/// Attempts per invocation, and per callback delivery.
+const MAX_ATTEMPTS: u32 = 3;
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+/// Finished results are kept this long unless AXIOM_JOB_TTL_SECS says otherwise.
+const DEFAULT_TTL_SECS: i64 = 3600;
+/// Oldest dead-lettered jobs are evicted past this.
+const DEAD_LETTER_CAPACITY: usize = 500;
+/// Jobs queued or running at once unless AXIOM_MAX_JOBS says otherwise.
+const DEFAULT_MAX_JOBS: usize = 1000;
+/// Retry-After suggested when a submission is shed because the limit is reached.
+const QUEUE_FULL_RETRY_AFTER_SECS: u64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Job {
+    pub id: String,
+    pub tomain_id: String,
+    pub function: String,
+    pub status: JobStatus,
+    pub attempts: u32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub result: Option<String>,
+    pub error: Option<String>,
+    /// Egress alias the finished job is POSTed to
+    pub callback: Option<String>,
+    /// "Delivered", "Failed: ..." or None while pending / without a callback
+    pub callback_status: Option<String>,
+    #[serde(skip)]
+    args: String,
+}
+
+/// Which part of a job failed, and so what a retry from the dead-letter list repeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailedStage {
+    /// The kernel call itself; a retry runs it again
+    Invocation,
+    /// Only the callback; a retry re-delivers the stored result
+    Callback,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetter {
+    pub job: Job,
+    pub stage: FailedStage,
+    pub reason: String,
+    pub dead_lettered_at: DateTime<Utc>,
+}
+
+pub struct JobStore {
+    /// job_id -> job
+    pub jobs: Arc<DashMap<String, Job>>,
+    pub dead_letter: Arc<RwLock<VecDeque<DeadLetter>>>,
+    ttl: chrono::Duration,
+    /// Jobs submitted and not yet finished (including their callback)
+    pending: AtomicUsize,
+    max_jobs: usize,
+}
+
+impl JobStore {
+    pub fn new() -> Self {
+        let ttl_secs = std::env::var("AXIOM_JOB_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_TTL_SECS);
+        let max_jobs = std::env::var("AXIOM_MAX_JOBS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MAX_JOBS);
+        Self {
+            jobs: Arc::new(DashMap::new()),
+            dead_letter: Arc::new(RwLock::new(VecDeque::new())),
+            ttl: chrono::Duration::seconds(ttl_secs),
+            pending: AtomicUsize::new(0),
+            max_jobs,
+        }
+    }
+
+    /// Queues an invocation and starts it in the background. Returns the job id, or `Shed` when
+    /// the Shell already has its limit of unfinished jobs.
+    pub fn submit(sv: Arc<WasmSupervisor>, tomain_id: &str, function: &str, args: String, callback: Option<String>) -> Result<String, Shed> {
+        Self::reserve(&sv, tomain_id, function)?;
+
+        let now = Utc::now();
+        let job = Job {
+            id: uuid::Uuid::new_v4().to_string(),
+            tomain_id: tomain_id.to_string(),
+            function: function.to_string(),
+            status: JobStatus::Queued,
+            attempts: 0,
+            created_at: now,
+            updated_at: now,
+            expires_at: None,
+            result: None,
+            error: None,
+            callback,
+            callback_status: None,
+            args,
+        };
+        let id = job.id.clone();
+        info!("📬 Job {} queued for {}/{}", id, tomain_id, function);
+        sv.jobs.jobs.insert(id.clone(), job);
+        sv.metrics.inc_counter("axiom_jobs_total", &[("tomain", tomain_id), ("status", "queued")], 1.0);
+
+        // Child of the submitting request's span, so the run and its callback join that trace
+        let span = tracing::info_span!("axiom.job", axiom.job_id = %id, axiom.tomain = %tomain_id, axiom.function = %function);
+        let job_id = id.clone();
+        tokio::spawn(async move {
+            Self::execute(sv.clone(), job_id).await;
+            sv.jobs.pending.fetch_sub(1, Ordering::AcqRel);
+        }.instrument(span));
+        Ok(id)
+    }
+
+    /// Counts one more unfinished job, or sheds it when the limit is reached.
+    fn reserve(sv: &WasmSupervisor, tomain_id: &str, function: &str) -> Result<(), Shed> {
+        if sv.jobs.pending.fetch_add(1, Ordering::AcqRel) >= sv.jobs.max_jobs {
+            sv.jobs.pending.fetch_sub(1, Ordering::AcqRel);
+            warn!("🚧 Job limit ({}) reached, shedding {}/{}", sv.jobs.max_jobs, tomain_id, function);
+            sv.metrics.inc_counter("axiom_load_shed_total", &[("scope", "jobs"), ("tomain", tomain_id), ("reason", "queue_full")], 1.0);
+            return Err(Shed { scope: "job queue".to_string(), reason: "queue_full", retry_after_secs: QUEUE_FULL_RETRY_AFTER_SECS });
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<Job> {
+        self.jobs.get(id).map(|j| j.value().clone())
+    }
+
+    fn update(&self, id: &str, f: impl FnOnce(&mut Job)) {
+        if let Some(mut job) = self.jobs.get_mut(id) {
+            f(&mut job);
+            job.updated_at = Utc::now();
+        }
+    }
+
+    async fn execute(sv: Arc<WasmSupervisor>, id: String) {
+        let Some(job) = sv.jobs.get(&id) else { return };
+
+        let mut outcome = Err("not attempted".to_string());
+        let mut attempts = 0;
+        for attempt in 1..=MAX_ATTEMPTS {
+            attempts = attempt;
+            sv.jobs.update(&id, |j| {
+                j.status = JobStatus::Running;
+                j.attempts = attempt;
+            });
+            match sv.clone().call(&job.tomain_id, &job.function, job.args.clone()).await {
+                Ok(result) => {
+                    outcome = Ok(result);
+                    break;
+                }
+                Err(e) => {
+                    outcome = Err(e.to_string());
+                    if attempt == MAX_ATTEMPTS || !is_transient(&e) {
+                        break;
+                    }
+                    let backoff = BASE_BACKOFF * 2u32.pow(attempt - 1);
+                    warn!("🔁 Job {} attempt {}/{} failed: {}. Retrying in {:?}", id, attempt, MAX_ATTEMPTS, e, backoff);
+                    tokio::time::sleep(backoff).await;
+                }
+            }
+        }
+
+        let expires_at = Some(Utc::now() + sv.jobs.ttl);
+        let status = match outcome {
+            Ok(result) => {
+                info!("✅ Job {} succeeded", id);
+                sv.jobs.update(&id, |j| {
+                    j.status = JobStatus::Succeeded;
+                    j.result = Some(result);
+                    j.expires_at = expires_at;
+                });
+                "succeeded"
+            }
+            Err(e) => {
+                warn!("❌ Job {} failed after {} attempt(s): {}", id, attempts, e);
+                sv.jobs.update(&id, |j| {
+                    j.status = JobStatus::Failed;
+                    j.error = Some(e.clone());
+                    j.expires_at = expires_at;
+                });
+                sv.jobs.push_dead_letter(&id, FailedStage::Invocation, format!("Invocation failed after {} attempt(s): {}", attempts, e)).await;
+                "failed"
+            }
+        };
+        sv.metrics.inc_counter("axiom_jobs_total", &[("tomain", &job.tomain_id), ("status", status)], 1.0);
+
+        if let Some(alias) = &job.callback {
+            Self::deliver_callback(&sv, &id, alias).await;
+        }
+    }
+
+    /// POSTs the finished job to the callback alias from the job's active slot. Transient failures
+    /// are retried with the job's backoff; the job id is the idempotency key, so a receiver can
+    /// drop a delivery it has already seen.
+    async fn deliver_callback(sv: &Arc<WasmSupervisor>, id: &str, alias: &str) {
+        let Some(job) = sv.jobs.get(id) else { return };
+        let body = serde_json::to_vec(&job).unwrap_or_default();
+
+        let mut error = String::new();
+        for attempt in 1..=MAX_ATTEMPTS {
+            let ctx = CallerContext {
+                tomain_id: job.tomain_id.clone(),
+                slot: sv.get_perspective(&job.tomain_id),
+                request_id: job.id.clone(),
+                depth: 0,
+            };
+            let transient = match crate::bridge::egress_post_json(sv.clone(), ctx, alias, body.clone(), &job.id).await {
+                Ok(res) if (200..300).contains(&res.status) => {
+                    info!("📨 Job {} callback delivered to {}", id, alias);
+                    sv.jobs.update(id, |j| j.callback_status = Some("Delivered".to_string()));
+                    return;
+                }
+                Ok(res) => {
+                    error = format!("HTTP {}", res.status);
+                    matches!(res.status, 408 | 429 | 500..=599)
+                }
+                Err(e) => {
+                    error = e.to_string();
+                    !matches!(e, EgressError::NoBinding(_) | EgressError::PolicyDenied(_))
+                }
+            };
+            if attempt == MAX_ATTEMPTS || !transient {
+                break;
+            }
+            let backoff = BASE_BACKOFF * 2u32.pow(attempt - 1);
+            warn!("🔁 Job {} callback attempt {}/{} to {} failed: {}. Retrying in {:?}", id, attempt, MAX_ATTEMPTS, alias, error, backoff);
+            tokio::time::sleep(backoff).await;
+        }
+
+        sv.jobs.update(id, |j| j.callback_status = Some(format!("Failed: {}", error)));
+        sv.jobs.push_dead_letter(id, FailedStage::Callback, format!("Callback to '{}' failed: {}", alias, error)).await;
+    }
+
+    /// Dead-letters a job, or adds the new reason to its entry if it is already dead-lettered
+    /// (an invocation failure followed by a failed callback). The entry keeps the earliest stage.
+    async fn push_dead_letter(&self, id: &str, stage: FailedStage, reason: String) {
+        let Some(job) = self.get(id) else { return };
+        warn!("🪦 Job {} dead-lettered: {}", id, reason);
+        let mut dead_letter = self.dead_letter.write().await;
+        if let Some(entry) = dead_letter.iter_mut().find(|d| d.job.id == id) {
+            entry.reason = format!("{}; {}", entry.reason, reason);
+            entry.job = job;
+            entry.dead_lettered_at = Utc::now();
+            return;
+        }
+        if dead_letter.len() >= DEAD_LETTER_CAPACITY {
+            dead_letter.pop_front();
+        }
+        dead_letter.push_back(DeadLetter { job, stage, reason, dead_lettered_at: Utc::now() });
+    }
+
+    pub async fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.dead_letter.read().await.iter().cloned().collect()
+    }
+
+    /// Takes a job off the dead-letter list and retries it. A failed invocation is submitted again
+    /// as a new job; a job whose only failure was its callback keeps its id and result and has the
+    /// callback delivered again, so the kernel isn't called twice. A shed retry leaves it dead-lettered.
+    pub async fn requeue(sv: Arc<WasmSupervisor>, id: &str) -> Option<Result<String, Shed>> {
+        let entry = {
+            let mut dead_letter = sv.jobs.dead_letter.write().await;
+            let pos = dead_letter.iter().position(|d| d.job.id == id)?;
+            dead_letter.remove(pos)?
+        };
+        let job = entry.job.clone();
+        let retried = match (entry.stage, job.callback.clone()) {
+            (FailedStage::Callback, Some(alias)) => Self::redeliver(sv.clone(), job, alias),
+            _ => Self::submit(sv.clone(), &job.tomain_id, &job.function, job.args, job.callback),
+        };
+        if retried.is_err() {
+            sv.jobs.dead_letter.write().await.push_back(entry);
+        }
+        Some(retried)
+    }
+
+    /// Puts a finished job back and delivers its callback again, without re-running the invocation.
+    fn redeliver(sv: Arc<WasmSupervisor>, mut job: Job, alias: String) -> Result<String, Shed> {
+        Self::reserve(&sv, &job.tomain_id, &job.function)?;
+        let id = job.id.clone();
+        job.callback_status = None;
+        job.expires_at = Some(Utc::now() + sv.jobs.ttl);
+        job.updated_at = Utc::now();
+        info!("📬 Job {} callback requeued to {}", id, alias);
+        sv.jobs.jobs.insert(id.clone(), job);
+
+        let span = tracing::info_span!("axiom.job", axiom.job_id = %id, axiom.callback = %alias);
+        let job_id = id.clone();
+        tokio::spawn(async move {
+            Self::deliver_callback(&sv, &job_id, &alias).await;
+            sv.jobs.pending.fetch_sub(1, Ordering::AcqRel);
+        }.instrument(span));
+        Ok(id)
+    }
+
+    /// Drops finished jobs whose TTL has passed.
+    pub fn sweep_expired(&self) {
+        let now = Utc::now();
+        let before = self.jobs.len();
+        self.jobs.retain(|_, j| j.expires_at.map(|t| t > now).unwrap_or(true));
+        let removed = before - self.jobs.len();
+        if removed > 0 {
+            info!("🧹 Swept {} expired job results", removed);
+        }
+    }
+}
+
+/// Whether a failed invocation is worth retrying. Unknown tomains or functions stay unknown until a
+/// redeploy, and a shed call retried from here would only add to the overload.
+fn is_transient(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<UnknownTarget>().is_none() && error.downcast_ref::<Shed>().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_with_job(id: &str) -> JobStore {
+        let store = JobStore::new();
+        let now = Utc::now();
+        store.jobs.insert(id.to_string(), Job {
+            id: id.to_string(),
+            tomain_id: "acme".to_string(),
+            function: "export".to_string(),
+            status: JobStatus::Failed,
+            attempts: 3,
+            created_at: now,
+            updated_at: now,
+            expires_at: None,
+            result: None,
+            error: Some("boom".to_string()),
+            callback: Some("hooks".to_string()),
+            callback_status: None,
+            args: "{}".to_string(),
+        });
+        store
+    }
+
+    #[tokio::test]
+    async fn a_job_is_dead_lettered_once() {
+        let store = store_with_job("job-1");
+        store.push_dead_letter("job-1", FailedStage::Invocation, "Invocation failed after 3 attempt(s): boom".to_string()).await;
+        store.update("job-1", |j| j.callback_status = Some("Failed: HTTP 500".to_string()));
+        store.push_dead_letter("job-1", FailedStage::Callback, "Callback to 'hooks' failed: HTTP 500".to_string()).await;
+
+        let dead = store.dead_letters().await;
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].stage, FailedStage::Invocation);
+        assert_eq!(dead[0].reason, "Invocation failed after 3 attempt(s): boom; Callback to 'hooks' failed: HTTP 500");
+        assert_eq!(dead[0].job.callback_status.as_deref(), Some("Failed: HTTP 500"));
+    }
+
+    #[tokio::test]
+    async fn callback_failures_are_redelivered_without_reinvoking() {
+        let sv = Arc::new(WasmSupervisor::new(Arc::new(crate::telemetry::SamplingRatios::new())).await.unwrap());
+        let job = store_with_job("job-2").get("job-2").unwrap();
+        sv.jobs.jobs.insert("job-2".to_string(), Job { status: JobStatus::Succeeded, result: Some("42".to_string()), error: None, ..job });
+        sv.jobs.push_dead_letter("job-2", FailedStage::Callback, "Callback to 'hooks' failed: HTTP 500".to_string()).await;
+        sv.jobs.jobs.clear();
+
+        let retried = JobStore::requeue(sv.clone(), "job-2").await.unwrap().unwrap();
+        assert_eq!(retried, "job-2");
+        // The tomain grants no http alias, so the callback fails again and comes back dead-lettered
+        for _ in 0..50 {
+            if !sv.jobs.dead_letters().await.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let job = sv.jobs.get("job-2").unwrap();
+        assert_eq!(job.status, JobStatus::Succeeded);
+        assert_eq!(job.attempts, 3);
+        assert_eq!(job.result.as_deref(), Some("42"));
+        let dead = sv.jobs.dead_letters().await;
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].stage, FailedStage::Callback);
+    }
+}
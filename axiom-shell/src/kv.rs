@@ -0,0 +1,456 @@
+/// Key-Value Store — small persistent state for kernels, which otherwise start every call fresh.
+/// Backends implement `KvProvider` (in-memory, embedded on-disk, Redis). Every key is namespaced
+/// as `{tomain}/{slot}/{key}` by `KvStore`, so one tomain can't read another's data and QA keys
+/// never show up in PROD.
+use anyhow::{Result, Context, anyhow};
+use async_trait::async_trait;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{info, error};
+
+/// Upper bound on keys returned by one scan.
+pub const MAX_SCAN: usize = 1000;
+
+#[async_trait]
+pub trait KvProvider: Send + Sync {
+    async fn get(&self, key: &str) -> Result<Option<String>>;
+    async fn set(&self, key: &str, value: &str, ttl: Option<Duration>) -> Result<()>;
+    /// Returns whether the key existed.
+    async fn delete(&self, key: &str) -> Result<bool>;
+    /// Writes `new` only if the current value equals `expected` (`None` = key must be absent).
+    async fn compare_and_swap(&self, key: &str, expected: Option<&str>, new: &str, ttl: Option<Duration>) -> Result<bool>;
+    /// Key/value pairs whose key starts with `prefix`, sorted by key.
+    async fn scan(&self, prefix: &str, limit: usize) -> Result<Vec<(String, String)>>;
+    /// Drops entries whose TTL has passed and returns how many went. Backends that expire keys
+    /// themselves keep the default.
+    async fn purge_expired(&self) -> Result<usize> {
+        Ok(0)
+    }
+    fn provider_name(&self) -> &'static str;
+}
+
+fn now_millis() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+fn expiry(ttl: Option<Duration>) -> Option<u64> {
+    ttl.map(|t| now_millis() + t.as_millis() as u64)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredValue {
+    value: String,
+    /// Unix millis after which the entry is gone
+    expires_at: Option<u64>,
+}
+
+impl StoredValue {
+    fn is_live(&self, now: u64) -> bool {
+        self.expires_at.map(|t| t > now).unwrap_or(true)
+    }
+}
+
+pub struct MemoryKv {
+    entries: DashMap<String, StoredValue>,
+}
+
+impl MemoryKv {
+    pub fn new() -> Self {
+        Self { entries: DashMap::new() }
+    }
+}
+
+#[async_trait]
+impl KvProvider for MemoryKv {
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        let now = now_millis();
+        Ok(self.entries.get(key).filter(|v| v.is_live(now)).map(|v| v.value.clone()))
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Option<Duration>) -> Result<()> {
+        self.entries.insert(key.to_string(), StoredValue { value: value.to_string(), expires_at: expiry(ttl) });
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        let now = now_millis();
+        Ok(self.entries.remove(key).map(|(_, v)| v.is_live(now)).unwrap_or(false))
+    }
+
+    async fn compare_and_swap(&self, key: &str, expected: Option<&str>, new: &str, ttl: Option<Duration>) -> Result<bool> {
+        let now = now_millis();
+        let stored = StoredValue { value: new.to_string(), expires_at: expiry(ttl) };
+        // The entry guard holds the shard lock, so the check and the write are atomic
+        match self.entries.entry(key.to_string()) {
+            dashmap::Entry::Occupied(mut entry) => {
+                let current = Some(entry.get()).filter(|v| v.is_live(now)).map(|v| v.value.as_str());
+                if current != expected {
+                    return Ok(false);
+                }
+                entry.insert(stored);
+            }
+            dashmap::Entry::Vacant(entry) => {
+                if expected.is_some() {
+                    return Ok(false);
+                }
+                entry.insert(stored);
+            }
+        }
+        Ok(true)
+    }
+
+    async fn scan(&self, prefix: &str, limit: usize) -> Result<Vec<(String, String)>> {
+        let now = now_millis();
+        let mut pairs: Vec<(String, String)> = self.entries.iter()
+            .filter(|e| e.key().starts_with(prefix) && e.is_live(now))
+            .map(|e| (e.key().clone(), e.value.clone()))
+            .collect();
+        pairs.sort();
+        pairs.truncate(limit);
+        Ok(pairs)
+    }
+
+    async fn purge_expired(&self) -> Result<usize> {
+        let now = now_millis();
+        let before = self.entries.len();
+        self.entries.retain(|_, v| v.is_live(now));
+        Ok(before.saturating_sub(self.entries.len()))
+    }
+
+    fn provider_name(&self) -> &'static str { "memory" }
+}
+
+/// Embedded store: an ordered map mirrored to ~/.axiom/kv/store.json after every write.
+pub struct DiskKv {
+    path: PathBuf,
+    entries: Mutex<BTreeMap<String, StoredValue>>,
+}
+
+impl DiskKv {
+    pub fn open(path: PathBuf) -> Result<Self> {
+        let mut entries: BTreeMap<String, StoredValue> = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).context("Corrupt KV store file")?,
+            Err(_) => BTreeMap::new(),
+        };
+        let now = now_millis();
+        entries.retain(|_, v| v.is_live(now));
+        Ok(Self { path, entries: Mutex::new(entries) })
+    }
+
+    /// Write to a temp file and rename so a crash never leaves a half-written store.
+    async fn flush(&self, entries: &BTreeMap<String, StoredValue>) -> Result<()> {
+        let content = serde_json::to_string(entries)?;
+        if let Some(dir) = self.path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, content).await?;
+        tokio::fs::rename(&tmp, &self.path).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl KvProvider for DiskKv {
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        let now = now_millis();
+        Ok(self.entries.lock().await.get(key).filter(|v| v.is_live(now)).map(|v| v.value.clone()))
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Option<Duration>) -> Result<()> {
+        let mut entries = self.entries.lock().await;
+        entries.insert(key.to_string(), StoredValue { value: value.to_string(), expires_at: expiry(ttl) });
+        self.flush(&entries).await
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        let now = now_millis();
+        let mut entries = self.entries.lock().await;
+        let existed = entries.remove(key).map(|v| v.is_live(now)).unwrap_or(false);
+        self.flush(&entries).await?;
+        Ok(existed)
+    }
+
+    async fn compare_and_swap(&self, key: &str, expected: Option<&str>, new: &str, ttl: Option<Duration>) -> Result<bool> {
+        let now = now_millis();
+        let mut entries = self.entries.lock().await;
+        let current = entries.get(key).filter(|v| v.is_live(now)).map(|v| v.value.as_str());
+        if current != expected {
+            return Ok(false);
+        }
+        entries.insert(key.to_string(), StoredValue { value: new.to_string(), expires_at: expiry(ttl) });
+        self.flush(&entries).await?;
+        Ok(true)
+    }
+
+    async fn scan(&self, prefix: &str, limit: usize) -> Result<Vec<(String, String)>> {
+        let now = now_millis();
+        Ok(self.entries.lock().await
+            .range(prefix.to_string()..)
+            .take_while(|(k, _)| k.starts_with(prefix))
+            .filter(|(_, v)| v.is_live(now))
+            .take(limit)
+            .map(|(k, v)| (k.clone(), v.value.clone()))
+            .collect())
+    }
+
+    async fn purge_expired(&self) -> Result<usize> {
+        let now = now_millis();
+        let mut entries = self.entries.lock().await;
+        let before = entries.len();
+        entries.retain(|_, v| v.is_live(now));
+        let removed = before - entries.len();
+        if removed > 0 {
+            self.flush(&entries).await?;
+        }
+        Ok(removed)
+    }
+
+    fn provider_name(&self) -> &'static str { "disk" }
+}
+
+/// Compare-and-swap as one server-side step. ARGV: has_expected ("0"/"1"), expected, new, ttl_ms ("0" = none).
+const REDIS_CAS_SCRIPT: &str = r#"
+local current = redis.call('GET', KEYS[1])
+if ARGV[1] == '0' then
+  if current then return 0 end
+elseif current ~= ARGV[2] then
+  return 0
+end
+if ARGV[4] ~= '0' then
+  redis.call('SET', KEYS[1], ARGV[3], 'PX', ARGV[4])
+else
+  redis.call('SET', KEYS[1], ARGV[3])
+end
+return 1
+"#;
+
+pub struct RedisKv {
+    conn: redis::aio::ConnectionManager,
+}
+
+impl RedisKv {
+    pub async fn new(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url).context("Invalid Redis URL")?;
+        let conn = redis::aio::ConnectionManager::new(client).await.context("Failed to connect to Redis")?;
+        Ok(Self { conn })
+    }
+}
+
+#[async_trait]
+impl KvProvider for RedisKv {
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        let mut conn = self.conn.clone();
+        Ok(redis::cmd("GET").arg(key).query_async(&mut conn).await?)
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Option<Duration>) -> Result<()> {
+        let mut conn = self.conn.clone();
+        let mut cmd = redis::cmd("SET");
+        cmd.arg(key).arg(value);
+        if let Some(ttl) = ttl {
+            cmd.arg("PX").arg(ttl.as_millis() as u64);
+        }
+        cmd.query_async::<()>(&mut conn).await?;
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        let mut conn = self.conn.clone();
+        let removed: u64 = redis::cmd("DEL").arg(key).query_async(&mut conn).await?;
+        Ok(removed > 0)
+    }
+
+    async fn compare_and_swap(&self, key: &str, expected: Option<&str>, new: &str, ttl: Option<Duration>) -> Result<bool> {
+        let mut conn = self.conn.clone();
+        let swapped: i64 = redis::Script::new(REDIS_CAS_SCRIPT)
+            .key(key)
+            .arg(if expected.is_some() { "1" } else { "0" })
+            .arg(expected.unwrap_or(""))
+            .arg(new)
+            .arg(ttl.map(|t| t.as_millis() as u64).unwrap_or(0))
+            .invoke_async(&mut conn)
+            .await?;
+        Ok(swapped == 1)
+    }
+
+    async fn scan(&self, prefix: &str, limit: usize) -> Result<Vec<(String, String)>> {
+        let mut conn = self.conn.clone();
+        let pattern = format!("{}*", redis_glob_escape(prefix));
+        let mut keys: Vec<String> = Vec::new();
+        let mut cursor: u64 = 0;
+        loop {
+            let (next, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor).arg("MATCH").arg(&pattern).arg("COUNT").arg(500)
+                .query_async(&mut conn).await?;
+            keys.extend(batch);
+            cursor = next;
+            if cursor == 0 {
+                break;
+            }
+        }
+        keys.sort();
+        keys.truncate(limit);
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let values: Vec<Option<String>> = redis::cmd("MGET").arg(&keys).query_async(&mut conn).await?;
+        // A key can expire between SCAN and MGET
+        Ok(keys.into_iter().zip(values)
+            .filter_map(|(k, v)| v.map(|v| (k, v)))
+            .collect())
+    }
+
+    fn provider_name(&self) -> &'static str { "redis" }
+}
+
+fn redis_glob_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Scopes every operation to `{tomain}/{slot}/` on top of the configured provider.
+pub struct KvStore {
+    provider: RwLock<Arc<dyn KvProvider>>,
+}
+
+impl KvStore {
+    pub fn new() -> Self {
+        Self { provider: RwLock::new(Arc::new(MemoryKv::new())) }
+    }
+
+    fn scoped(tomain_id: &str, slot: &str, key: &str) -> String {
+        format!("{}/{}/{}", tomain_id, slot.to_uppercase(), key)
+    }
+
+    pub async fn provider(&self) -> Arc<dyn KvProvider> {
+        self.provider.read().await.clone()
+    }
+
+    pub async fn get(&self, tomain_id: &str, slot: &str, key: &str) -> Result<Option<String>> {
+        self.provider().await.get(&Self::scoped(tomain_id, slot, key)).await
+    }
+
+    pub async fn set(&self, tomain_id: &str, slot: &str, key: &str, value: &str, ttl: Option<Duration>) -> Result<()> {
+        self.provider().await.set(&Self::scoped(tomain_id, slot, key), value, ttl).await
+    }
+
+    pub async fn delete(&self, tomain_id: &str, slot: &str, key: &str) -> Result<bool> {
+        self.provider().await.delete(&Self::scoped(tomain_id, slot, key)).await
+    }
+
+    pub async fn compare_and_swap(&self, tomain_id: &str, slot: &str, key: &str, expected: Option<&str>, new: &str, ttl: Option<Duration>) -> Result<bool> {
+        self.provider().await.compare_and_swap(&Self::scoped(tomain_id, slot, key), expected, new, ttl).await
+    }
+
+    /// Scans within the tomain's namespace; returned keys have the namespace stripped.
+    pub async fn scan(&self, tomain_id: &str, slot: &str, prefix: &str, limit: usize) -> Result<Vec<(String, String)>> {
+        let namespace = Self::scoped(tomain_id, slot, "");
+        let pairs = self.provider().await.scan(&format!("{}{}", namespace, prefix), limit.min(MAX_SCAN)).await?;
+        Ok(pairs.into_iter()
+            .filter_map(|(k, v)| k.strip_prefix(&namespace).map(|k| (k.to_string(), v)))
+            .collect())
+    }
+
+    /// Drops expired keys from backends that don't expire them on their own.
+    pub async fn sweep_expired(&self) {
+        match self.provider().await.purge_expired().await {
+            Ok(0) => {}
+            Ok(removed) => info!("🧹 Swept {} expired KV entries", removed),
+            Err(e) => error!("Failed to sweep expired KV entries: {}", e),
+        }
+    }
+
+    /// Picks the backend from session.json infra: `kv_provider` (memory | disk | redis) if set,
+    /// otherwise Redis when a `cache_url` is configured, otherwise the embedded on-disk store.
+    pub async fn reload_from_registry(&self) -> Result<()> {
+        let path = dirs::home_dir()
+            .unwrap_or_default()
+            .join(".axiom")
+            .join("session.json");
+
+        let infra = std::fs::read_to_string(&path).ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+            .and_then(|json| json.get("infra").cloned())
+            .unwrap_or(Value::Null);
+        let cache_url = infra.get("cache_url").and_then(|u| u.as_str());
+        let kind = infra.get("kv_provider").and_then(|p| p.as_str())
+            .unwrap_or(if cache_url.is_some() { "redis" } else { "disk" });
+
+        let current = self.provider().await.provider_name();
+        if current == kind && kind != "redis" {
+            return Ok(());
+        }
+
+        let provider: Arc<dyn KvProvider> = match kind {
+            "memory" => Arc::new(MemoryKv::new()),
+            "disk" => {
+                let file = dirs::home_dir().unwrap_or_default().join(".axiom").join("kv").join("store.json");
+                Arc::new(DiskKv::open(file).inspect_err(|e| error!("Failed to open on-disk KV store: {}", e))?)
+            }
+            "redis" => {
+                let url = cache_url.ok_or_else(|| anyhow!("kv_provider is redis but no cache_url is set"))
+                    .inspect_err(|e| error!("{}", e))?;
+                Arc::new(RedisKv::new(url).await.inspect_err(|e| error!("Failed to initialize Redis KV at {}: {}", url, e))?)
+            }
+            other => {
+                error!("Unknown kv_provider '{}', keeping {}", other, current);
+                return Err(anyhow!("Unknown kv_provider '{}'", other));
+            }
+        };
+        info!("🗄️ KV store backend: {}", provider.provider_name());
+        *self.provider.write().await = provider;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn failed_cas_on_a_missing_key_leaves_nothing_behind() {
+        let kv = MemoryKv::new();
+        for i in 0..100 {
+            assert!(!kv.compare_and_swap(&format!("k{}", i), Some("old"), "new", None).await.unwrap());
+        }
+        assert!(kv.entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cas_compares_against_live_values_only() {
+        let kv = MemoryKv::new();
+        assert!(kv.compare_and_swap("k", None, "a", None).await.unwrap());
+        assert!(!kv.compare_and_swap("k", None, "b", None).await.unwrap());
+        assert!(kv.compare_and_swap("k", Some("a"), "b", None).await.unwrap());
+        assert_eq!(kv.get("k").await.unwrap().as_deref(), Some("b"));
+
+        kv.entries.insert("gone".into(), StoredValue { value: "x".into(), expires_at: Some(0) });
+        assert!(!kv.compare_and_swap("gone", Some("x"), "y", None).await.unwrap());
+        assert!(kv.compare_and_swap("gone", None, "y", None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn purge_drops_only_expired_entries() {
+        let kv = MemoryKv::new();
+        kv.set("live", "1", None).await.unwrap();
+        kv.set("later", "2", Some(Duration::from_secs(60))).await.unwrap();
+        kv.entries.insert("gone".into(), StoredValue { value: "3".into(), expires_at: Some(0) });
+        assert_eq!(kv.purge_expired().await.unwrap(), 1);
+        assert_eq!(kv.entries.len(), 2);
+        assert!(!kv.entries.contains_key("gone"));
+    }
+}
@@ -0,0 +1,114 @@
+/// Guest log capture — a bounded ring buffer per (tomain, slot) fed by the `axiom_log` host
+/// function, plus a broadcast channel for live tails over Server-Sent Events.
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Entries kept per (tomain, slot) before the oldest are evicted.
+const RING_CAPACITY: usize = 1000;
+/// Entries a slow tail subscriber may lag behind before it starts skipping.
+const TAIL_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    pub tomain_id: String,
+    pub slot: String,
+    pub level: String,
+    pub timestamp: DateTime<Utc>,
+    pub request_id: String,
+    pub message: String,
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Filters accepted by the query and tail endpoints.
+#[derive(Debug, Default, Deserialize)]
+pub struct LogQuery {
+    pub slot: Option<String>,
+    /// Minimum severity: error, warn, info, debug or trace
+    pub level: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
+}
+
+impl LogQuery {
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        self.slot.as_ref().is_none_or(|slot| entry.slot.eq_ignore_ascii_case(slot))
+            && self.level.as_ref().is_none_or(|level| severity(&entry.level) <= severity(level))
+            && self.since.is_none_or(|since| entry.timestamp >= since)
+            && self.until.is_none_or(|until| entry.timestamp <= until)
+    }
+}
+
+/// Maps the `axiom_log` ABI level to its name.
+pub fn level_name(level: u32) -> &'static str {
+    match level {
+        0 => "error",
+        1 => "warn",
+        2 => "info",
+        3 => "debug",
+        _ => "trace",
+    }
+}
+
+fn severity(level: &str) -> u8 {
+    match level.to_lowercase().as_str() {
+        "error" => 0,
+        "warn" => 1,
+        "info" => 2,
+        "debug" => 3,
+        _ => 4,
+    }
+}
+
+pub struct LogStore {
+    /// (tomain_id, slot) -> most recent entries, oldest first
+    pub buffers: Arc<DashMap<(String, String), VecDeque<LogEntry>>>,
+    tail: broadcast::Sender<LogEntry>,
+}
+
+impl LogStore {
+    pub fn new() -> Self {
+        let (tail, _) = broadcast::channel(TAIL_CAPACITY);
+        Self {
+            buffers: Arc::new(DashMap::new()),
+            tail,
+        }
+    }
+
+    pub fn push(&self, entry: LogEntry) {
+        {
+            let mut buffer = self.buffers
+                .entry((entry.tomain_id.clone(), entry.slot.clone()))
+                .or_insert_with(|| VecDeque::with_capacity(RING_CAPACITY));
+            if buffer.len() >= RING_CAPACITY {
+                buffer.pop_front();
+            }
+            buffer.push_back(entry.clone());
+        }
+        // No receivers is the common case; the send error is not interesting
+        let _ = self.tail.send(entry);
+    }
+
+    /// Returns matching entries across the tomain's slots, oldest first, capped at `limit`.
+    pub fn query(&self, tomain_id: &str, query: &LogQuery) -> Vec<LogEntry> {
+        let mut entries: Vec<LogEntry> = self.buffers.iter()
+            .filter(|b| b.key().0 == tomain_id)
+            .flat_map(|b| b.value().iter().filter(|e| query.matches(e)).cloned().collect::<Vec<_>>())
+            .collect();
+        entries.sort_by_key(|e| e.timestamp);
+
+        let limit = query.limit.unwrap_or(RING_CAPACITY);
+        if entries.len() > limit {
+            entries.drain(..entries.len() - limit);
+        }
+        entries
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<LogEntry> {
+        self.tail.subscribe()
+    }
+}
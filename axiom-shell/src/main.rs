@@ -2,8 +2,10 @@ use anyhow::{Result, Context};
 use std::sync::Arc;
 use tokio::net::{UnixListener, TcpListener};
 use tokio::io::AsyncReadExt;
-use tracing::{info, error, warn};
-use axum::{Router, routing::get, extract::{Path, State}, response::Html, Json};
+use tracing::{info, error, warn, Instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use axum::{Router, routing::get, extract::{ConnectInfo, Path, State}, response::Html, Json};
+use std::net::SocketAddr;
 use std::process::Command;
 
 mod runtime;
@@ -14,6 +16,20 @@ mod supervisor;
 mod egress;
 mod db;
 mod resilience;
+mod metrics;
+mod telemetry;
+mod logs;
+mod scheduler;
+mod jobs;
+mod events;
+mod kv;
+mod secrets;
+mod config;
+mod capabilities;
+mod egress_cache;
+mod chaos;
+mod oauth;
+mod tls;
 
 use crate::runtime::WasmSupervisor;
 
@@ -28,18 +44,31 @@ struct DeployPayload {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    tracing_subscriber::fmt::init();
+    let sampling = Arc::new(telemetry::SamplingRatios::new());
+    let _telemetry = telemetry::init(sampling.clone())?;
     info!("🚀 Booting Axiom Shell Supervisor...");
 
     // Recursive Startup: Ensure CCP is running before starting Shell
     ensure_ccp_running().await;
 
-    let supervisor = Arc::new(WasmSupervisor::new().await?);
+    let supervisor = Arc::new(WasmSupervisor::new(sampling).await?);
     
     // Load bindings from ~/.axiom/session.json into the live egress DashMap
     supervisor.egress.reload_from_registry();
+    supervisor.sampling.reload_from_registry();
     let _ = supervisor.db_registry.reload_from_registry().await;
     let _ = supervisor.resilience.reload_from_registry().await;
+    supervisor.scheduler.reload_from_registry();
+    let _ = supervisor.kv.reload_from_registry().await;
+    supervisor.secrets.reload_from_registry();
+    supervisor.config.reload_from_registry();
+    supervisor.capabilities.reload_from_registry();
+    supervisor.egress_cache.reload_from_registry();
+    supervisor.oauth.reload_from_registry();
+    supervisor.tls.reload_from_registry();
+    for (tomain_id, change) in supervisor.chaos.reload_from_registry() {
+        supervisor.audit_log.entry(tomain_id).or_default().push(change);
+    }
     
     // Cleanup port 9000 if in use
     cleanup_port(9000);
@@ -56,11 +85,36 @@ async fn main() -> Result<()> {
         }
     });
 
+    // 1b. Cron Scheduler Loop
+    tokio::spawn(scheduler::Scheduler::run(supervisor.clone()));
+
+    // 1c. Event Bus Router
+    tokio::spawn(events::EventBus::run(supervisor.clone()));
+
+    // 1d. Async job result and expired KV sweeper
+    let sv_jobs = supervisor.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+            sv_jobs.jobs.sweep_expired();
+            sv_jobs.kv.sweep_expired().await;
+        }
+    });
+
     // 2. Start HTTP Server for UI/Reflection (Team-Aware Refactoring Section #4)
     let supervisor_http = supervisor.clone();
     tokio::spawn(async move {
         let app = Router::new()
             .route("/", get(|| async { Html("<h1>Axiom Shell Status: ONLINE</h1>") }))
+            // Prometheus scrape endpoint
+            .route("/metrics", get(
+                |State(sv): State<Arc<WasmSupervisor>>| async move {
+                    axum::response::Response::builder()
+                        .header("Content-Type", "text/plain; version=0.0.4")
+                        .body(axum::body::Body::from(sv.render_metrics().await))
+                        .unwrap()
+                }
+            ))
             // Reflection Route
             .route("/reflect/{tomain}", get(
                 |Path(tomain): Path<String>, State(sv): State<Arc<WasmSupervisor>>| async move {
@@ -90,12 +144,90 @@ async fn main() -> Result<()> {
                     }
                 }
             ))
+            // Async Invocation: queues the call and answers 202 with a job id.
+            // An optional X-Axiom-Callback header names an egress alias to POST the finished job to.
+            .route("/async/{tomain}/{func}", axum::routing::post(
+                |Path((tomain, func)): Path<(String, String)>,
+                 uri: axum::http::Uri,
+                 headers: axum::http::HeaderMap,
+                 ConnectInfo(peer): ConnectInfo<SocketAddr>,
+                 State(sv): State<Arc<WasmSupervisor>>,
+                 body: axum::body::Bytes| async move {
+                    let limit = match ingress_guard(&sv, &tomain, &func, &headers, peer) {
+                        Ok(limit) => limit,
+                        Err(rejection) => return *rejection,
+                    };
+                    let args = invocation_args(&axum::http::Method::POST, &uri, &body);
+                    let callback = headers.get("X-Axiom-Callback")
+                        .and_then(|v| v.to_str().ok())
+                        .map(|v| v.to_string());
+
+                    // Ingress span: the job runs (and calls back) as part of the caller's trace
+                    let slot = sv.get_perspective(&tomain);
+                    let span = tracing::info_span!(
+                        "axiom.ingress",
+                        otel.kind = "server",
+                        axiom.slot = %slot,
+                        axiom.tomain = %tomain,
+                        axiom.function = %func,
+                        http.method = "POST",
+                        axiom.async = true,
+                    );
+                    span.set_parent(crate::telemetry::extract_context(&headers));
+
+                    let submitted = span.in_scope(|| jobs::JobStore::submit(sv.clone(), &tomain, &func, args, callback));
+                    let job_id = match submitted {
+                        Ok(job_id) => job_id,
+                        Err(shed) => {
+                            let mut response = axum::response::Response::builder()
+                                .status(axum::http::StatusCode::SERVICE_UNAVAILABLE)
+                                .header("Access-Control-Allow-Origin", "*")
+                                .header("Retry-After", shed.retry_after_secs.to_string())
+                                .body(axum::body::Body::from(shed.to_string()))
+                                .unwrap();
+                            rate_limit_headers(&mut response, &limit);
+                            return response;
+                        }
+                    };
+                    let body = serde_json::json!({
+                        "job_id": job_id,
+                        "status_url": format!("/jobs/{}", job_id),
+                    });
+                    let mut response = axum::response::Response::builder()
+                        .status(axum::http::StatusCode::ACCEPTED)
+                        .header("Content-Type", "application/json")
+                        .header("Location", format!("/jobs/{}", job_id))
+                        .header("Access-Control-Allow-Origin", "*")
+                        .body(axum::body::Body::from(body.to_string()))
+                        .unwrap();
+                    rate_limit_headers(&mut response, &limit);
+                    response
+                }
+            ))
+            // Job polling: status, attempts, result or error until the TTL expires
+            .route("/jobs/{id}", get(
+                |Path(id): Path<String>, State(sv): State<Arc<WasmSupervisor>>| async move {
+                    match sv.jobs.get(&id) {
+                        Some(job) => axum::response::Response::builder()
+                            .header("Content-Type", "application/json")
+                            .header("Access-Control-Allow-Origin", "*")
+                            .body(axum::body::Body::from(serde_json::to_string(&job).unwrap()))
+                            .unwrap(),
+                        None => axum::response::Response::builder()
+                            .status(404)
+                            .header("Access-Control-Allow-Origin", "*")
+                            .body(axum::body::Body::from(format!("Job '{}' not found or expired", id)))
+                            .unwrap(),
+                    }
+                }
+            ))
             // Invocation Route (Generic) - supports GET, POST, PUT, DELETE and CORS preflight
             .route("/{tomain}/{func}", axum::routing::any(
                 |method: axum::http::Method,
                  Path((tomain, func)): Path<(String, String)>, 
                  uri: axum::http::Uri,
                  headers: axum::http::HeaderMap,
+                 ConnectInfo(peer): ConnectInfo<SocketAddr>,
                  State(sv): State<Arc<WasmSupervisor>>,
                  body: axum::body::Bytes| async move {
                     // 1. Handle CORS preflight
@@ -109,83 +241,67 @@ async fn main() -> Result<()> {
                     }
 
                     // 2. Upstream Resilience Guards
-                    // a. Rate Limiting (Default 100 req/sec if not specified)
-                    if !sv.resilience.traffic.check_upstream(&tomain, 100.0) {
-                        return axum::response::Response::builder()
-                            .status(axum::http::StatusCode::TOO_MANY_REQUESTS)
-                            .header("Access-Control-Allow-Origin", "*")
-                            .body(axum::body::Body::from("Rate Limit Exceeded (Upstream)"))
-                            .unwrap();
-                    }
-
-                    // b. JWT Identity Validation (Pillar #9)
-                    if sv.resilience.security.public_keys.contains_key(&tomain) {
-                        let auth_valid = if let Some(auth_val) = headers.get("Authorization") {
-                            if let Ok(auth_str) = auth_val.to_str() {
-                                if auth_str.starts_with("Bearer ") {
-                                    let token = &auth_str[7..];
-                                    sv.resilience.security.validate_jwt(&tomain, token).is_ok()
-                                } else { false }
-                            } else { false }
-                        } else { false };
-
-                        if !auth_valid {
-                            return axum::response::Response::builder()
-                                .status(axum::http::StatusCode::UNAUTHORIZED)
-                                .header("Access-Control-Allow-Origin", "*")
-                                .body(axum::body::Body::from("Invalid or Missing Authorization Token"))
-                                .unwrap();
-                        }
-                    }
-                    let query_json = if method == axum::http::Method::POST 
-                        || method == axum::http::Method::PUT 
-                    {
-                        // For POST/PUT: prefer the request body
-                        if !body.is_empty() {
-                            String::from_utf8_lossy(&body).to_string()
-                        } else if let Some(query) = uri.query() {
-                            // Fallback to query params if body is empty
-                            let params: std::collections::HashMap<String, String> = 
-                                url::form_urlencoded::parse(query.as_bytes())
-                                    .map(|(k, v)| (k.to_string(), v.to_string()))
-                                    .collect();
-                            serde_json::to_string(&params).unwrap_or_else(|_| "{}".to_string())
-                        } else {
-                            "{}".to_string()
-                        }
-                    } else {
-                        // For GET: use query params
-                        if let Some(query) = uri.query() {
-                            let params: std::collections::HashMap<String, String> = 
-                                url::form_urlencoded::parse(query.as_bytes())
-                                    .map(|(k, v)| (k.to_string(), v.to_string()))
-                                    .collect();
-                            serde_json::to_string(&params).unwrap_or_else(|_| "{}".to_string())
-                        } else {
-                            "{}".to_string()
-                        }
+                    let limit = match ingress_guard(&sv, &tomain, &func, &headers, peer) {
+                        Ok(limit) => limit,
+                        Err(rejection) => return *rejection,
                     };
+                    let query_json = invocation_args(&method, &uri, &body);
+
+                    // Ingress span: continues the caller's W3C trace if a traceparent was sent
+                    let slot = sv.get_perspective(&tomain);
+                    let span = tracing::info_span!(
+                        "axiom.ingress",
+                        otel.kind = "server",
+                        axiom.slot = %slot,
+                        axiom.tomain = %tomain,
+                        axiom.function = %func,
+                        http.method = %method,
+                    );
+                    span.set_parent(crate::telemetry::extract_context(&headers));
 
-                    match sv.call(&tomain, &func, query_json).await {
+                    let mut response = match sv.call(&tomain, &func, query_json).instrument(span).await {
                         Ok(res) => axum::response::Response::builder()
                             .header("Content-Type", "text/plain")
                             .header("Access-Control-Allow-Origin", "*")
                             .body(axum::body::Body::from(res))
                             .unwrap(),
-                        Err(e) => axum::response::Response::builder()
-                            .status(500)
-                            .header("Access-Control-Allow-Origin", "*")
-                            .body(axum::body::Body::from(format!("Invocation Error: {}", e)))
-                            .unwrap(),
-                    }
+                        Err(e) => match e.downcast_ref::<resilience::Shed>() {
+                            Some(shed) => axum::response::Response::builder()
+                                .status(axum::http::StatusCode::SERVICE_UNAVAILABLE)
+                                .header("Access-Control-Allow-Origin", "*")
+                                .header("Retry-After", shed.retry_after_secs.to_string())
+                                .body(axum::body::Body::from(shed.to_string()))
+                                .unwrap(),
+                            None => axum::response::Response::builder()
+                                .status(500)
+                                .header("Access-Control-Allow-Origin", "*")
+                                .body(axum::body::Body::from(format!("Invocation Error: {}", e)))
+                                .unwrap(),
+                        },
+                    };
+                    rate_limit_headers(&mut response, &limit);
+                    response
                 }
             ))
             // Hot-reload endpoint: CCP calls this after any binding change
             .route("/admin/reload-bindings", axum::routing::post(
                 |State(sv): State<Arc<WasmSupervisor>>| async move {
                     sv.egress.reload_from_registry();
+                    sv.sampling.reload_from_registry();
                     let _ = sv.db_registry.reload_from_registry().await;
                     let _ = sv.resilience.reload_from_registry().await;
+                    sv.scheduler.reload_from_registry();
+                    sv.scheduler.resync_all(sv.clone()).await;
+                    let _ = sv.kv.reload_from_registry().await;
+                    sv.secrets.reload_from_registry();
+                    sv.config.reload_from_registry();
+                    sv.capabilities.reload_from_registry();
+                    sv.egress_cache.reload_from_registry();
+                    sv.oauth.reload_from_registry();
+                    sv.tls.reload_from_registry();
+                    for (tomain_id, change) in sv.chaos.reload_from_registry() {
+                        sv.audit_log.entry(tomain_id).or_default().push(change);
+                    }
                     axum::response::Response::builder()
                         .header("Content-Type", "text/plain")
                         .body(axum::body::Body::from("Bindings reloaded"))
@@ -259,11 +375,296 @@ async fn main() -> Result<()> {
                         .unwrap()
                 }
             ))
+            // Captured guest logs, filterable by slot, minimum level and time window
+            .route("/admin/logs/{tomain}", get(
+                |Path(tomain): Path<String>,
+                 axum::extract::Query(query): axum::extract::Query<crate::logs::LogQuery>,
+                 State(sv): State<Arc<WasmSupervisor>>| async move {
+                    let entries = sv.logs.query(&tomain, &query);
+                    axum::response::Response::builder()
+                        .header("Content-Type", "application/json")
+                        .header("Access-Control-Allow-Origin", "*")
+                        .body(axum::body::Body::from(serde_json::to_string(&entries).unwrap()))
+                        .unwrap()
+                }
+            ))
+            // Live tail over Server-Sent Events, one `log` event per entry
+            .route("/admin/logs/{tomain}/tail", get(
+                |Path(tomain): Path<String>,
+                 axum::extract::Query(query): axum::extract::Query<crate::logs::LogQuery>,
+                 State(sv): State<Arc<WasmSupervisor>>| async move {
+                    let rx = sv.logs.subscribe();
+                    let stream = futures::stream::unfold((rx, tomain, query), |(mut rx, tomain, query)| async move {
+                        loop {
+                            match rx.recv().await {
+                                Ok(entry) if entry.tomain_id == tomain && query.matches(&entry) => {
+                                    let event = axum::response::sse::Event::default()
+                                        .event("log")
+                                        .json_data(&entry);
+                                    return Some((event, (rx, tomain, query)));
+                                }
+                                Ok(_) => continue,
+                                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                                    warn!("Log tail for {} lagged, skipped {} entries", tomain, skipped);
+                                    continue;
+                                }
+                                Err(tokio::sync::broadcast::error::RecvError::Closed) => return None,
+                            }
+                        }
+                    });
+                    axum::response::sse::Sse::new(stream)
+                        .keep_alive(axum::response::sse::KeepAlive::default())
+                }
+            ))
+            // Slot lifecycle view: every held instance with its state and in-flight call count
+            .route("/admin/slots", get(
+                |State(sv): State<Arc<WasmSupervisor>>| async move {
+                    let mut instances = Vec::new();
+                    for env_map in sv.manager.tenants.read().await.values() {
+                        instances.extend(env_map.values().cloned());
+                    }
+                    instances.extend(sv.manager.draining.read().await.iter().cloned());
+
+                    let slots: Vec<serde_json::Value> = instances.iter().map(|t| serde_json::json!({
+                        "tomain_id": t.id,
+                        "env": t.env,
+                        "generation": t.generation,
+                        "lifecycle": t.lifecycle(),
+                        "in_flight": t.in_flight(),
+                        "config_version": sv.config.version(&t.id, &t.env),
+                    })).collect();
+                    let body = serde_json::json!({
+                        "slots": slots,
+                        "statuses": sv.registry.snapshot().await,
+                    });
+                    axum::response::Response::builder()
+                        .header("Content-Type", "application/json")
+                        .header("Access-Control-Allow-Origin", "*")
+                        .body(axum::body::Body::from(body.to_string()))
+                        .unwrap()
+                }
+            ))
+            // Cron schedules with next/last run and recent history
+            .route("/admin/schedules", get(
+                |State(sv): State<Arc<WasmSupervisor>>| async move {
+                    axum::response::Response::builder()
+                        .header("Content-Type", "application/json")
+                        .header("Access-Control-Allow-Origin", "*")
+                        .body(axum::body::Body::from(serde_json::to_string(&sv.scheduler.list(None)).unwrap()))
+                        .unwrap()
+                }
+            ))
+            .route("/admin/schedules/{tomain}", get(
+                |Path(tomain): Path<String>, State(sv): State<Arc<WasmSupervisor>>| async move {
+                    axum::response::Response::builder()
+                        .header("Content-Type", "application/json")
+                        .header("Access-Control-Allow-Origin", "*")
+                        .body(axum::body::Body::from(serde_json::to_string(&sv.scheduler.list(Some(&tomain))).unwrap()))
+                        .unwrap()
+                }
+            ))
+            .route("/admin/schedules/{tomain}/{func}/{action}", axum::routing::post(
+                |Path((tomain, func, action)): Path<(String, String, String)>, State(sv): State<Arc<WasmSupervisor>>| async move {
+                    let paused = match action.as_str() {
+                        "pause" => true,
+                        "resume" => false,
+                        _ => return axum::response::Response::builder()
+                            .status(400)
+                            .body(axum::body::Body::from(format!("Unknown schedule action '{}'", action)))
+                            .unwrap(),
+                    };
+                    if sv.scheduler.set_paused(&tomain, &func, paused) {
+                        axum::response::Response::builder()
+                            .header("Content-Type", "text/plain")
+                            .body(axum::body::Body::from(format!("Schedule {}/{} {}d", tomain, func, action)))
+                            .unwrap()
+                    } else {
+                        axum::response::Response::builder()
+                            .status(404)
+                            .body(axum::body::Body::from(format!("No schedule for {}/{}", tomain, func)))
+                            .unwrap()
+                    }
+                }
+            ))
+            // Dead-lettered async jobs and manual replay
+            .route("/admin/jobs/dead-letter", get(
+                |State(sv): State<Arc<WasmSupervisor>>| async move {
+                    axum::response::Response::builder()
+                        .header("Content-Type", "application/json")
+                        .header("Access-Control-Allow-Origin", "*")
+                        .body(axum::body::Body::from(serde_json::to_string(&sv.jobs.dead_letters().await).unwrap()))
+                        .unwrap()
+                }
+            ))
+            .route("/admin/jobs/dead-letter/{id}/retry", axum::routing::post(
+                |Path(id): Path<String>, State(sv): State<Arc<WasmSupervisor>>| async move {
+                    match jobs::JobStore::requeue(sv.clone(), &id).await {
+                        Some(Ok(job_id)) => axum::response::Response::builder()
+                            .status(axum::http::StatusCode::ACCEPTED)
+                            .header("Content-Type", "application/json")
+                            .body(axum::body::Body::from(serde_json::json!({ "job_id": job_id }).to_string()))
+                            .unwrap(),
+                        Some(Err(shed)) => axum::response::Response::builder()
+                            .status(axum::http::StatusCode::SERVICE_UNAVAILABLE)
+                            .header("Retry-After", shed.retry_after_secs.to_string())
+                            .body(axum::body::Body::from(shed.to_string()))
+                            .unwrap(),
+                        None => axum::response::Response::builder()
+                            .status(404)
+                            .body(axum::body::Body::from(format!("Job '{}' is not dead-lettered", id)))
+                            .unwrap(),
+                    }
+                }
+            ))
+            // Event bus: live subscriptions, dead-lettered deliveries and manual redelivery
+            .route("/admin/events/subscriptions", get(
+                |State(sv): State<Arc<WasmSupervisor>>| async move {
+                    axum::response::Response::builder()
+                        .header("Content-Type", "application/json")
+                        .header("Access-Control-Allow-Origin", "*")
+                        .body(axum::body::Body::from(serde_json::to_string(&sv.events.subscriptions()).unwrap()))
+                        .unwrap()
+                }
+            ))
+            .route("/admin/events/dead-letter", get(
+                |State(sv): State<Arc<WasmSupervisor>>| async move {
+                    axum::response::Response::builder()
+                        .header("Content-Type", "application/json")
+                        .header("Access-Control-Allow-Origin", "*")
+                        .body(axum::body::Body::from(serde_json::to_string(&sv.events.dead_letters().await).unwrap()))
+                        .unwrap()
+                }
+            ))
+            .route("/admin/events/dead-letter/{id}/retry", axum::routing::post(
+                |Path(id): Path<String>, State(sv): State<Arc<WasmSupervisor>>| async move {
+                    match sv.events.redeliver(&id).await {
+                        Ok(_) => axum::response::Response::builder()
+                            .status(axum::http::StatusCode::ACCEPTED)
+                            .body(axum::body::Body::from(format!("Event {} requeued", id)))
+                            .unwrap(),
+                        Err(e) => axum::response::Response::builder()
+                            .status(404)
+                            .body(axum::body::Body::from(e.to_string()))
+                            .unwrap(),
+                    }
+                }
+            ))
+            // Egress response cache: size/entry stats and purge by tomain, env and/or alias
+            .route("/admin/egress-cache", get(
+                |State(sv): State<Arc<WasmSupervisor>>| async move {
+                    axum::response::Response::builder()
+                        .header("Content-Type", "application/json")
+                        .header("Access-Control-Allow-Origin", "*")
+                        .body(axum::body::Body::from(serde_json::to_string(&sv.egress_cache.stats()).unwrap()))
+                        .unwrap()
+                }
+            ))
+            .route("/admin/egress-cache/purge", axum::routing::post(
+                |State(sv): State<Arc<WasmSupervisor>>, Json(payload): Json<serde_json::Value>| async move {
+                    let purged = sv.egress_cache.purge(
+                        payload["tomain_id"].as_str(),
+                        payload["environment"].as_str(),
+                        payload["alias"].as_str(),
+                    );
+                    info!("🧹 Egress cache: Purged {} entries ({})", purged, payload);
+                    sv.metrics.set_gauge("axiom_egress_cache_bytes", &[], sv.egress_cache.stats().bytes as f64);
+                    axum::response::Response::builder()
+                        .header("Content-Type", "application/json")
+                        .header("Access-Control-Allow-Origin", "*")
+                        .body(axum::body::Body::from(serde_json::json!({ "purged": purged }).to_string()))
+                        .unwrap()
+                }
+            ))
+            // Fault injection rules currently loaded, and whether each one applies
+            .route("/admin/chaos", get(
+                |State(sv): State<Arc<WasmSupervisor>>| async move {
+                    axum::response::Response::builder()
+                        .header("Content-Type", "application/json")
+                        .header("Access-Control-Allow-Origin", "*")
+                        .body(axum::body::Body::from(serde_json::to_string(&sv.chaos.statuses()).unwrap()))
+                        .unwrap()
+                }
+            ))
+            // OAuth2 client-credential bindings and when their cached token expires
+            .route("/admin/oauth", get(
+                |State(sv): State<Arc<WasmSupervisor>>| async move {
+                    axum::response::Response::builder()
+                        .header("Content-Type", "application/json")
+                        .header("Access-Control-Allow-Origin", "*")
+                        .body(axum::body::Body::from(serde_json::to_string(&sv.oauth.statuses().await).unwrap()))
+                        .unwrap()
+                }
+            ))
+            // TLS profiles per binding (secret refs only, never key material) and pooled client count
+            .route("/admin/tls", get(
+                |State(sv): State<Arc<WasmSupervisor>>| async move {
+                    let body = serde_json::json!({
+                        "profiles": sv.tls.statuses(),
+                        "pooled_clients": sv.tls.pooled(),
+                    });
+                    axum::response::Response::builder()
+                        .header("Content-Type", "application/json")
+                        .header("Access-Control-Allow-Origin", "*")
+                        .body(axum::body::Body::from(body.to_string()))
+                        .unwrap()
+                }
+            ))
+            // Bulkheads: in-flight, queued and current limit per tomain slot and egress alias
+            .route("/admin/bulkheads", get(
+                |State(sv): State<Arc<WasmSupervisor>>| async move {
+                    axum::response::Response::builder()
+                        .header("Content-Type", "application/json")
+                        .header("Access-Control-Allow-Origin", "*")
+                        .body(axum::body::Body::from(serde_json::to_string(&sv.resilience.bulkheads.statuses()).unwrap()))
+                        .unwrap()
+                }
+            ))
+            // Circuit breakers per (tomain, env, alias): inspect, force open, reset
+            .route("/admin/breakers", get(
+                |State(sv): State<Arc<WasmSupervisor>>| async move {
+                    axum::response::Response::builder()
+                        .header("Content-Type", "application/json")
+                        .header("Access-Control-Allow-Origin", "*")
+                        .body(axum::body::Body::from(serde_json::to_string(&sv.resilience.fault.statuses()).unwrap()))
+                        .unwrap()
+                }
+            ))
+            .route("/admin/breakers/{tomain}", get(
+                |Path(tomain): Path<String>, State(sv): State<Arc<WasmSupervisor>>| async move {
+                    let statuses: Vec<_> = sv.resilience.fault.statuses().into_iter().filter(|b| b.tomain_id == tomain).collect();
+                    axum::response::Response::builder()
+                        .header("Content-Type", "application/json")
+                        .header("Access-Control-Allow-Origin", "*")
+                        .body(axum::body::Body::from(serde_json::to_string(&statuses).unwrap()))
+                        .unwrap()
+                }
+            ))
+            .route("/admin/breakers/{tomain}/{env}/{alias}/{action}", axum::routing::post(
+                |Path((tomain, env, alias, action)): Path<(String, String, String, String)>, State(sv): State<Arc<WasmSupervisor>>| async move {
+                    let key = (tomain, env.to_uppercase(), alias);
+                    let transition = match action.as_str() {
+                        "open" => sv.resilience.fault.force_open(&key),
+                        "reset" => sv.resilience.fault.reset(&key),
+                        _ => return axum::response::Response::builder()
+                            .status(400)
+                            .body(axum::body::Body::from(format!("Unknown action '{}' (use open or reset)", action)))
+                            .unwrap(),
+                    };
+                    if let Some(transition) = transition {
+                        sv.announce_breaker(transition);
+                    }
+                    info!("🧯 Breaker {}/{}/{}: {} by operator", key.0, key.1, key.2, action);
+                    axum::response::Response::builder()
+                        .header("Content-Type", "application/json")
+                        .body(axum::body::Body::from(serde_json::json!({ "state": sv.resilience.fault.get_status(&key) }).to_string()))
+                        .unwrap()
+                }
+            ))
             .with_state(supervisor_http);
             
         let tcp_listener = TcpListener::bind(HTTP_PORT).await.expect("Failed to bind Shell HTTP port");
         info!("🌐 Shell HTTP Server active on http://localhost:9000");
-        if let Err(e) = axum::serve(tcp_listener, app).await {
+        if let Err(e) = axum::serve(tcp_listener, app.into_make_service_with_connect_info::<SocketAddr>()).await {
             error!("HTTP Server crashed: {:#}", e);
         }
     });
@@ -302,6 +703,101 @@ async fn main() -> Result<()> {
     }
 }
 
+/// Upstream guards shared by sync and async ingress: JWT identity, then rate limiting by the
+/// caller identity the tomain's policy keys on. Returns the limit state for the response headers,
+/// or the rejection response when the request may not proceed.
+fn ingress_guard(
+    sv: &WasmSupervisor,
+    tomain: &str,
+    func: &str,
+    headers: &axum::http::HeaderMap,
+    peer: SocketAddr,
+) -> std::result::Result<resilience::RateLimitDecision, Box<axum::response::Response>> {
+    let mut caller = resilience::CallerIdentity {
+        client_ip: Some(peer.ip()),
+        ..Default::default()
+    };
+
+    // a. JWT Identity Validation (Pillar #9)
+    if sv.resilience.security.public_keys.contains_key(tomain) {
+        let claims = headers.get("Authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .and_then(|token| sv.resilience.security.validate_jwt(tomain, token).ok());
+
+        match claims {
+            Some(claims) => caller.subject = Some(claims.sub),
+            None => return Err(Box::new(axum::response::Response::builder()
+                .status(axum::http::StatusCode::UNAUTHORIZED)
+                .header("Access-Control-Allow-Origin", "*")
+                .body(axum::body::Body::from("Invalid or Missing Authorization Token"))
+                .unwrap())),
+        }
+    }
+    if let Some(header) = sv.resilience.traffic.api_key_header(tomain) {
+        caller.api_key = headers.get(header.as_str()).and_then(|v| v.to_str().ok()).map(|v| v.to_string());
+    }
+
+    // b. Rate Limiting (100 req/sec per tomain unless a policy says otherwise)
+    let limit = sv.resilience.traffic.check_upstream(tomain, func, &caller);
+    if !limit.allowed {
+        sv.metrics.inc_counter("axiom_upstream_rate_limited_total", &[("tomain", tomain)], 1.0);
+        let mut response = axum::response::Response::builder()
+            .status(axum::http::StatusCode::TOO_MANY_REQUESTS)
+            .header("Access-Control-Allow-Origin", "*")
+            .header("Retry-After", limit.retry_after_secs.to_string())
+            .body(axum::body::Body::from("Rate Limit Exceeded (Upstream)"))
+            .unwrap();
+        rate_limit_headers(&mut response, &limit);
+        return Err(Box::new(response));
+    }
+    Ok(limit)
+}
+
+/// `RateLimit-Limit`, `RateLimit-Remaining` and `RateLimit-Reset` for the caller's bucket.
+fn rate_limit_headers(response: &mut axum::response::Response, limit: &resilience::RateLimitDecision) {
+    let headers = response.headers_mut();
+    for (name, value) in [
+        ("RateLimit-Limit", limit.limit),
+        ("RateLimit-Remaining", limit.remaining),
+        ("RateLimit-Reset", limit.reset_secs),
+    ] {
+        headers.insert(name, value.into());
+    }
+}
+
+/// Builds the kernel's JSON arguments: the body for POST/PUT, otherwise the query string.
+fn invocation_args(method: &axum::http::Method, uri: &axum::http::Uri, body: &[u8]) -> String {
+    if method == axum::http::Method::POST 
+        || method == axum::http::Method::PUT 
+    {
+        // For POST/PUT: prefer the request body
+        if !body.is_empty() {
+            String::from_utf8_lossy(body).to_string()
+        } else if let Some(query) = uri.query() {
+            // Fallback to query params if body is empty
+            let params: std::collections::HashMap<String, String> = 
+                url::form_urlencoded::parse(query.as_bytes())
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect();
+            serde_json::to_string(&params).unwrap_or_else(|_| "{}".to_string())
+        } else {
+            "{}".to_string()
+        }
+    } else {
+        // For GET: use query params
+        if let Some(query) = uri.query() {
+            let params: std::collections::HashMap<String, String> = 
+                url::form_urlencoded::parse(query.as_bytes())
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect();
+            serde_json::to_string(&params).unwrap_or_else(|_| "{}".to_string())
+        } else {
+            "{}".to_string()
+        }
+    }
+}
+
 fn cleanup_port(port: u16) {
     let _ = Command::new("sh")
         .arg("-c")
@@ -340,3 +836,38 @@ async fn ensure_ccp_running() {
         info!("📡 Axiom CCP is already healthy on port 3000.");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn rejected_ingress_carries_rate_limit_headers() {
+        let sv = WasmSupervisor::new(Arc::new(telemetry::SamplingRatios::new())).await.unwrap();
+        sv.resilience.traffic.upstream_policies.insert(
+            "acme".to_string(),
+            serde_json::from_value::<resilience::RateLimitPolicy>(serde_json::json!({ "key_by": "client_ip", "rate_per_sec": 0.5, "burst": 2.0 })).unwrap(),
+        );
+        let headers = axum::http::HeaderMap::new();
+        let peer: SocketAddr = "10.0.0.7:5000".parse().unwrap();
+
+        let first = ingress_guard(&sv, "acme", "list", &headers, peer).unwrap();
+        assert_eq!((first.limit, first.remaining), (2, 1));
+        let mut ok = axum::response::Response::new(axum::body::Body::empty());
+        rate_limit_headers(&mut ok, &first);
+        assert_eq!(ok.headers()["RateLimit-Limit"], "2");
+        assert_eq!(ok.headers()["RateLimit-Remaining"], "1");
+        assert_eq!(ok.headers()["RateLimit-Reset"], "2");
+
+        ingress_guard(&sv, "acme", "list", &headers, peer).unwrap();
+        let rejected = ingress_guard(&sv, "acme", "list", &headers, peer).unwrap_err();
+        assert_eq!(rejected.status(), axum::http::StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(rejected.headers()["Retry-After"], "2");
+        assert_eq!(rejected.headers()["RateLimit-Remaining"], "0");
+        assert_eq!(rejected.headers()["RateLimit-Reset"], "4");
+
+        // Another client still has its own budget
+        let other: SocketAddr = "10.0.0.8:5000".parse().unwrap();
+        assert!(ingress_guard(&sv, "acme", "list", &headers, other).is_ok());
+    }
+}
@@ -0,0 +1,447 @@
+/// Prometheus metrics — in-memory families rendered in the text exposition format on GET /metrics.
+/// Every family caps its number of label sets; anything past the cap folds into an overflow series
+/// so a misbehaving guest (or a scan of random URLs) can't grow the registry without bound.
+use dashmap::DashMap;
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+/// Max distinct label sets per family before new ones fold into the overflow series.
+pub const MAX_SERIES_PER_FAMILY: usize = 2000;
+pub const OVERFLOW_LABEL: &str = "__overflow__";
+
+/// Prefix for metrics recorded by kernels through the `axiom_metric` host import. No Shell family
+/// uses it, so a guest name can never land on one of the Shell's own series.
+pub const GUEST_PREFIX: &str = "axiom_kernel_";
+/// Distinct metric names one tomain may register.
+pub const MAX_GUEST_METRICS_PER_TOMAIN: usize = 50;
+/// Label pairs one guest sample may carry (tomain and slot are added by the shell).
+pub const MAX_GUEST_LABELS: usize = 8;
+/// Distinct label sets one tomain may create across all of its metrics.
+pub const MAX_GUEST_SERIES_PER_TOMAIN: usize = 500;
+
+const DEFAULT_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl MetricKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+            MetricKind::Histogram => "histogram",
+        }
+    }
+}
+
+type LabelSet = Vec<(String, String)>;
+
+/// Kind codes used by the `axiom_metric` ABI.
+pub fn guest_kind(code: u32) -> Option<MetricKind> {
+    match code {
+        0 => Some(MetricKind::Counter),
+        1 => Some(MetricKind::Gauge),
+        2 => Some(MetricKind::Histogram),
+        _ => None,
+    }
+}
+
+enum Series {
+    Value(f64),
+    Histogram { buckets: Vec<u64>, sum: f64, count: u64 },
+}
+
+pub struct MetricFamily {
+    pub kind: MetricKind,
+    pub help: String,
+    bounds: Vec<f64>,
+    series: DashMap<LabelSet, Series>,
+}
+
+impl MetricFamily {
+    fn new(kind: MetricKind, help: &str) -> Self {
+        Self {
+            kind,
+            help: help.to_string(),
+            bounds: DEFAULT_BUCKETS.to_vec(),
+            series: DashMap::new(),
+        }
+    }
+
+    fn label_set(&self, labels: &[(&str, &str)]) -> LabelSet {
+        let key: LabelSet = labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        if self.series.len() >= MAX_SERIES_PER_FAMILY && !self.series.contains_key(&key) {
+            return labels.iter().map(|(k, _)| (k.to_string(), OVERFLOW_LABEL.to_string())).collect();
+        }
+        key
+    }
+}
+
+pub struct MetricsRegistry {
+    pub families: Arc<DashMap<String, MetricFamily>>,
+    /// tomain_id -> guest metric names it has registered
+    guest_names: Arc<DashMap<String, std::collections::HashSet<String>>>,
+    /// tomain_id -> distinct guest label sets it has created
+    guest_series: Arc<DashMap<String, std::collections::HashSet<(String, LabelSet)>>>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        let registry = Self {
+            families: Arc::new(DashMap::new()),
+            guest_names: Arc::new(DashMap::new()),
+            guest_series: Arc::new(DashMap::new()),
+        };
+
+        // Invocations
+        registry.describe("axiom_invocations_total", MetricKind::Counter, "Kernel invocations by tomain, slot, function and outcome");
+        registry.describe("axiom_invocation_duration_seconds", MetricKind::Histogram, "End-to-end kernel invocation latency");
+        registry.describe("axiom_fuel_consumed_total", MetricKind::Counter, "Wasm fuel consumed by kernel invocations");
+        registry.describe("axiom_instantiate_duration_seconds", MetricKind::Histogram, "Time spent instantiating a kernel module");
+        registry.describe("axiom_memory_high_water_bytes", MetricKind::Gauge, "Largest linear memory observed for a slot");
+        registry.describe("axiom_upstream_rate_limited_total", MetricKind::Counter, "Ingress requests rejected by the upstream rate limiter");
+        registry.describe("axiom_tomain_calls_total", MetricKind::Counter, "In-process kernel-to-kernel calls by caller, target and outcome");
+        registry.describe("axiom_jobs_total", MetricKind::Counter, "Async jobs by tomain and status (queued, succeeded, failed)");
+
+        // Egress
+        registry.describe("axiom_egress_requests_total", MetricKind::Counter, "Egress HTTP attempts by alias and status class");
+        registry.describe("axiom_egress_retries_total", MetricKind::Counter, "Egress retry attempts by alias");
+        registry.describe("axiom_egress_rate_limited_total", MetricKind::Counter, "Egress calls rejected by the downstream rate limiter");
+        registry.describe("axiom_egress_denied_total", MetricKind::Counter, "Egress calls refused before leaving the Shell (no_binding, policy_denied)");
+        registry.describe("axiom_egress_circuit_rejections_total", MetricKind::Counter, "Egress calls rejected by an open circuit breaker");
+        registry.describe("axiom_circuit_breaker_state", MetricKind::Gauge, "Circuit breaker state per alias (0=closed, 1=half-open, 2=open)");
+
+        // Events
+        registry.describe("axiom_events_published_total", MetricKind::Counter, "Events published by tomain and outcome (accepted, denied, error)");
+        registry.describe("axiom_event_deliveries_total", MetricKind::Counter, "Event deliveries by subscribing tomain and outcome (acked, retried, dead_lettered)");
+
+        // Database
+        registry.describe("axiom_db_queries_total", MetricKind::Counter, "Database bridge queries by alias and outcome");
+        registry.describe("axiom_db_query_duration_seconds", MetricKind::Histogram, "Database bridge query latency by alias");
+
+        // Key-value store
+        registry.describe("axiom_kv_operations_total", MetricKind::Counter, "Key-value store operations by tomain, op and outcome");
+
+        // Secrets
+        registry.describe("axiom_secret_accesses_total", MetricKind::Counter, "Kernel secret reads by tomain and outcome (granted, denied, missing)");
+
+        // Tenants
+        registry.describe("axiom_tenants_loaded", MetricKind::Gauge, "Tenant instances loaded per slot and lifecycle state");
+        registry.describe("axiom_guest_metric_rejections_total", MetricKind::Counter, "Guest metric samples dropped for breaking naming or per-tenant limits");
+
+        registry
+    }
+
+    pub fn describe(&self, name: &str, kind: MetricKind, help: &str) {
+        self.families.entry(name.to_string()).or_insert_with(|| MetricFamily::new(kind, help));
+    }
+
+    pub fn inc_counter(&self, name: &str, labels: &[(&str, &str)], by: f64) {
+        if let Some(family) = self.families.get(name) {
+            let key = family.label_set(labels);
+            let mut entry = family.series.entry(key).or_insert(Series::Value(0.0));
+            if let Series::Value(v) = entry.value_mut() {
+                *v += by;
+            }
+        }
+    }
+
+    pub fn set_gauge(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        if let Some(family) = self.families.get(name) {
+            let key = family.label_set(labels);
+            family.series.insert(key, Series::Value(value));
+        }
+    }
+
+    /// Raises a gauge to `value` if it is higher than the current reading (high-water marks).
+    pub fn max_gauge(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        if let Some(family) = self.families.get(name) {
+            let key = family.label_set(labels);
+            let mut entry = family.series.entry(key).or_insert(Series::Value(value));
+            if let Series::Value(v) = entry.value_mut() {
+                *v = v.max(value);
+            }
+        }
+    }
+
+    pub fn observe(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        if let Some(family) = self.families.get(name) {
+            let key = family.label_set(labels);
+            let bucket_count = family.bounds.len();
+            let mut entry = family.series.entry(key).or_insert_with(|| Series::Histogram {
+                buckets: vec![0; bucket_count],
+                sum: 0.0,
+                count: 0,
+            });
+            if let Series::Histogram { buckets, sum, count } = entry.value_mut() {
+                for (i, bound) in family.bounds.iter().enumerate() {
+                    if value <= *bound {
+                        buckets[i] += 1;
+                    }
+                }
+                *sum += value;
+                *count += 1;
+            }
+        }
+    }
+
+    /// Records a sample from a kernel under `axiom_kernel_<name>`, labelled with its tomain and slot.
+    /// Enforces naming rules and the per-tenant metric, label and series limits.
+    pub fn record_guest(&self, tomain_id: &str, slot: &str, kind: MetricKind, name: &str, labels: &[(String, String)], value: f64) -> Result<(), String> {
+        let result = self.check_guest(tomain_id, kind, name, labels);
+        if let Err(reason) = &result {
+            self.inc_counter("axiom_guest_metric_rejections_total", &[("tomain", tomain_id)], 1.0);
+            return Err(reason.clone());
+        }
+
+        let family_name = format!("{}{}", GUEST_PREFIX, name);
+        let mut all_labels: Vec<(&str, &str)> = vec![("tomain", tomain_id), ("slot", slot)];
+        all_labels.extend(labels.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        match kind {
+            MetricKind::Counter => self.inc_counter(&family_name, &all_labels, value.max(0.0)),
+            MetricKind::Gauge => self.set_gauge(&family_name, &all_labels, value),
+            MetricKind::Histogram => self.observe(&family_name, &all_labels, value),
+        }
+        result
+    }
+
+    fn check_guest(&self, tomain_id: &str, kind: MetricKind, name: &str, labels: &[(String, String)]) -> Result<(), String> {
+        if !is_valid_name(name) {
+            return Err(format!("invalid metric name '{}'", name));
+        }
+        if labels.len() > MAX_GUEST_LABELS {
+            return Err(format!("'{}' has {} labels (max {})", name, labels.len(), MAX_GUEST_LABELS));
+        }
+        for (k, _) in labels {
+            if !is_valid_name(k) || k == "tomain" || k == "slot" || k == "le" || k.starts_with("__") {
+                return Err(format!("invalid or reserved label '{}' on '{}'", k, name));
+            }
+        }
+
+        let family_name = format!("{}{}", GUEST_PREFIX, name);
+        {
+            let mut names = self.guest_names.entry(tomain_id.to_string()).or_default();
+            if !names.contains(name) {
+                if names.len() >= MAX_GUEST_METRICS_PER_TOMAIN {
+                    return Err(format!("metric limit reached ({} per tomain)", MAX_GUEST_METRICS_PER_TOMAIN));
+                }
+                names.insert(name.to_string());
+            }
+        }
+
+        // First kernel to register a name fixes its type for everyone
+        let family = self.families.entry(family_name).or_insert_with(|| MetricFamily::new(kind, &format!("Kernel metric '{}'", name)));
+        if family.kind != kind {
+            return Err(format!("'{}' is already registered as a {}", name, family.kind.as_str()));
+        }
+        drop(family);
+
+        let mut sorted = labels.to_vec();
+        sorted.sort();
+        let mut series = self.guest_series.entry(tomain_id.to_string()).or_default();
+        let key = (name.to_string(), sorted);
+        if !series.contains(&key) {
+            if series.len() >= MAX_GUEST_SERIES_PER_TOMAIN {
+                return Err(format!("series limit reached ({} per tomain)", MAX_GUEST_SERIES_PER_TOMAIN));
+            }
+            series.insert(key);
+        }
+        Ok(())
+    }
+
+    /// Drops every series of a family, used for gauges that are recomputed on each scrape.
+    pub fn reset(&self, name: &str) {
+        if let Some(family) = self.families.get(name) {
+            family.series.clear();
+        }
+    }
+
+    /// Renders every family in the Prometheus text exposition format (v0.0.4).
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let mut names: Vec<String> = self.families.iter().map(|f| f.key().clone()).collect();
+        names.sort();
+
+        for name in names {
+            let Some(family) = self.families.get(&name) else { continue };
+            if family.series.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "# HELP {} {}", name, family.help);
+            let _ = writeln!(out, "# TYPE {} {}", name, family.kind.as_str());
+
+            for series in family.series.iter() {
+                let labels = series.key();
+                match series.value() {
+                    Series::Value(v) => {
+                        let _ = writeln!(out, "{}{} {}", name, format_labels(labels, None), v);
+                    }
+                    Series::Histogram { buckets, sum, count } => {
+                        for (i, bound) in family.bounds.iter().enumerate() {
+                            let _ = writeln!(out, "{}_bucket{} {}", name, format_labels(labels, Some(&bound.to_string())), buckets[i]);
+                        }
+                        let _ = writeln!(out, "{}_bucket{} {}", name, format_labels(labels, Some("+Inf")), count);
+                        let _ = writeln!(out, "{}_sum{} {}", name, format_labels(labels, None), sum);
+                        let _ = writeln!(out, "{}_count{} {}", name, format_labels(labels, None), count);
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+fn format_labels(labels: &[(String, String)], le: Option<&str>) -> String {
+    let mut parts: Vec<String> = labels.iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape_label(v)))
+        .collect();
+    if let Some(le) = le {
+        parts.push(format!("le=\"{}\"", le));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", parts.join(","))
+    }
+}
+
+/// Prometheus metric/label name rule: `[a-zA-Z_][a-zA-Z0-9_]*`.
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    name.len() <= 128 && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Buckets an HTTP status into its class label (2xx, 4xx, ...).
+pub fn status_class(status: u16) -> &'static str {
+    match status {
+        100..=199 => "1xx",
+        200..=299 => "2xx",
+        300..=399 => "3xx",
+        400..=499 => "4xx",
+        _ => "5xx",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series_count(registry: &MetricsRegistry, name: &str) -> usize {
+        registry.families.get(name).map(|f| f.series.len()).unwrap_or(0)
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn family_folds_new_label_sets_into_overflow_at_the_cap() {
+        let registry = MetricsRegistry::new();
+        for i in 0..MAX_SERIES_PER_FAMILY {
+            registry.inc_counter("axiom_jobs_total", &[("tomain", &i.to_string())], 1.0);
+        }
+        registry.inc_counter("axiom_jobs_total", &[("tomain", "late")], 1.0);
+        registry.inc_counter("axiom_jobs_total", &[("tomain", "later")], 1.0);
+        // Existing series keep counting past the cap
+        registry.inc_counter("axiom_jobs_total", &[("tomain", "0")], 1.0);
+
+        assert_eq!(series_count(&registry, "axiom_jobs_total"), MAX_SERIES_PER_FAMILY + 1);
+        let rendered = registry.render();
+        assert!(rendered.contains(&format!("axiom_jobs_total{{tomain=\"{}\"}} 2", OVERFLOW_LABEL)), "{}", rendered);
+        assert!(rendered.contains("axiom_jobs_total{tomain=\"0\"} 2"));
+        assert!(!rendered.contains("tomain=\"late\""));
+    }
+
+    #[test]
+    fn guest_metrics_are_prefixed_and_scoped() {
+        let registry = MetricsRegistry::new();
+        registry.record_guest("acme", "PROD", MetricKind::Counter, "orders", &labels(&[("region", "eu")]), 3.0).unwrap();
+
+        let rendered = registry.render();
+        assert!(rendered.contains("axiom_kernel_orders{tomain=\"acme\",slot=\"PROD\",region=\"eu\"} 3"), "{}", rendered);
+    }
+
+    #[test]
+    fn guest_names_and_labels_are_validated() {
+        let registry = MetricsRegistry::new();
+        let ok = labels(&[("region", "eu")]);
+        assert!(registry.record_guest("acme", "PROD", MetricKind::Counter, "9lives", &ok, 1.0).is_err());
+        assert!(registry.record_guest("acme", "PROD", MetricKind::Counter, "bad-name", &ok, 1.0).is_err());
+        for reserved in ["tomain", "slot", "le", "__name"] {
+            let err = registry.record_guest("acme", "PROD", MetricKind::Counter, "orders", &labels(&[(reserved, "x")]), 1.0);
+            assert!(err.is_err(), "label '{}' should be reserved", reserved);
+        }
+
+        let too_many: Vec<(String, String)> = (0..=MAX_GUEST_LABELS).map(|i| (format!("l{}", i), "v".to_string())).collect();
+        assert!(registry.record_guest("acme", "PROD", MetricKind::Counter, "orders", &too_many, 1.0).is_err());
+    }
+
+    #[test]
+    fn first_registration_fixes_a_guest_metric_kind() {
+        let registry = MetricsRegistry::new();
+        registry.record_guest("acme", "PROD", MetricKind::Gauge, "depth", &[], 1.0).unwrap();
+        let err = registry.record_guest("globex", "PROD", MetricKind::Counter, "depth", &[], 1.0).unwrap_err();
+        assert!(err.contains("already registered as a gauge"), "{}", err);
+    }
+
+    #[test]
+    fn guest_metric_names_are_capped_per_tomain() {
+        let registry = MetricsRegistry::new();
+        for i in 0..MAX_GUEST_METRICS_PER_TOMAIN {
+            registry.record_guest("acme", "PROD", MetricKind::Counter, &format!("m{}", i), &[], 1.0).unwrap();
+        }
+        assert!(registry.record_guest("acme", "PROD", MetricKind::Counter, "one_more", &[], 1.0).is_err());
+        // Names already registered and other tomains are unaffected
+        assert!(registry.record_guest("acme", "PROD", MetricKind::Counter, "m0", &[], 1.0).is_ok());
+        assert!(registry.record_guest("globex", "PROD", MetricKind::Counter, "one_more", &[], 1.0).is_ok());
+    }
+
+    #[test]
+    fn guest_series_are_capped_per_tomain() {
+        let registry = MetricsRegistry::new();
+        for i in 0..MAX_GUEST_SERIES_PER_TOMAIN {
+            let set = labels(&[("user", &i.to_string())]);
+            registry.record_guest("acme", "PROD", MetricKind::Counter, "hits", &set, 1.0).unwrap();
+        }
+        let err = registry.record_guest("acme", "PROD", MetricKind::Counter, "hits", &labels(&[("user", "new")]), 1.0).unwrap_err();
+        assert!(err.contains("series limit"), "{}", err);
+        assert_eq!(series_count(&registry, "axiom_kernel_hits"), MAX_GUEST_SERIES_PER_TOMAIN);
+
+        // Label order doesn't make a new series
+        let set = labels(&[("a", "1"), ("b", "2")]);
+        let reversed = labels(&[("b", "2"), ("a", "1")]);
+        registry.record_guest("globex", "PROD", MetricKind::Counter, "pairs", &set, 1.0).unwrap();
+        registry.record_guest("globex", "PROD", MetricKind::Counter, "pairs", &reversed, 1.0).unwrap();
+        assert_eq!(registry.guest_series.get("globex").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn rejections_are_counted_per_tomain() {
+        let registry = MetricsRegistry::new();
+        let _ = registry.record_guest("acme", "PROD", MetricKind::Counter, "bad-name", &[], 1.0);
+        assert!(registry.render().contains("axiom_guest_metric_rejections_total{tomain=\"acme\"} 1"));
+    }
+
+    #[test]
+    fn histograms_render_cumulative_buckets() {
+        let registry = MetricsRegistry::new();
+        registry.observe("axiom_db_query_duration_seconds", &[("alias", "main")], 0.02);
+        let rendered = registry.render();
+        assert!(rendered.contains("axiom_db_query_duration_seconds_bucket{alias=\"main\",le=\"0.01\"} 0"));
+        assert!(rendered.contains("axiom_db_query_duration_seconds_bucket{alias=\"main\",le=\"0.025\"} 1"));
+        assert!(rendered.contains("axiom_db_query_duration_seconds_bucket{alias=\"main\",le=\"+Inf\"} 1"));
+        assert!(rendered.contains("axiom_db_query_duration_seconds_count{alias=\"main\"} 1"));
+    }
+}
@@ -0,0 +1,230 @@
+/// OAuth2 client credentials for egress bindings. A binding listed in session.json `oauth2_clients`
+/// (tomain → env → alias) gets a bearer token fetched from its token endpoint instead of a static
+/// `vault` token. The client secret is a key in the tomain's `secrets` map, never stored inline.
+/// Tokens are cached until shortly before they expire; one refresh runs per binding at a time.
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// Lifetime assumed when the token endpoint omits `expires_in`.
+const DEFAULT_TOKEN_LIFETIME_SECS: i64 = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientAuthMethod {
+    /// HTTP Basic with client id and secret (RFC 6749 §2.3.1)
+    #[default]
+    ClientSecretBasic,
+    /// `client_id` and `client_secret` in the form body
+    ClientSecretPost,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OAuthClientConfig {
+    pub token_url: String,
+    pub client_id: String,
+    /// Key in the tomain's `secrets` map holding the client secret
+    pub client_secret_ref: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub audience: Option<String>,
+    #[serde(default)]
+    pub auth_method: ClientAuthMethod,
+    /// Refresh this many seconds before the token expires
+    #[serde(default = "default_refresh_skew")]
+    pub refresh_skew_secs: u64,
+}
+
+fn default_refresh_skew() -> u64 { 60 }
+
+#[derive(Debug, Clone)]
+struct CachedToken {
+    access_token: String,
+    refresh_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+}
+
+/// One binding's token; the mutex makes concurrent callers wait for a single refresh.
+type TokenSlot = Arc<Mutex<Option<CachedToken>>>;
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenStatus {
+    pub tomain_id: String,
+    pub environment: String,
+    pub alias: String,
+    pub token_url: String,
+    pub client_id: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+pub struct OAuthClients {
+    /// (tomain_id, env, alias) -> client config; "GLOBAL" applies to every environment without its own
+    configs: DashMap<(String, String, String), OAuthClientConfig>,
+    /// Same key as `configs`
+    tokens: DashMap<(String, String, String), TokenSlot>,
+}
+
+impl OAuthClients {
+    pub fn new() -> Self {
+        Self {
+            configs: DashMap::new(),
+            tokens: DashMap::new(),
+        }
+    }
+
+    /// Load `oauth2_clients` from ~/.axiom/session.json. Cached tokens are kept only for
+    /// bindings whose config is unchanged.
+    pub fn reload_from_registry(&self) {
+        let path = dirs::home_dir()
+            .unwrap_or_default()
+            .join(".axiom")
+            .join("session.json");
+
+        let Ok(content) = std::fs::read_to_string(&path) else { return };
+        let Ok(json) = serde_json::from_str::<Value>(&content) else { return };
+
+        let previous: Vec<_> = self.configs.iter().map(|e| (e.key().clone(), e.value().clone())).collect();
+        self.configs.clear();
+        if let Some(all) = json.get("oauth2_clients").and_then(|c| c.as_object()) {
+            for (tomain_id, envs) in all {
+                for (env, aliases) in envs.as_object().into_iter().flatten() {
+                    for (alias, config) in aliases.as_object().into_iter().flatten() {
+                        match serde_json::from_value::<OAuthClientConfig>(config.clone()) {
+                            Ok(config) => { self.configs.insert((tomain_id.clone(), env.to_uppercase(), alias.clone()), config); }
+                            Err(e) => warn!("Invalid OAuth2 client for {} ({}/{}): {}", tomain_id, env, alias, e),
+                        }
+                    }
+                }
+            }
+        }
+        for (key, config) in previous {
+            if self.configs.get(&key).is_none_or(|c| *c != config) {
+                self.tokens.remove(&key);
+            }
+        }
+        info!("🔑 OAuth2: Loaded {} client credential bindings", self.configs.len());
+    }
+
+    fn config(&self, tomain_id: &str, environment: &str, alias: &str) -> Option<((String, String, String), OAuthClientConfig)> {
+        let key = |env: &str| (tomain_id.to_string(), env.to_string(), alias.to_string());
+        [key(&environment.to_uppercase()), key("GLOBAL")].into_iter()
+            .find_map(|k| self.configs.get(&k).map(|c| (k, c.value().clone())))
+    }
+
+    pub fn has_client(&self, tomain_id: &str, environment: &str, alias: &str) -> bool {
+        self.config(tomain_id, environment, alias).is_some()
+    }
+
+    /// Bearer token for the binding. None when it has no OAuth2 client; Err when one couldn't be fetched.
+    pub async fn token(
+        &self,
+        tomain_id: &str,
+        environment: &str,
+        alias: &str,
+        secrets: &crate::secrets::SecretStore,
+        client: &reqwest::Client,
+    ) -> Option<Result<String, String>> {
+        let (key, config) = self.config(tomain_id, environment, alias)?;
+        let slot = self.tokens.entry(key).or_default().clone();
+        let mut cached = slot.lock().await;
+        if let Some(token) = cached.as_ref().filter(|t| Utc::now() < t.refresh_at) {
+            return Some(Ok(token.access_token.clone()));
+        }
+
+        match fetch_token(tomain_id, &config, secrets, client).await {
+            Ok(token) => {
+                info!("🔑 OAuth2: Fetched token for {} -> '{}' ({}), valid until {}", tomain_id, alias, environment, token.expires_at);
+                let access_token = token.access_token.clone();
+                *cached = Some(token);
+                Some(Ok(access_token))
+            }
+            // A token that hasn't actually expired yet is still better than failing the call
+            Err(e) => match cached.as_ref().filter(|t| Utc::now() < t.expires_at) {
+                Some(token) => {
+                    warn!("🔑 OAuth2: Refresh for '{}' failed, using current token until it expires: {}", alias, e);
+                    Some(Ok(token.access_token.clone()))
+                }
+                None => Some(Err(e)),
+            },
+        }
+    }
+
+    /// Drops the cached token, e.g. after the downstream rejected it with a 401.
+    pub async fn invalidate(&self, tomain_id: &str, environment: &str, alias: &str) {
+        if let Some((key, _)) = self.config(tomain_id, environment, alias)
+            && let Some(slot) = self.tokens.get(&key).map(|s| s.value().clone())
+        {
+            *slot.lock().await = None;
+        }
+    }
+
+    /// Configured clients and when their current token expires; tokens themselves are never exposed.
+    pub async fn statuses(&self) -> Vec<TokenStatus> {
+        let configs: Vec<_> = self.configs.iter().map(|e| (e.key().clone(), e.value().clone())).collect();
+        let mut statuses = Vec::with_capacity(configs.len());
+        for (key, config) in configs {
+            let slot = self.tokens.get(&key).map(|s| s.value().clone());
+            let expires_at = match slot {
+                Some(slot) => slot.lock().await.as_ref().map(|t| t.expires_at),
+                None => None,
+            };
+            let (tomain_id, environment, alias) = key;
+            statuses.push(TokenStatus { tomain_id, environment, alias, token_url: config.token_url, client_id: config.client_id, expires_at });
+        }
+        statuses
+    }
+}
+
+async fn fetch_token(
+    tomain_id: &str,
+    config: &OAuthClientConfig,
+    secrets: &crate::secrets::SecretStore,
+    client: &reqwest::Client,
+) -> Result<CachedToken, String> {
+    let secret = secrets.credential(tomain_id, &config.client_secret_ref)
+        .ok_or_else(|| format!("client secret '{}' is not set for {}", config.client_secret_ref, tomain_id))?;
+
+    let mut form = vec![("grant_type", "client_credentials".to_string())];
+    if !config.scopes.is_empty() {
+        form.push(("scope", config.scopes.join(" ")));
+    }
+    if let Some(audience) = &config.audience {
+        form.push(("audience", audience.clone()));
+    }
+    let mut req = client.post(&config.token_url);
+    match config.auth_method {
+        ClientAuthMethod::ClientSecretBasic => req = req.basic_auth(&config.client_id, Some(&secret)),
+        ClientAuthMethod::ClientSecretPost => {
+            form.push(("client_id", config.client_id.clone()));
+            form.push(("client_secret", secret));
+        }
+    }
+
+    let res = req.form(&form).send().await
+        .map_err(|e| format!("token endpoint {} unreachable: {}", config.token_url, e))?;
+    if !res.status().is_success() {
+        return Err(format!("token endpoint {} answered {}", config.token_url, res.status()));
+    }
+    let token: TokenResponse = res.json().await
+        .map_err(|e| format!("token endpoint {} sent an unreadable response: {}", config.token_url, e))?;
+
+    let lifetime = token.expires_in.unwrap_or(DEFAULT_TOKEN_LIFETIME_SECS).max(1);
+    let skew = (config.refresh_skew_secs as i64).min(lifetime / 2);
+    let now = Utc::now();
+    Ok(CachedToken {
+        access_token: token.access_token,
+        refresh_at: now + chrono::Duration::seconds(lifetime - skew),
+        expires_at: now + chrono::Duration::seconds(lifetime),
+    })
+}
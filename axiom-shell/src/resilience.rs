@@ -1,3 +1,4 @@
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use dashmap::DashMap;
 use chrono::{DateTime, Utc};
@@ -29,14 +30,13 @@ impl SecurityManager {
         }
     }
 
-    pub fn validate_jwt(&self, tomain_id: &str, token: &str) -> Result<()> {
+    pub fn validate_jwt(&self, tomain_id: &str, token: &str) -> Result<Claims> {
         let pem = self.public_keys.get(tomain_id)
             .ok_or_else(|| anyhow!("No public key found for tomain: {}", tomain_id))?;
             
         let key = DecodingKey::from_rsa_pem(pem.as_bytes())?;
         let validation = Validation::new(Algorithm::RS256);
-        decode::<Claims>(token, &key, &validation)?;
-        Ok(())
+        Ok(decode::<Claims>(token, &key, &validation)?.claims)
     }
 
     pub fn get_vault_token(&self, alias: &str) -> Option<String> {
@@ -46,6 +46,13 @@ impl SecurityManager {
 
 // --- Traffic Pillar #1 ---
 
+/// Upstream default when a tomain has no policy (per tomain, like before identities existed).
+pub const DEFAULT_UPSTREAM_RPS: f64 = 100.0;
+/// Downstream default when an alias has no limit in `rate_limits.downstream`.
+pub const DEFAULT_DOWNSTREAM_RPS: f64 = 10.0;
+/// Identity buckets kept before idle ones are dropped.
+const MAX_UPSTREAM_BUCKETS: usize = 100_000;
+
 pub struct TokenBucket {
     pub capacity: f64,
     pub tokens: f64,
@@ -55,32 +62,152 @@ pub struct TokenBucket {
 
 impl TokenBucket {
     pub fn new(rate: f64) -> Self {
+        Self::with_burst(rate, rate)
+    }
+
+    /// `burst` tokens available at once, refilled at `rate` per second.
+    pub fn with_burst(rate: f64, burst: f64) -> Self {
         Self {
-            capacity: rate,
-            tokens: rate,
+            capacity: burst.max(1.0),
+            tokens: burst.max(1.0),
             fill_rate: rate,
             last_filled: Utc::now(),
         }
     }
 
-    pub fn try_consume(&mut self) -> bool {
+    fn refill(&mut self) {
         let now = Utc::now();
         let elapsed = (now - self.last_filled).num_milliseconds() as f64 / 1000.0;
         self.tokens = (self.tokens + elapsed * self.fill_rate).min(self.capacity);
         self.last_filled = now;
+    }
 
-        if self.tokens >= 1.0 {
+    /// Takes one token and reports what the `RateLimit-*` headers should say.
+    pub fn take(&mut self) -> RateLimitDecision {
+        self.refill();
+        let allowed = self.tokens >= 1.0;
+        if allowed {
             self.tokens -= 1.0;
-            true
-        } else {
-            false
+        }
+        let secs_until = |tokens: f64| if self.fill_rate > 0.0 { (tokens / self.fill_rate).ceil() as u64 } else { u64::MAX };
+        RateLimitDecision {
+            allowed,
+            limit: self.capacity as u64,
+            remaining: self.tokens.floor() as u64,
+            reset_secs: secs_until(self.capacity - self.tokens),
+            retry_after_secs: if allowed { 0 } else { secs_until(1.0 - self.tokens).max(1) },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub limit: u64,
+    pub remaining: u64,
+    /// Seconds until the bucket is full again
+    pub reset_secs: u64,
+    /// Seconds until one request fits; 0 when allowed
+    pub retry_after_secs: u64,
+}
+
+/// What a caller is counted as. Keys the policy can't resolve fall back to the client IP.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitKey {
+    /// One bucket for the whole tomain
+    #[default]
+    Tomain,
+    /// The verified JWT `sub`
+    Subject,
+    /// The value of `api_key_header`
+    ApiKey,
+    ClientIp,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub rate_per_sec: f64,
+    /// Bucket capacity; defaults to one second of `rate_per_sec`
+    #[serde(default)]
+    pub burst: Option<f64>,
+}
+
+impl RateLimit {
+    fn bucket(&self) -> TokenBucket {
+        TokenBucket::with_burst(self.rate_per_sec, self.burst.unwrap_or(self.rate_per_sec))
+    }
+}
+
+/// A tomain's ingress limit from `rate_limits` in session.json. A bare number is the legacy
+/// form: that many req/sec for the whole tomain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitPolicy {
+    #[serde(default)]
+    pub key_by: RateLimitKey,
+    #[serde(flatten)]
+    pub limit: RateLimit,
+    /// Function name -> its own limit, counted separately from the rest of the tomain
+    #[serde(default)]
+    pub functions: HashMap<String, RateLimit>,
+    #[serde(default = "default_api_key_header")]
+    pub api_key_header: String,
+}
+
+fn default_api_key_header() -> String { "X-Api-Key".to_string() }
+
+impl RateLimitPolicy {
+    pub fn per_tomain(rate_per_sec: f64) -> Self {
+        Self {
+            key_by: RateLimitKey::Tomain,
+            limit: RateLimit { rate_per_sec, burst: None },
+            functions: HashMap::new(),
+            api_key_header: default_api_key_header(),
+        }
+    }
+
+    fn from_value(value: &serde_json::Value) -> Option<Self> {
+        match value.as_f64() {
+            Some(rps) => Some(Self::per_tomain(rps)),
+            None => serde_json::from_value(value.clone()).ok(),
+        }
+    }
+}
+
+/// Who is calling, as far as ingress can tell.
+#[derive(Debug, Clone, Default)]
+pub struct CallerIdentity {
+    /// `sub` of a JWT that passed validation
+    pub subject: Option<String>,
+    pub api_key: Option<String>,
+    pub client_ip: Option<std::net::IpAddr>,
+    /// Set for in-process kernel-to-kernel calls
+    pub tomain: Option<String>,
+}
+
+impl CallerIdentity {
+    fn bucket_key(&self, key_by: RateLimitKey) -> String {
+        let fallback = || match (&self.tomain, self.client_ip) {
+            (Some(tomain), _) => format!("tomain:{}", tomain),
+            (None, Some(ip)) => format!("ip:{}", ip),
+            (None, None) => "anonymous".to_string(),
+        };
+        match key_by {
+            RateLimitKey::Tomain => "*".to_string(),
+            RateLimitKey::Subject => self.subject.as_ref().map(|s| format!("sub:{}", s)).unwrap_or_else(fallback),
+            RateLimitKey::ApiKey => self.api_key.as_ref().map(|k| format!("key:{}", k)).unwrap_or_else(fallback),
+            RateLimitKey::ClientIp => fallback(),
         }
     }
 }
 
 pub struct TrafficController {
-    /// Upstream rate limiting (tomain_id -> bucket)
-    pub upstream_buckets: Arc<DashMap<String, TokenBucket>>,
+    /// tomain_id -> ingress policy
+    pub upstream_policies: Arc<DashMap<String, RateLimitPolicy>>,
+    /// Upstream rate limiting ((tomain_id, function or "*", caller identity) -> bucket)
+    pub upstream_buckets: Arc<DashMap<(String, String, String), TokenBucket>>,
+    /// alias -> egress limit
+    pub downstream_limits: Arc<DashMap<String, RateLimit>>,
     /// Downstream rate limiting (alias -> bucket)
     pub downstream_buckets: Arc<DashMap<String, TokenBucket>>,
 }
@@ -88,21 +215,46 @@ pub struct TrafficController {
 impl TrafficController {
     pub fn new() -> Self {
         Self {
+            upstream_policies: Arc::new(DashMap::new()),
             upstream_buckets: Arc::new(DashMap::new()),
+            downstream_limits: Arc::new(DashMap::new()),
             downstream_buckets: Arc::new(DashMap::new()),
         }
     }
 
-    pub fn check_upstream(&self, tomain_id: &str, limit_per_sec: f64) -> bool {
-        let mut bucket = self.upstream_buckets.entry(tomain_id.to_string())
-            .or_insert_with(|| TokenBucket::new(limit_per_sec));
-        bucket.try_consume()
+    /// Counts one ingress call to `tomain_id::function` against the caller's bucket.
+    pub fn check_upstream(&self, tomain_id: &str, function: &str, caller: &CallerIdentity) -> RateLimitDecision {
+        let policy = self.upstream_policies.get(tomain_id)
+            .map(|p| p.value().clone())
+            .unwrap_or_else(|| RateLimitPolicy::per_tomain(DEFAULT_UPSTREAM_RPS));
+        let (scope, limit) = match policy.functions.get(function) {
+            Some(limit) => (function.to_string(), limit.clone()),
+            None => ("*".to_string(), policy.limit.clone()),
+        };
+
+        if self.upstream_buckets.len() > MAX_UPSTREAM_BUCKETS {
+            let idle_cutoff = Utc::now() - chrono::Duration::minutes(5);
+            self.upstream_buckets.retain(|_, b| b.last_filled > idle_cutoff);
+        }
+        let key = (tomain_id.to_string(), scope, caller.bucket_key(policy.key_by));
+        let mut bucket = self.upstream_buckets.entry(key).or_insert_with(|| limit.bucket());
+        bucket.take()
     }
 
-    pub fn check_downstream(&self, alias: &str, limit_per_sec: f64) -> bool {
+    pub fn check_downstream(&self, alias: &str) -> RateLimitDecision {
         let mut bucket = self.downstream_buckets.entry(alias.to_string())
-            .or_insert_with(|| TokenBucket::new(limit_per_sec));
-        bucket.try_consume()
+            .or_insert_with(|| match self.downstream_limits.get(alias) {
+                Some(limit) => limit.bucket(),
+                None => TokenBucket::new(DEFAULT_DOWNSTREAM_RPS),
+            });
+        bucket.take()
+    }
+
+    /// The header an API-key policy reads for `tomain_id`, if it uses one.
+    pub fn api_key_header(&self, tomain_id: &str) -> Option<String> {
+        self.upstream_policies.get(tomain_id)
+            .filter(|p| p.key_by == RateLimitKey::ApiKey)
+            .map(|p| p.api_key_header.clone())
     }
 }
 
@@ -115,70 +267,584 @@ pub enum CircuitState {
     HalfOpen,
 }
 
+/// (tomain_id, environment, alias) — QA failures never trip the PROD breaker.
+pub type BreakerKey = (String, String, String);
+
+/// When a breaker opens and how it recovers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BreakerPolicy {
+    /// Sliding window the failure rate is computed over
+    pub window_secs: u64,
+    /// Fraction of failed calls in the window (0.0–1.0) that opens the breaker
+    pub failure_rate: f64,
+    /// Calls needed in the window before the rate counts
+    pub min_requests: u32,
+    /// How long the breaker stays open before letting probes through
+    pub open_secs: u64,
+    /// Concurrent probes allowed while half-open; this many successes close the breaker
+    pub half_open_probes: u32,
+}
+
+impl Default for BreakerPolicy {
+    fn default() -> Self {
+        Self {
+            window_secs: 60,
+            failure_rate: 0.5,
+            min_requests: 5,
+            open_secs: 30,
+            half_open_probes: 1,
+        }
+    }
+}
+
+/// A state change, published on the event bus as `axiom.circuit.<state>`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakerTransition {
+    pub tomain_id: String,
+    pub environment: String,
+    pub alias: String,
+    pub from: CircuitState,
+    pub to: CircuitState,
+    pub reason: String,
+    pub at: DateTime<Utc>,
+}
+
+/// What `/admin/breakers` shows for one breaker.
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakerStatus {
+    pub tomain_id: String,
+    pub environment: String,
+    pub alias: String,
+    pub state: CircuitState,
+    pub forced_open: bool,
+    pub window_requests: usize,
+    pub window_failures: usize,
+    pub opened_at: Option<DateTime<Utc>>,
+    pub policy: BreakerPolicy,
+}
+
 pub struct CircuitBreaker {
     pub state: CircuitState,
-    pub failure_count: u32,
-    pub last_failure: Option<DateTime<Utc>>,
+    pub policy: BreakerPolicy,
+    /// (finished_at, succeeded) for calls inside the window
+    outcomes: VecDeque<(DateTime<Utc>, bool)>,
+    pub opened_at: Option<DateTime<Utc>>,
+    /// Held open by an operator until reset
+    pub forced_open: bool,
+    /// When each in-flight half-open probe was let through. A probe that hasn't reported back
+    /// within `open_secs` (its call was dropped before `record`) stops holding a slot.
+    probes: VecDeque<DateTime<Utc>>,
+    probe_successes: u32,
 }
 
 impl CircuitBreaker {
-    pub fn new() -> Self {
+    pub fn new(policy: BreakerPolicy) -> Self {
         Self {
             state: CircuitState::Closed,
-            failure_count: 0,
-            last_failure: None,
+            policy,
+            outcomes: VecDeque::new(),
+            opened_at: None,
+            forced_open: false,
+            probes: VecDeque::new(),
+            probe_successes: 0,
+        }
+    }
+
+    fn prune(&mut self, now: DateTime<Utc>) {
+        let window = chrono::Duration::seconds(self.policy.window_secs as i64);
+        while self.outcomes.front().is_some_and(|(at, _)| now - *at > window) {
+            self.outcomes.pop_front();
         }
     }
 
-    pub fn report_success(&mut self) {
-        self.state = CircuitState::Closed;
-        self.failure_count = 0;
+    fn window_failures(&self) -> usize {
+        self.outcomes.iter().filter(|(_, ok)| !ok).count()
     }
 
-    pub fn report_failure(&mut self) {
-        self.failure_count += 1;
-        self.last_failure = Some(Utc::now());
-        if self.failure_count >= 5 {
-            self.state = CircuitState::Open;
-            warn!("🚨 Circuit Breaker OPENED after 5 failures.");
+    /// Moves to `to` and returns (from, to, reason) when the state actually changed.
+    fn transition(&mut self, to: CircuitState, reason: String) -> Option<(CircuitState, CircuitState, String)> {
+        let from = self.state;
+        if from == to {
+            return None;
+        }
+        self.state = to;
+        self.probes.clear();
+        self.probe_successes = 0;
+        match to {
+            CircuitState::Open => self.opened_at = Some(Utc::now()),
+            CircuitState::Closed => {
+                self.opened_at = None;
+                self.outcomes.clear();
+            }
+            CircuitState::HalfOpen => {}
         }
+        Some((from, to, reason))
     }
 
-    pub fn should_allow(&mut self) -> bool {
-        match self.state {
+    /// Whether a call may go out now. Open breakers turn half-open once `open_secs` has passed, and
+    /// a half-open probe that hasn't been recorded after as long is released.
+    pub fn try_acquire(&mut self) -> (bool, Option<(CircuitState, CircuitState, String)>) {
+        let mut changed = None;
+        if self.state == CircuitState::Open && !self.forced_open {
+            let elapsed = self.opened_at.map(|at| (Utc::now() - at).num_seconds()).unwrap_or(i64::MAX);
+            if elapsed >= self.policy.open_secs as i64 {
+                changed = self.transition(CircuitState::HalfOpen, format!("open for {}s", elapsed));
+            }
+        }
+        let allowed = match self.state {
             CircuitState::Closed => true,
-            CircuitState::Open => {
+            CircuitState::Open => false,
+            CircuitState::HalfOpen => {
                 let now = Utc::now();
-                if let Some(last) = self.last_failure {
-                    if (now - last).num_seconds() > 30 {
-                        self.state = CircuitState::HalfOpen;
-                        info!("🔄 Circuit Breaker HALF-OPEN (Testing...).");
-                        return true;
-                    }
+                let stale = chrono::Duration::seconds(self.policy.open_secs.max(1) as i64);
+                while self.probes.front().is_some_and(|at| now - *at >= stale) {
+                    warn!("🚨 Circuit Breaker: half-open probe never reported back, releasing its slot");
+                    self.probes.pop_front();
+                }
+                if self.probes.len() < self.policy.half_open_probes.max(1) as usize {
+                    self.probes.push_back(now);
+                    true
+                } else {
+                    false
+                }
+            }
+        };
+        (allowed, changed)
+    }
+
+    pub fn record(&mut self, success: bool) -> Option<(CircuitState, CircuitState, String)> {
+        let now = Utc::now();
+        match self.state {
+            CircuitState::HalfOpen => {
+                self.probes.pop_front();
+                if !success {
+                    return self.transition(CircuitState::Open, "half-open probe failed".to_string());
+                }
+                self.probe_successes += 1;
+                if self.probe_successes >= self.policy.half_open_probes.max(1) {
+                    return self.transition(CircuitState::Closed, format!("{} probe(s) succeeded", self.probe_successes));
                 }
-                false
+                None
             }
-            CircuitState::HalfOpen => true,
+            CircuitState::Open => None,
+            CircuitState::Closed => {
+                self.outcomes.push_back((now, success));
+                self.prune(now);
+                let total = self.outcomes.len();
+                let failures = self.window_failures();
+                if total >= self.policy.min_requests as usize && failures as f64 / total as f64 >= self.policy.failure_rate {
+                    let reason = format!("{}/{} calls failed in {}s", failures, total, self.policy.window_secs);
+                    warn!("🚨 Circuit Breaker OPENED: {}", reason);
+                    return self.transition(CircuitState::Open, reason);
+                }
+                None
+            }
+        }
+    }
+}
+
+/// How the Egress Guard retries one alias in one environment. Only idempotent methods are retried
+/// unless `retry_non_idempotent` is set, in which case every attempt carries the same idempotency key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+    /// Full jitter: sleep a random duration up to the exponential backoff
+    pub jitter: bool,
+    /// Statuses worth retrying; transport errors and attempt timeouts always are
+    pub retry_on: Vec<u16>,
+    pub attempt_timeout_ms: u64,
+    /// Budget for all attempts and backoff together
+    pub deadline_ms: u64,
+    /// Opt-in for POST/PATCH
+    pub retry_non_idempotent: bool,
+    pub idempotency_header: String,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 100,
+            max_delay_ms: 2_000,
+            jitter: true,
+            retry_on: vec![502, 503, 504],
+            attempt_timeout_ms: 5_000,
+            deadline_ms: 15_000,
+            retry_non_idempotent: false,
+            idempotency_header: "Idempotency-Key".to_string(),
         }
     }
 }
 
+impl RetryPolicy {
+    pub fn allows_method(&self, method: &str) -> bool {
+        matches!(method, "GET" | "HEAD" | "OPTIONS" | "PUT" | "DELETE") || self.retry_non_idempotent
+    }
+
+    /// Delay before retry number `retry` (1-based).
+    pub fn backoff(&self, retry: u32) -> std::time::Duration {
+        let exp = self.base_delay_ms.saturating_mul(1u64 << (retry - 1).min(20)).min(self.max_delay_ms);
+        let ms = if self.jitter && exp > 0 { rand::random::<u64>() % (exp + 1) } else { exp };
+        std::time::Duration::from_millis(ms)
+    }
+}
+
 pub struct FaultTolerance {
-    pub breakers: Arc<DashMap<String, CircuitBreaker>>,
+    pub breakers: Arc<DashMap<BreakerKey, CircuitBreaker>>,
+    /// (tomain_id, env, alias) -> breaker policy; "GLOBAL" applies to every environment without its own
+    pub breaker_policies: Arc<DashMap<BreakerKey, BreakerPolicy>>,
+    /// (tomain_id, env, alias) -> retry policy; "GLOBAL" applies to every environment without its own
+    pub retry_policies: Arc<DashMap<BreakerKey, RetryPolicy>>,
 }
 
 impl FaultTolerance {
     pub fn new() -> Self {
         Self {
             breakers: Arc::new(DashMap::new()),
+            breaker_policies: Arc::new(DashMap::new()),
+            retry_policies: Arc::new(DashMap::new()),
         }
     }
 
-    pub fn get_status(&self, alias: &str) -> CircuitState {
-        self.breakers.get(alias)
+    pub fn retry_policy(&self, tomain_id: &str, environment: &str, alias: &str) -> RetryPolicy {
+        let key = |env: &str| (tomain_id.to_string(), env.to_string(), alias.to_string());
+        self.retry_policies.get(&key(&environment.to_uppercase()))
+            .or_else(|| self.retry_policies.get(&key("GLOBAL")))
+            .map(|p| p.value().clone())
+            .unwrap_or_default()
+    }
+
+    pub fn breaker_policy(&self, key: &BreakerKey) -> BreakerPolicy {
+        let (tomain_id, _, alias) = key;
+        self.breaker_policies.get(key)
+            .or_else(|| self.breaker_policies.get(&(tomain_id.clone(), "GLOBAL".to_string(), alias.clone())))
+            .map(|p| p.value().clone())
+            .unwrap_or_default()
+    }
+
+    fn transition(key: &BreakerKey, change: Option<(CircuitState, CircuitState, String)>) -> Option<BreakerTransition> {
+        let (from, to, reason) = change?;
+        Some(BreakerTransition {
+            tomain_id: key.0.clone(),
+            environment: key.1.clone(),
+            alias: key.2.clone(),
+            from,
+            to,
+            reason,
+            at: Utc::now(),
+        })
+    }
+
+    /// Whether a call may go out, plus the Open → HalfOpen transition if this call caused one.
+    pub fn try_acquire(&self, key: &BreakerKey) -> (bool, Option<BreakerTransition>) {
+        let mut breaker = self.breakers.entry(key.clone())
+            .or_insert_with(|| CircuitBreaker::new(self.breaker_policy(key)));
+        let (allowed, change) = breaker.try_acquire();
+        (allowed, Self::transition(key, change))
+    }
+
+    pub fn record(&self, key: &BreakerKey, success: bool) -> Option<BreakerTransition> {
+        let change = self.breakers.get_mut(key)?.record(success);
+        Self::transition(key, change)
+    }
+
+    /// Holds the breaker open until `reset`, whatever the traffic does.
+    pub fn force_open(&self, key: &BreakerKey) -> Option<BreakerTransition> {
+        let mut breaker = self.breakers.entry(key.clone())
+            .or_insert_with(|| CircuitBreaker::new(self.breaker_policy(key)));
+        breaker.forced_open = true;
+        let change = breaker.transition(CircuitState::Open, "forced open by operator".to_string());
+        Self::transition(key, change)
+    }
+
+    pub fn reset(&self, key: &BreakerKey) -> Option<BreakerTransition> {
+        let mut breaker = self.breakers.get_mut(key)?;
+        breaker.forced_open = false;
+        let change = breaker.transition(CircuitState::Closed, "reset by operator".to_string());
+        breaker.outcomes.clear();
+        Self::transition(key, change)
+    }
+
+    pub fn get_status(&self, key: &BreakerKey) -> CircuitState {
+        self.breakers.get(key)
             .map(|b| b.state)
             .unwrap_or(CircuitState::Closed)
     }
+
+    pub fn statuses(&self) -> Vec<BreakerStatus> {
+        let now = Utc::now();
+        let mut statuses: Vec<BreakerStatus> = self.breakers.iter_mut().map(|mut entry| {
+            let (tomain_id, environment, alias) = entry.key().clone();
+            let breaker = entry.value_mut();
+            breaker.prune(now);
+            BreakerStatus {
+                tomain_id,
+                environment,
+                alias,
+                state: breaker.state,
+                forced_open: breaker.forced_open,
+                window_requests: breaker.outcomes.len(),
+                window_failures: breaker.window_failures(),
+                opened_at: breaker.opened_at,
+                policy: breaker.policy.clone(),
+            }
+        }).collect();
+        statuses.sort_by(|a, b| (&a.tomain_id, &a.environment, &a.alias).cmp(&(&b.tomain_id, &b.environment, &b.alias)));
+        statuses
+    }
+}
+
+// --- Bulkheads Pillar #2 ---
+
+/// How many calls may run at once in one scope, and how long the rest may wait.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BulkheadPolicy {
+    pub max_concurrency: usize,
+    /// Callers allowed to wait for a slot; beyond this new calls are shed immediately
+    pub max_queue: usize,
+    pub queue_timeout_ms: u64,
+    /// AIMD: shrink the limit by 10% when a call is slower than `target_latency_ms`,
+    /// grow it by one per limit's worth of fast calls, between `min_concurrency` and `max_concurrency`
+    pub adaptive: bool,
+    pub min_concurrency: usize,
+    pub target_latency_ms: u64,
+}
+
+impl Default for BulkheadPolicy {
+    fn default() -> Self {
+        Self {
+            max_concurrency: 256,
+            max_queue: 1024,
+            queue_timeout_ms: 2_000,
+            adaptive: false,
+            min_concurrency: 4,
+            target_latency_ms: 1_000,
+        }
+    }
+}
+
+/// A call refused by a full bulkhead. HTTP ingress answers 503 with `Retry-After`.
+#[derive(Debug, Clone)]
+pub struct Shed {
+    pub scope: String,
+    /// `queue_full` or `queue_timeout`
+    pub reason: &'static str,
+    pub retry_after_secs: u64,
+}
+
+impl std::fmt::Display for Shed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Overloaded: {} shed the call ({})", self.scope, self.reason)
+    }
+}
+
+impl std::error::Error for Shed {}
+
+struct BulkheadState {
+    policy: BulkheadPolicy,
+    in_flight: usize,
+    queued: usize,
+    /// Current limit; fixed at `max_concurrency` unless adaptive
+    limit: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkheadStatus {
+    pub scope: String,
+    pub in_flight: usize,
+    pub queued: usize,
+    pub limit: usize,
+    pub policy: BulkheadPolicy,
+}
+
+pub struct Bulkhead {
+    pub scope: String,
+    state: std::sync::Mutex<BulkheadState>,
+    released: tokio::sync::Notify,
+}
+
+/// Held for the duration of a call; dropping it frees the slot and feeds the adaptive limit.
+pub struct BulkheadPermit {
+    bulkhead: Arc<Bulkhead>,
+    started: std::time::Instant,
+}
+
+impl Drop for BulkheadPermit {
+    fn drop(&mut self) {
+        let elapsed = self.started.elapsed();
+        let mut st = self.bulkhead.state.lock().unwrap();
+        st.in_flight = st.in_flight.saturating_sub(1);
+        if st.policy.adaptive {
+            let (min, max) = (st.policy.min_concurrency.max(1) as f64, st.policy.max_concurrency.max(1) as f64);
+            st.limit = if elapsed.as_millis() as u64 > st.policy.target_latency_ms {
+                (st.limit * 0.9).max(min)
+            } else {
+                (st.limit + 1.0 / st.limit).min(max)
+            };
+        }
+        drop(st);
+        self.bulkhead.released.notify_one();
+    }
+}
+
+/// Decrements the wait queue however the waiting caller leaves it (slot, timeout or cancellation).
+struct QueueSlot<'a>(&'a Bulkhead);
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        let mut st = self.0.state.lock().unwrap();
+        st.queued = st.queued.saturating_sub(1);
+    }
+}
+
+impl Bulkhead {
+    pub fn new(scope: String, policy: BulkheadPolicy) -> Self {
+        let limit = policy.max_concurrency.max(1) as f64;
+        Self {
+            scope,
+            state: std::sync::Mutex::new(BulkheadState { policy, in_flight: 0, queued: 0, limit }),
+            released: tokio::sync::Notify::new(),
+        }
+    }
+
+    pub fn set_policy(&self, policy: BulkheadPolicy) {
+        let mut st = self.state.lock().unwrap();
+        st.limit = if policy.adaptive {
+            st.limit.clamp(policy.min_concurrency.max(1) as f64, policy.max_concurrency.max(1) as f64)
+        } else {
+            policy.max_concurrency.max(1) as f64
+        };
+        st.policy = policy;
+        drop(st);
+        self.released.notify_waiters();
+    }
+
+    fn shed(&self, reason: &'static str, policy: &BulkheadPolicy) -> Shed {
+        Shed {
+            scope: self.scope.clone(),
+            reason,
+            retry_after_secs: policy.queue_timeout_ms.div_ceil(1000).max(1),
+        }
+    }
+
+    pub async fn acquire(self: &Arc<Self>) -> Result<BulkheadPermit, Shed> {
+        let mut slot: Option<QueueSlot<'_>> = None;
+        let mut deadline = None;
+        loop {
+            let notified = self.released.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            {
+                let mut st = self.state.lock().unwrap();
+                if st.in_flight < st.limit.floor().max(1.0) as usize {
+                    st.in_flight += 1;
+                    drop(st);
+                    drop(slot);
+                    return Ok(BulkheadPermit { bulkhead: self.clone(), started: std::time::Instant::now() });
+                }
+                if slot.is_none() {
+                    if st.queued >= st.policy.max_queue {
+                        return Err(self.shed("queue_full", &st.policy));
+                    }
+                    st.queued += 1;
+                    deadline = Some(tokio::time::Instant::now() + std::time::Duration::from_millis(st.policy.queue_timeout_ms));
+                    drop(st);
+                    slot = Some(QueueSlot(self));
+                }
+            }
+            if tokio::time::timeout_at(deadline.unwrap(), notified).await.is_err() {
+                let policy = self.state.lock().unwrap().policy.clone();
+                return Err(self.shed("queue_timeout", &policy));
+            }
+        }
+    }
+
+    pub fn status(&self) -> BulkheadStatus {
+        let st = self.state.lock().unwrap();
+        BulkheadStatus {
+            scope: self.scope.clone(),
+            in_flight: st.in_flight,
+            queued: st.queued,
+            limit: st.limit.floor() as usize,
+            policy: st.policy.clone(),
+        }
+    }
+}
+
+pub struct Bulkheads {
+    /// (tomain_id, slot) -> ingress bulkhead
+    pub ingress: Arc<DashMap<(String, String), Arc<Bulkhead>>>,
+    /// alias -> egress bulkhead
+    pub egress: Arc<DashMap<String, Arc<Bulkhead>>>,
+    /// (tomain_id, env) -> policy; "GLOBAL" applies to every environment without its own
+    pub ingress_policies: Arc<DashMap<(String, String), BulkheadPolicy>>,
+    pub egress_policies: Arc<DashMap<String, BulkheadPolicy>>,
+}
+
+/// Egress defaults are tighter: a slow downstream shouldn't hold more than this many calls.
+fn default_egress_bulkhead() -> BulkheadPolicy {
+    BulkheadPolicy { max_concurrency: 64, max_queue: 256, ..Default::default() }
+}
+
+impl Bulkheads {
+    pub fn new() -> Self {
+        Self {
+            ingress: Arc::new(DashMap::new()),
+            egress: Arc::new(DashMap::new()),
+            ingress_policies: Arc::new(DashMap::new()),
+            egress_policies: Arc::new(DashMap::new()),
+        }
+    }
+
+    fn ingress_policy(&self, tomain_id: &str, slot: &str) -> BulkheadPolicy {
+        self.ingress_policies.get(&(tomain_id.to_string(), slot.to_uppercase()))
+            .or_else(|| self.ingress_policies.get(&(tomain_id.to_string(), "GLOBAL".to_string())))
+            .map(|p| p.value().clone())
+            .unwrap_or_default()
+    }
+
+    fn egress_policy(&self, alias: &str) -> BulkheadPolicy {
+        self.egress_policies.get(alias).map(|p| p.value().clone()).unwrap_or_else(default_egress_bulkhead)
+    }
+
+    pub async fn acquire_ingress(&self, tomain_id: &str, slot: &str) -> Result<BulkheadPermit, Shed> {
+        let bulkhead = self.ingress.entry((tomain_id.to_string(), slot.to_string()))
+            .or_insert_with(|| Arc::new(Bulkhead::new(format!("{}/{}", tomain_id, slot), self.ingress_policy(tomain_id, slot))))
+            .clone();
+        bulkhead.acquire().await
+    }
+
+    pub async fn acquire_egress(&self, alias: &str) -> Result<BulkheadPermit, Shed> {
+        let bulkhead = self.egress.entry(alias.to_string())
+            .or_insert_with(|| Arc::new(Bulkhead::new(format!("egress:{}", alias), self.egress_policy(alias))))
+            .clone();
+        bulkhead.acquire().await
+    }
+
+    /// Pushes reloaded policies into live bulkheads without dropping their in-flight counts.
+    fn apply_policies(&self) {
+        for entry in self.ingress.iter() {
+            let (tomain_id, slot) = entry.key();
+            entry.value().set_policy(self.ingress_policy(tomain_id, slot));
+        }
+        for entry in self.egress.iter() {
+            entry.value().set_policy(self.egress_policy(entry.key()));
+        }
+    }
+
+    pub fn statuses(&self) -> Vec<BulkheadStatus> {
+        let mut statuses: Vec<BulkheadStatus> = self.ingress.iter().map(|b| b.status())
+            .chain(self.egress.iter().map(|b| b.status()))
+            .collect();
+        statuses.sort_by(|a, b| a.scope.cmp(&b.scope));
+        statuses
+    }
 }
 
 // --- Resilience Manager ---
@@ -187,6 +853,7 @@ pub struct ResilienceManager {
     pub security: SecurityManager,
     pub traffic: TrafficController,
     pub fault: FaultTolerance,
+    pub bulkheads: Bulkheads,
 }
 
 impl ResilienceManager {
@@ -195,6 +862,7 @@ impl ResilienceManager {
             security: SecurityManager::new(),
             traffic: TrafficController::new(),
             fault: FaultTolerance::new(),
+            bulkheads: Bulkheads::new(),
         }
     }
 
@@ -210,8 +878,14 @@ impl ResilienceManager {
             // Clear existing state for a fresh reload
             self.security.public_keys.clear();
             self.security.vault.clear();
+            self.traffic.upstream_policies.clear();
             self.traffic.upstream_buckets.clear();
+            self.traffic.downstream_limits.clear();
             self.traffic.downstream_buckets.clear();
+            self.fault.retry_policies.clear();
+            self.fault.breaker_policies.clear();
+            self.bulkheads.ingress_policies.clear();
+            self.bulkheads.egress_policies.clear();
 
             // 1. Load Public Keys (for Upstream Auth)
             if let Some(keys) = json.get("public_keys").and_then(|k| k.as_object()) {
@@ -233,18 +907,341 @@ impl ResilienceManager {
                 }
             }
 
-            // 3. Load Rate Limits
+            // 3. Load Rate Limits: tomain_id -> policy (or req/sec), the legacy `upstream` map,
+            //    and `downstream` alias -> limit (or req/sec)
             if let Some(limits) = json.get("rate_limits").and_then(|l| l.as_object()) {
-                if let Some(upstream) = limits.get("upstream").and_then(|u| u.as_object()) {
-                    for (tomain_id, limit) in upstream {
-                        if let Some(l) = limit.as_f64() {
-                            self.traffic.upstream_buckets.insert(tomain_id.clone(), TokenBucket::new(l));
-                            info!("🚦 Set upstream rate limit for {}: {} req/sec", tomain_id, l);
+                for (key, value) in limits {
+                    match key.as_str() {
+                        "upstream" => {
+                            for (tomain_id, limit) in value.as_object().into_iter().flatten() {
+                                if let Some(l) = limit.as_f64() {
+                                    self.traffic.upstream_policies.insert(tomain_id.clone(), RateLimitPolicy::per_tomain(l));
+                                    info!("🚦 Set upstream rate limit for {}: {} req/sec", tomain_id, l);
+                                }
+                            }
+                        }
+                        "downstream" => {
+                            for (alias, limit) in value.as_object().into_iter().flatten() {
+                                let parsed = match limit.as_f64() {
+                                    Some(rps) => Some(RateLimit { rate_per_sec: rps, burst: None }),
+                                    None => serde_json::from_value::<RateLimit>(limit.clone()).ok(),
+                                };
+                                match parsed {
+                                    Some(limit) => {
+                                        info!("🚦 Set downstream rate limit for {}: {} req/sec", alias, limit.rate_per_sec);
+                                        self.traffic.downstream_limits.insert(alias.clone(), limit);
+                                    }
+                                    None => warn!("Invalid downstream rate limit for {}", alias),
+                                }
+                            }
+                        }
+                        tomain_id => match RateLimitPolicy::from_value(value) {
+                            Some(policy) => {
+                                info!("🚦 Set upstream rate limit for {}: {} req/sec by {:?}", tomain_id, policy.limit.rate_per_sec, policy.key_by);
+                                self.traffic.upstream_policies.insert(tomain_id.to_string(), policy);
+                            }
+                            None => warn!("Invalid rate limit for {}", tomain_id),
+                        },
+                    }
+                }
+            }
+
+            // 4. Load Retry Policies (tomain -> env -> alias -> policy)
+            if let Some(policies) = json.get("retry_policies").and_then(|p| p.as_object()) {
+                for (tomain_id, envs) in policies {
+                    for (env, aliases) in envs.as_object().into_iter().flatten() {
+                        for (alias, policy) in aliases.as_object().into_iter().flatten() {
+                            match serde_json::from_value::<RetryPolicy>(policy.clone()) {
+                                Ok(policy) => { self.fault.retry_policies.insert((tomain_id.clone(), env.to_uppercase(), alias.clone()), policy); }
+                                Err(e) => warn!("Invalid retry policy for {} ({}/{}): {}", tomain_id, env, alias, e),
+                            }
+                        }
+                    }
+                }
+                info!("🔁 Loaded {} retry policies", self.fault.retry_policies.len());
+            }
+
+            // 5. Load Circuit Breaker Policies (same shape); live breakers keep their state
+            if let Some(policies) = json.get("breaker_policies").and_then(|p| p.as_object()) {
+                for (tomain_id, envs) in policies {
+                    for (env, aliases) in envs.as_object().into_iter().flatten() {
+                        for (alias, policy) in aliases.as_object().into_iter().flatten() {
+                            match serde_json::from_value::<BreakerPolicy>(policy.clone()) {
+                                Ok(policy) => { self.fault.breaker_policies.insert((tomain_id.clone(), env.to_uppercase(), alias.clone()), policy); }
+                                Err(e) => warn!("Invalid breaker policy for {} ({}/{}): {}", tomain_id, env, alias, e),
+                            }
                         }
                     }
                 }
+                info!("🧯 Loaded {} circuit breaker policies", self.fault.breaker_policies.len());
             }
+            for mut breaker in self.fault.breakers.iter_mut() {
+                let policy = self.fault.breaker_policy(breaker.key());
+                breaker.policy = policy;
+            }
+
+            // 6. Load Bulkheads: `bulkheads` (tomain -> env -> policy) and `egress_bulkheads` (alias -> policy)
+            if let Some(policies) = json.get("bulkheads").and_then(|p| p.as_object()) {
+                for (tomain_id, envs) in policies {
+                    for (env, policy) in envs.as_object().into_iter().flatten() {
+                        match serde_json::from_value::<BulkheadPolicy>(policy.clone()) {
+                            Ok(policy) => { self.bulkheads.ingress_policies.insert((tomain_id.clone(), env.to_uppercase()), policy); }
+                            Err(e) => warn!("Invalid bulkhead for {} ({}): {}", tomain_id, env, e),
+                        }
+                    }
+                }
+            }
+            if let Some(policies) = json.get("egress_bulkheads").and_then(|p| p.as_object()) {
+                for (alias, policy) in policies {
+                    match serde_json::from_value::<BulkheadPolicy>(policy.clone()) {
+                        Ok(policy) => { self.bulkheads.egress_policies.insert(alias.clone(), policy); }
+                        Err(e) => warn!("Invalid egress bulkhead for {}: {}", alias, e),
+                    }
+                }
+            }
+            self.bulkheads.apply_policies();
+            info!("🚧 Loaded {} ingress and {} egress bulkhead policies", self.bulkheads.ingress_policies.len(), self.bulkheads.egress_policies.len());
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caller(subject: Option<&str>, api_key: Option<&str>) -> CallerIdentity {
+        CallerIdentity {
+            subject: subject.map(str::to_string),
+            api_key: api_key.map(str::to_string),
+            client_ip: Some("10.0.0.7".parse().unwrap()),
+            tomain: None,
+        }
+    }
+
+    fn policy(json: serde_json::Value) -> RateLimitPolicy {
+        RateLimitPolicy::from_value(&json).expect("valid rate limit policy")
+    }
+
+    #[test]
+    fn bucket_key_follows_the_policy() {
+        let full = caller(Some("alice"), Some("k-123"));
+        assert_eq!(full.bucket_key(RateLimitKey::Subject), "sub:alice");
+        assert_eq!(full.bucket_key(RateLimitKey::ApiKey), "key:k-123");
+        assert_eq!(full.bucket_key(RateLimitKey::ClientIp), "ip:10.0.0.7");
+        assert_eq!(full.bucket_key(RateLimitKey::Tomain), "*");
+    }
+
+    #[test]
+    fn unresolved_keys_fall_back_to_the_client_ip() {
+        let anonymous = caller(None, None);
+        assert_eq!(anonymous.bucket_key(RateLimitKey::Subject), "ip:10.0.0.7");
+        assert_eq!(anonymous.bucket_key(RateLimitKey::ApiKey), "ip:10.0.0.7");
+
+        let in_process = CallerIdentity { tomain: Some("billing".into()), ..caller(None, None) };
+        assert_eq!(in_process.bucket_key(RateLimitKey::Subject), "tomain:billing");
+        assert_eq!(CallerIdentity::default().bucket_key(RateLimitKey::ClientIp), "anonymous");
+    }
+
+    #[test]
+    fn burst_is_available_at_once_and_refills_at_rate() {
+        let mut bucket = TokenBucket::with_burst(0.5, 3.0);
+        for remaining in [2, 1, 0] {
+            let decision = bucket.take();
+            assert!(decision.allowed);
+            assert_eq!((decision.limit, decision.remaining, decision.retry_after_secs), (3, remaining, 0));
+        }
+        let rejected = bucket.take();
+        assert!(!rejected.allowed);
+        // One token takes 2s at 0.5/s, a full bucket 6s
+        assert_eq!((rejected.remaining, rejected.retry_after_secs, rejected.reset_secs), (0, 2, 6));
+
+        bucket.last_filled = Utc::now() - chrono::Duration::seconds(2);
+        let refilled = bucket.take();
+        assert!(refilled.allowed);
+        assert_eq!(refilled.reset_secs, 6);
+    }
+
+    #[test]
+    fn burst_defaults_to_one_second_of_rate() {
+        let limit = policy(serde_json::json!({ "rate_per_sec": 4.0 })).limit.bucket();
+        assert_eq!((limit.capacity, limit.fill_rate), (4.0, 4.0));
+        let legacy = policy(serde_json::json!(25));
+        assert_eq!((legacy.key_by, legacy.limit.rate_per_sec), (RateLimitKey::Tomain, 25.0));
+    }
+
+    #[test]
+    fn callers_get_their_own_buckets() {
+        let traffic = TrafficController::new();
+        traffic.upstream_policies.insert("acme".into(), policy(serde_json::json!({ "key_by": "subject", "rate_per_sec": 1.0 })));
+        assert!(traffic.check_upstream("acme", "list", &caller(Some("alice"), None)).allowed);
+        assert!(!traffic.check_upstream("acme", "list", &caller(Some("alice"), None)).allowed);
+        assert!(traffic.check_upstream("acme", "list", &caller(Some("bob"), None)).allowed);
+    }
+
+    #[test]
+    fn function_overrides_are_counted_separately() {
+        let traffic = TrafficController::new();
+        traffic.upstream_policies.insert("acme".into(), policy(serde_json::json!({
+            "rate_per_sec": 1.0,
+            "functions": { "export": { "rate_per_sec": 0.1, "burst": 2.0 } },
+        })));
+        let anyone = caller(None, None);
+        assert!(traffic.check_upstream("acme", "export", &anyone).allowed);
+        let second = traffic.check_upstream("acme", "export", &anyone);
+        assert!(second.allowed);
+        assert_eq!(second.limit, 2);
+        let third = traffic.check_upstream("acme", "export", &anyone);
+        assert!(!third.allowed);
+        assert_eq!(third.retry_after_secs, 10);
+        // The tomain-wide bucket is untouched by the override
+        assert!(traffic.check_upstream("acme", "list", &anyone).allowed);
+    }
+
+    #[test]
+    fn api_key_header_is_only_read_for_api_key_policies() {
+        let traffic = TrafficController::new();
+        traffic.upstream_policies.insert("acme".into(), policy(serde_json::json!({ "key_by": "api_key", "rate_per_sec": 5.0 })));
+        traffic.upstream_policies.insert("globex".into(), policy(serde_json::json!({ "key_by": "subject", "rate_per_sec": 5.0 })));
+        assert_eq!(traffic.api_key_header("acme").as_deref(), Some("X-Api-Key"));
+        assert_eq!(traffic.api_key_header("globex"), None);
+    }
+
+    fn half_open(policy: BreakerPolicy) -> CircuitBreaker {
+        let mut breaker = CircuitBreaker::new(policy);
+        breaker.transition(CircuitState::HalfOpen, "test".to_string());
+        breaker
+    }
+
+    fn tripped(policy: BreakerPolicy) -> CircuitBreaker {
+        let mut breaker = CircuitBreaker::new(policy);
+        for _ in 0..breaker.policy.min_requests {
+            breaker.record(false);
+        }
+        assert_eq!(breaker.state, CircuitState::Open);
+        breaker
+    }
+
+    #[test]
+    fn breaker_waits_for_minimum_volume() {
+        let mut breaker = CircuitBreaker::new(BreakerPolicy { min_requests: 5, ..Default::default() });
+        for _ in 0..4 {
+            assert!(breaker.record(false).is_none());
+        }
+        assert_eq!(breaker.state, CircuitState::Closed);
+        let (from, to, reason) = breaker.record(false).unwrap();
+        assert_eq!((from, to), (CircuitState::Closed, CircuitState::Open));
+        assert_eq!(reason, "5/5 calls failed in 60s");
+    }
+
+    #[test]
+    fn breaker_opens_on_failure_rate_not_count() {
+        let mut breaker = CircuitBreaker::new(BreakerPolicy { min_requests: 4, failure_rate: 0.5, ..Default::default() });
+        for success in [true, true, true, false, false] {
+            breaker.record(success);
+        }
+        // 2/5 failed, below the 50% threshold
+        assert_eq!(breaker.state, CircuitState::Closed);
+        breaker.record(false);
+        assert_eq!(breaker.state, CircuitState::Open);
+    }
+
+    #[test]
+    fn outcomes_outside_the_window_are_forgotten() {
+        let mut breaker = CircuitBreaker::new(BreakerPolicy { window_secs: 60, min_requests: 3, ..Default::default() });
+        let long_ago = Utc::now() - chrono::Duration::seconds(120);
+        breaker.outcomes.extend([(long_ago, false), (long_ago, false)]);
+        breaker.record(false);
+        assert_eq!(breaker.outcomes.len(), 1);
+        assert_eq!(breaker.state, CircuitState::Closed);
+    }
+
+    #[test]
+    fn open_breaker_rejects_until_open_secs_pass() {
+        let mut breaker = tripped(BreakerPolicy::default());
+        assert_eq!(breaker.try_acquire(), (false, None));
+
+        breaker.opened_at = Some(Utc::now() - chrono::Duration::seconds(31));
+        let (allowed, change) = breaker.try_acquire();
+        assert!(allowed);
+        assert_eq!(change.map(|(from, to, _)| (from, to)), Some((CircuitState::Open, CircuitState::HalfOpen)));
+    }
+
+    #[test]
+    fn forced_open_breaker_stays_open() {
+        let mut breaker = CircuitBreaker::new(BreakerPolicy::default());
+        breaker.forced_open = true;
+        breaker.transition(CircuitState::Open, "forced".to_string());
+        breaker.opened_at = Some(Utc::now() - chrono::Duration::days(1));
+        assert_eq!(breaker.try_acquire(), (false, None));
+    }
+
+    #[test]
+    fn half_open_limits_concurrent_probes() {
+        let mut breaker = half_open(BreakerPolicy { half_open_probes: 2, ..Default::default() });
+        assert!(breaker.try_acquire().0);
+        assert!(breaker.try_acquire().0);
+        assert!(!breaker.try_acquire().0);
+
+        // A finished probe frees its slot
+        assert!(breaker.record(true).is_none());
+        assert!(breaker.try_acquire().0);
+    }
+
+    #[test]
+    fn half_open_closes_after_enough_successful_probes() {
+        let mut breaker = half_open(BreakerPolicy { half_open_probes: 2, ..Default::default() });
+        breaker.try_acquire();
+        breaker.try_acquire();
+        assert!(breaker.record(true).is_none());
+        let (_, to, reason) = breaker.record(true).unwrap();
+        assert_eq!(to, CircuitState::Closed);
+        assert_eq!(reason, "2 probe(s) succeeded");
+        assert!(breaker.outcomes.is_empty() && breaker.opened_at.is_none());
+    }
+
+    #[test]
+    fn a_failed_probe_reopens_the_breaker() {
+        let mut breaker = half_open(BreakerPolicy { half_open_probes: 2, ..Default::default() });
+        breaker.try_acquire();
+        breaker.record(true);
+        breaker.try_acquire();
+        let (_, to, _) = breaker.record(false).unwrap();
+        assert_eq!(to, CircuitState::Open);
+        assert!(breaker.opened_at.is_some());
+        assert!(!breaker.try_acquire().0);
+    }
+
+    #[test]
+    fn breakers_are_scoped_per_environment() {
+        let fault = FaultTolerance::new();
+        let qa: BreakerKey = ("acme".into(), "QA".into(), "stripe".into());
+        let prod: BreakerKey = ("acme".into(), "PROD".into(), "stripe".into());
+        fault.try_acquire(&qa);
+        let mut opened = None;
+        for _ in 0..BreakerPolicy::default().min_requests {
+            opened = opened.or(fault.record(&qa, false));
+        }
+        let opened = opened.expect("QA breaker opens");
+        assert_eq!((opened.environment.as_str(), opened.to), ("QA", CircuitState::Open));
+        assert_eq!(fault.get_status(&prod), CircuitState::Closed);
+        assert!(fault.try_acquire(&prod).0);
+
+        assert!(fault.reset(&qa).is_some());
+        assert_eq!(fault.get_status(&qa), CircuitState::Closed);
+    }
+
+    #[test]
+    fn abandoned_probes_are_released_after_open_secs() {
+        let mut breaker = half_open(BreakerPolicy { open_secs: 30, ..Default::default() });
+        assert!(breaker.try_acquire().0);
+        // The probe's call was dropped before it could record
+        assert!(!breaker.try_acquire().0);
+
+        breaker.probes[0] = Utc::now() - chrono::Duration::seconds(31);
+        assert!(breaker.try_acquire().0);
+        assert_eq!(breaker.probes.len(), 1);
+        assert!(breaker.record(true).is_some());
+        assert_eq!(breaker.state, CircuitState::Closed);
+    }
+}
@@ -1,10 +1,47 @@
-use anyhow::{Result, Context};
+use anyhow::{Result, Context, anyhow};
 use std::sync::Arc;
-use tracing::info;
-use crate::supervisor::TenantManager;
+use tracing::{info, warn, Instrument};
+use crate::supervisor::{TenantInstance, TenantLifecycle, TenantManager};
 use crate::adapters::InfraRegistry;
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 
+/// How long a replaced or retired instance may keep serving in-flight calls before it is dropped.
+const DRAIN_DEADLINE: std::time::Duration = std::time::Duration::from_secs(30);
+/// Kernel-to-kernel hops allowed in one request before the chain is cut off.
+const MAX_CALL_DEPTH: u32 = 8;
+
+/// The invocation a kernel-to-kernel call is made from.
+pub struct CallerContext {
+    pub tomain_id: String,
+    pub slot: String,
+    pub request_id: String,
+    pub depth: u32,
+}
+
+/// A call to a tomain that isn't loaded in the slot, or to a function its kernel doesn't export.
+/// Retrying can't help until something new is deployed.
+#[derive(Debug)]
+pub struct UnknownTarget(pub String);
+
+impl std::fmt::Display for UnknownTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UnknownTarget {}
+
+/// The registry environment a perspective's traffic belongs to. GREEN is the live slot and RED is
+/// live traffic in audit mode, so both are PROD; BLUE is the pre-release slot. Registry names
+/// (DEV, QA, STAGING, PROD, GLOBAL) pass through, upper-cased.
+pub fn registry_environment(environment: &str) -> String {
+    match environment.to_uppercase().as_str() {
+        "GREEN" | "RED" => "PROD".to_string(),
+        "BLUE" => "STAGING".to_string(),
+        other => other.to_string(),
+    }
+}
+
 pub struct WasmSupervisor {
     pub manager: TenantManager,
     pub registry: Arc<InfraRegistry>,
@@ -14,10 +51,24 @@ pub struct WasmSupervisor {
     pub resilience: Arc<crate::resilience::ResilienceManager>,
     pub perspective: Arc<dashmap::DashMap<String, String>>, // tomain_id -> GREEN/BLUE/RED
     pub audit_log: Arc<dashmap::DashMap<String, Vec<String>>>, // tomain_id -> entries
+    pub metrics: Arc<crate::metrics::MetricsRegistry>,
+    pub sampling: Arc<crate::telemetry::SamplingRatios>,
+    pub logs: Arc<crate::logs::LogStore>,
+    pub scheduler: Arc<crate::scheduler::Scheduler>,
+    pub jobs: Arc<crate::jobs::JobStore>,
+    pub events: Arc<crate::events::EventBus>,
+    pub kv: Arc<crate::kv::KvStore>,
+    pub secrets: Arc<crate::secrets::SecretStore>,
+    pub config: Arc<crate::config::ConfigStore>,
+    pub capabilities: Arc<crate::capabilities::CapabilityStore>,
+    pub egress_cache: Arc<crate::egress_cache::EgressCache>,
+    pub chaos: Arc<crate::chaos::FaultInjector>,
+    pub oauth: Arc<crate::oauth::OAuthClients>,
+    pub tls: Arc<crate::tls::TlsClients>,
 }
 
 impl WasmSupervisor {
-    pub async fn new() -> Result<Self> {
+    pub async fn new(sampling: Arc<crate::telemetry::SamplingRatios>) -> Result<Self> {
         Ok(Self {
             manager: TenantManager::new(),
             registry: Arc::new(InfraRegistry::new()),
@@ -29,10 +80,24 @@ impl WasmSupervisor {
             resilience: Arc::new(crate::resilience::ResilienceManager::new()),
             perspective: Arc::new(dashmap::DashMap::new()),
             audit_log: Arc::new(dashmap::DashMap::new()),
+            metrics: Arc::new(crate::metrics::MetricsRegistry::new()),
+            sampling,
+            logs: Arc::new(crate::logs::LogStore::new()),
+            scheduler: Arc::new(crate::scheduler::Scheduler::new()),
+            jobs: Arc::new(crate::jobs::JobStore::new()),
+            events: Arc::new(crate::events::EventBus::new()),
+            kv: Arc::new(crate::kv::KvStore::new()),
+            secrets: Arc::new(crate::secrets::SecretStore::new()),
+            config: Arc::new(crate::config::ConfigStore::new()),
+            capabilities: Arc::new(crate::capabilities::CapabilityStore::new()),
+            egress_cache: Arc::new(crate::egress_cache::EgressCache::new()),
+            chaos: Arc::new(crate::chaos::FaultInjector::new()),
+            oauth: Arc::new(crate::oauth::OAuthClients::new()),
+            tls: Arc::new(crate::tls::TlsClients::new()),
         })
     }
 
-    pub async fn update_perspective(&self, tomain_id: &str, target: &str) -> Result<()> {
+    pub async fn update_perspective(self: &Arc<Self>, tomain_id: &str, target: &str) -> Result<()> {
         let target_env = target.to_uppercase();
         info!("🔄 Perspective shift for {}: -> {}", tomain_id, target_env);
         
@@ -56,10 +121,12 @@ impl WasmSupervisor {
             info!("🔴 AUDIT MODE ENABLED for tomain: {}", tomain_id);
             self.audit_log.entry(tomain_id.to_string()).or_insert_with(Vec::new);
         }
+        // Schedules follow the active slot
+        self.scheduler.sync_tomain(self.clone(), tomain_id).await;
         Ok(())
     }
 
-    pub async fn deploy_kernel(&self, tomain_id: &str, env: String, wasm_base64: String) -> Result<()> {
+    pub async fn deploy_kernel(self: &Arc<Self>, tomain_id: &str, env: String, wasm_base64: String) -> Result<()> {
         let tenant_count = self.manager.tenants.read().await.len();
         if tenant_count >= 4 && !self.manager.tenants.read().await.contains_key(tomain_id) {
             return Err(anyhow::anyhow!("Shell capacity reached (max 4 active kernels). Please stop a service before deploying a new one."));
@@ -67,32 +134,203 @@ impl WasmSupervisor {
 
         info!("Deploying kernel for Tomain: {} in {} slot", tomain_id, env);
         let wasm_bytes = BASE64.decode(wasm_base64).context("Failed to decode wasm base64")?;
-        
-        self.manager.register_tenant(tomain_id, &env, &wasm_bytes).await?;
-        self.registry.update_status(tomain_id, "Active").await?;
-        
+
+        // 1. Loading: compile the module off to the side, the current slot keeps serving
+        let tenant = self.manager.load_tenant(tomain_id, &env, &wasm_bytes)?;
+        self.registry.update_status(tomain_id, &tenant.env, tenant.generation, TenantLifecycle::Loading).await;
+
+        // The manifest may have been synced just before this deploy
+        self.capabilities.reload_from_registry();
+        if let Err(e) = crate::capabilities::validate_imports(tomain_id, &tenant.module, &self.capabilities.get(tomain_id)) {
+            warn!("❌ Rejected kernel for {} in {} slot: {}", tomain_id, tenant.env, e);
+            tenant.set_lifecycle(TenantLifecycle::Retired);
+            self.registry.update_status(tomain_id, &tenant.env, tenant.generation, TenantLifecycle::Retired).await;
+            return Err(e);
+        }
+
+        // 2. Warming: instantiate once and health-check before taking traffic
+        tenant.set_lifecycle(TenantLifecycle::Warming);
+        self.registry.update_status(tomain_id, &tenant.env, tenant.generation, TenantLifecycle::Warming).await;
+        if let Err(e) = crate::bridge::invoke_health(self.clone(), tenant.clone()).await {
+            warn!("❌ Pre-warm failed for {} in {} slot, keeping previous instance: {}", tomain_id, tenant.env, e);
+            tenant.set_lifecycle(TenantLifecycle::Retired);
+            self.registry.update_status(tomain_id, &tenant.env, tenant.generation, TenantLifecycle::Retired).await;
+            return Err(e.context(format!("Pre-warm health check failed for {} in {} slot", tomain_id, tenant.env)));
+        }
+
+        // 3. Active: swap into the slot, drain whatever it replaced
+        self.registry.update_status(tomain_id, &tenant.env, tenant.generation, TenantLifecycle::Active).await;
+        if let Some(previous) = self.manager.activate_tenant(tenant.clone()).await {
+            self.drain_tenant(previous);
+        }
+        let slot = tenant.env.clone();
+        self.events.sync_subscriptions(self.clone(), tenant).await;
+        if self.get_perspective(tomain_id).to_uppercase() == slot {
+            self.scheduler.sync_tomain(self.clone(), tomain_id).await;
+        }
+
         Ok(())
     }
 
-    pub async fn retire_service(&self, tomain_id: &str, env: &str) -> Result<()> {
-        self.manager.remove_tenant(tomain_id, env).await?;
+    pub async fn retire_service(self: &Arc<Self>, tomain_id: &str, env: &str) -> Result<()> {
+        if let Some(tenant) = self.manager.remove_tenant(tomain_id, env).await {
+            self.drain_tenant(tenant);
+        }
+        self.events.remove_subscriptions(tomain_id, &env.to_uppercase());
+        self.scheduler.sync_tomain(self.clone(), tomain_id).await;
         Ok(())
     }
 
+    /// Moves an instance that no longer takes traffic through Draining -> Retired in the background.
+    fn drain_tenant(self: &Arc<Self>, tenant: Arc<TenantInstance>) {
+        let sv = self.clone();
+        tokio::spawn(async move {
+            tenant.set_lifecycle(TenantLifecycle::Draining);
+            sv.registry.update_status(&tenant.id, &tenant.env, tenant.generation, TenantLifecycle::Draining).await;
+            sv.manager.draining.write().await.push(tenant.clone());
+            info!("⏳ Draining {} ({} #{}) with {} in-flight call(s)", tenant.id, tenant.env, tenant.generation, tenant.in_flight());
+
+            if !tenant.wait_idle(DRAIN_DEADLINE).await {
+                warn!("⌛ Drain deadline hit for {} ({} #{}), dropping with {} call(s) still running",
+                    tenant.id, tenant.env, tenant.generation, tenant.in_flight());
+            }
+
+            sv.manager.draining.write().await.retain(|t| !Arc::ptr_eq(t, &tenant));
+            tenant.set_lifecycle(TenantLifecycle::Retired);
+            sv.registry.update_status(&tenant.id, &tenant.env, tenant.generation, TenantLifecycle::Retired).await;
+            info!("Tenant retired: {} from {} slot (generation {})", tenant.id, tenant.env, tenant.generation);
+        });
+    }
+
     pub async fn reflect(self: Arc<Self>, tomain_id: &str) -> Result<String> {
         let env = self.get_perspective(tomain_id);
-        let tenant = self.manager.get_tenant(tomain_id, &env).await
+        let tenant = self.manager.checkout_tenant(tomain_id, &env).await
             .context(format!("Tenant '{}' not found in {} slot", tomain_id, env))?;
             
-        crate::bridge::invoke_reflect(self.clone(), tenant).await
+        crate::bridge::invoke_reflect(self.clone(), tenant.tenant()).await
     }
 
     pub async fn call(self: Arc<Self>, tomain_id: &str, func_name: &str, query_json: String) -> Result<String> {
         let env = self.get_perspective(tomain_id);
-        let tenant = self.manager.get_tenant(tomain_id, &env).await
-            .context(format!("Tenant '{}' not found in {} slot", tomain_id, env))?;
-            
-        crate::bridge::invoke_call(self.clone(), tenant, func_name, query_json).await
+        let tenant = self.manager.checkout_tenant(tomain_id, &env).await
+            .ok_or_else(|| anyhow::Error::new(UnknownTarget(format!("Tenant '{}' not found in {} slot", tomain_id, env))))?;
+        let _permit = self.acquire_bulkhead(tomain_id, &env).await?;
+
+        let started = std::time::Instant::now();
+        let result = crate::bridge::invoke_call(self.clone(), tenant.tenant(), func_name, query_json).await;
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        self.metrics.inc_counter("axiom_invocations_total", &[("tomain", tomain_id), ("slot", &env), ("function", func_name), ("outcome", outcome)], 1.0);
+        self.metrics.observe("axiom_invocation_duration_seconds", &[("tomain", tomain_id), ("slot", &env), ("function", func_name)], started.elapsed().as_secs_f64());
+        result
+    }
+
+    /// Waits for a slot in the tomain's bulkhead; a shed call is counted and surfaces as a `Shed` error.
+    async fn acquire_bulkhead(&self, tomain_id: &str, slot: &str) -> Result<crate::resilience::BulkheadPermit> {
+        self.resilience.bulkheads.acquire_ingress(tomain_id, slot).await.map_err(|shed| {
+            warn!("🚧 Shedding call to {} ({} slot): {}", tomain_id, slot, shed.reason);
+            self.metrics.inc_counter("axiom_load_shed_total", &[("scope", "ingress"), ("tomain", tomain_id), ("reason", shed.reason)], 1.0);
+            anyhow::Error::new(shed)
+        })
+    }
+
+    /// In-process kernel-to-kernel call. The target must be loaded in the caller's slot (the
+    /// environment boundary), the call counts against the target's upstream rate limit like an
+    /// ingress request, and the callee runs under the caller's request id and trace.
+    pub async fn call_from(self: Arc<Self>, caller: &CallerContext, target: &str, func_name: &str, query_json: String) -> Result<String> {
+        if caller.depth >= MAX_CALL_DEPTH {
+            return Err(anyhow!("Call depth limit ({}) reached calling {}/{}", MAX_CALL_DEPTH, target, func_name));
+        }
+        let env = caller.slot.clone();
+        let tenant = self.manager.checkout_tenant(target, &env).await
+            .context(format!("Security Boundary: {} not promoted to {}", target, env))?;
+
+        // Counted like an ingress request, with the calling tomain as the identity
+        let identity = crate::resilience::CallerIdentity { tomain: Some(caller.tomain_id.clone()), ..Default::default() };
+        if !self.resilience.traffic.check_upstream(target, func_name, &identity).allowed {
+            self.metrics.inc_counter("axiom_upstream_rate_limited_total", &[("tomain", target)], 1.0);
+            return Err(anyhow!("Rate Limit Exceeded (Upstream) for {}", target));
+        }
+        let _permit = self.acquire_bulkhead(target, &env).await?;
+
+        let started = std::time::Instant::now();
+        let result = crate::bridge::invoke_call_from(self.clone(), tenant.tenant(), func_name, query_json, Some(caller))
+            .instrument(tracing::info_span!("axiom.tomain_call", otel.kind = "internal", axiom.caller = %caller.tomain_id, axiom.target = %target, axiom.function = %func_name))
+            .await;
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        self.metrics.inc_counter("axiom_invocations_total", &[("tomain", target), ("slot", &env), ("function", func_name), ("outcome", outcome)], 1.0);
+        self.metrics.observe("axiom_invocation_duration_seconds", &[("tomain", target), ("slot", &env), ("function", func_name)], started.elapsed().as_secs_f64());
+        self.metrics.inc_counter("axiom_tomain_calls_total", &[("caller", &caller.tomain_id), ("target", target), ("outcome", outcome)], 1.0);
+        result
+    }
+
+    /// Refreshes scrape-time gauges (loaded tenants, breaker states) and renders the registry.
+    pub async fn render_metrics(&self) -> String {
+        self.metrics.reset("axiom_tenants_loaded");
+        let mut counts: std::collections::HashMap<(String, TenantLifecycle), usize> = std::collections::HashMap::new();
+        for env_map in self.manager.tenants.read().await.values() {
+            for tenant in env_map.values() {
+                *counts.entry((tenant.env.clone(), tenant.lifecycle())).or_default() += 1;
+            }
+        }
+        for tenant in self.manager.draining.read().await.iter() {
+            *counts.entry((tenant.env.clone(), tenant.lifecycle())).or_default() += 1;
+        }
+        for ((slot, lifecycle), count) in counts {
+            let state = format!("{:?}", lifecycle);
+            self.metrics.set_gauge("axiom_tenants_loaded", &[("slot", &slot), ("state", &state)], count as f64);
+        }
+
+        self.metrics.reset("axiom_bulkhead_in_flight");
+        self.metrics.reset("axiom_bulkhead_queued");
+        self.metrics.reset("axiom_bulkhead_limit");
+        for bulkhead in self.resilience.bulkheads.statuses() {
+            self.metrics.set_gauge("axiom_bulkhead_in_flight", &[("scope", &bulkhead.scope)], bulkhead.in_flight as f64);
+            self.metrics.set_gauge("axiom_bulkhead_queued", &[("scope", &bulkhead.scope)], bulkhead.queued as f64);
+            self.metrics.set_gauge("axiom_bulkhead_limit", &[("scope", &bulkhead.scope)], bulkhead.limit as f64);
+        }
+
+        self.metrics.reset("axiom_circuit_breaker_state");
+        for breaker in self.resilience.fault.breakers.iter() {
+            let value = match breaker.state {
+                crate::resilience::CircuitState::Closed => 0.0,
+                crate::resilience::CircuitState::HalfOpen => 1.0,
+                crate::resilience::CircuitState::Open => 2.0,
+            };
+            let (tomain_id, environment, alias) = breaker.key();
+            self.metrics.set_gauge("axiom_circuit_breaker_state", &[("tomain", tomain_id), ("env", environment), ("alias", alias)], value);
+        }
+
+        self.metrics.render()
+    }
+
+    /// Logs and counts a circuit breaker state change and publishes it on the event bus
+    /// as `axiom.circuit.<closed|open|half_open>` in the breaker's environment.
+    pub fn announce_breaker(self: &Arc<Self>, transition: crate::resilience::BreakerTransition) {
+        let to = match transition.to {
+            crate::resilience::CircuitState::Closed => "closed",
+            crate::resilience::CircuitState::Open => "open",
+            crate::resilience::CircuitState::HalfOpen => "half_open",
+        };
+        info!("🧯 Circuit {}/{}/{}: {:?} -> {:?} ({})", transition.tomain_id, transition.environment, transition.alias, transition.from, transition.to, transition.reason);
+        self.metrics.inc_counter("axiom_circuit_breaker_transitions_total", &[("tomain", &transition.tomain_id), ("alias", &transition.alias), ("to", to)], 1.0);
+
+        let sv = self.clone();
+        tokio::spawn(async move {
+            let topic = format!("axiom.circuit.{}", to);
+            let payload = serde_json::to_string(&transition).unwrap_or_default();
+            if let Err(e) = sv.events.publish_system(&transition.environment, &topic, payload).await {
+                warn!("📣 Could not publish {}: {}", topic, e);
+            }
+        });
+    }
+
+    /// Counts and audits a fault about to be injected into a call to `alias`.
+    pub fn record_fault(&self, tomain_id: &str, environment: &str, alias: &str, injection: &crate::chaos::Injection) {
+        let fault = injection.fault.map_or("latency", |f| f.label());
+        warn!("🐒 Chaos: Injecting fault into {} -> '{}' ({}): {}", tomain_id, alias, environment, injection);
+        self.metrics.inc_counter("axiom_faults_injected_total", &[("tomain", tomain_id), ("alias", alias), ("fault", fault)], 1.0);
+        let audit_entry = format!("CHAOS_INJECTED {} {} {} ({})", environment, alias, fault, injection);
+        self.audit_log.entry(tomain_id.to_string()).or_default().push(audit_entry);
     }
 
     pub fn get_perspective(&self, tomain_id: &str) -> String {
@@ -0,0 +1,250 @@
+/// Cron Scheduler — runs kernel functions on a schedule against the tomain's active slot.
+/// Schedules come from `#[axiom_api(schedule = "...")]` metadata in the kernel and from the
+/// `schedules` map in session.json (axiom.toml via the CCP); the manifest wins on conflict.
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use rand::Rng;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{info, warn};
+use crate::runtime::WasmSupervisor;
+
+/// Upper bound of the random delay added to each run so schedules don't fire in lockstep.
+const MAX_JITTER_MS: u64 = 2000;
+/// Past runs kept per schedule.
+const HISTORY_LEN: usize = 20;
+const TICK: std::time::Duration = std::time::Duration::from_secs(1);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunRecord {
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    /// "Success", "Failed: ..." or "Skipped: ..."
+    pub outcome: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleEntry {
+    pub tomain_id: String,
+    pub function: String,
+    pub expression: String,
+    /// "kernel" (axiom_api attribute) or "manifest" (axiom.toml)
+    pub source: String,
+    pub paused: bool,
+    pub running: bool,
+    pub next_run: Option<DateTime<Utc>>,
+    pub last_run: Option<DateTime<Utc>>,
+    pub last_outcome: Option<String>,
+    pub history: VecDeque<RunRecord>,
+    #[serde(skip)]
+    schedule: Option<cron::Schedule>,
+}
+
+impl ScheduleEntry {
+    fn new(tomain_id: &str, function: &str, expression: &str, source: &str) -> Self {
+        let schedule = cron::Schedule::from_str(expression);
+        if let Err(e) = &schedule {
+            warn!("⏰ Invalid schedule '{}' for {}/{}: {}", expression, tomain_id, function, e);
+        }
+        let schedule = schedule.ok();
+        let next_run = schedule.as_ref().and_then(|s| s.upcoming(Utc).next());
+        Self {
+            tomain_id: tomain_id.to_string(),
+            function: function.to_string(),
+            expression: expression.to_string(),
+            source: source.to_string(),
+            paused: false,
+            running: false,
+            next_run,
+            last_run: None,
+            last_outcome: schedule.is_none().then(|| "Invalid cron expression".to_string()),
+            history: VecDeque::new(),
+            schedule,
+        }
+    }
+
+    fn advance(&mut self) {
+        self.next_run = self.schedule.as_ref().and_then(|s| s.upcoming(Utc).next());
+    }
+
+    fn record(&mut self, run: RunRecord) {
+        self.last_run = Some(run.started_at);
+        self.last_outcome = Some(run.outcome.clone());
+        if self.history.len() >= HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(run);
+    }
+}
+
+pub struct Scheduler {
+    /// (tomain_id, function) -> schedule state
+    pub entries: Arc<DashMap<(String, String), ScheduleEntry>>,
+    /// (tomain_id, function) -> cron expression from the manifest
+    pub manifest: Arc<DashMap<(String, String), String>>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self {
+            entries: Arc::new(DashMap::new()),
+            manifest: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Load manifest-declared schedules from ~/.axiom/session.json.
+    pub fn reload_from_registry(&self) {
+        let path = dirs::home_dir()
+            .unwrap_or_default()
+            .join(".axiom")
+            .join("session.json");
+
+        let Ok(content) = std::fs::read_to_string(&path) else { return };
+        let Ok(json) = serde_json::from_str::<Value>(&content) else { return };
+
+        self.manifest.clear();
+        if let Some(all) = json.get("schedules").and_then(|s| s.as_object()) {
+            for (tomain_id, funcs) in all {
+                if let Some(funcs) = funcs.as_object() {
+                    for (func, expr) in funcs {
+                        if let Some(expr) = expr.as_str() {
+                            self.manifest.insert((tomain_id.clone(), func.clone()), expr.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        info!("⏰ Scheduler: Loaded {} manifest schedules", self.manifest.len());
+    }
+
+    /// Rebuilds a tomain's schedules from its active kernel and the manifest.
+    /// Pause state, run history and an in-flight run survive as long as the schedule still exists.
+    pub async fn sync_tomain(&self, sv: Arc<WasmSupervisor>, tomain_id: &str) {
+        let mut desired: HashMap<String, (String, &'static str)> = HashMap::new();
+
+        let env = sv.get_perspective(tomain_id);
+        if let Some(tenant) = sv.manager.get_tenant(tomain_id, &env).await {
+            match crate::bridge::invoke_metadata(sv.clone(), tenant).await {
+                Ok(functions) => {
+                    for meta in functions {
+                        if let (Some(name), Some(expr)) = (meta["name"].as_str(), meta["schedule"].as_str()) {
+                            desired.insert(name.to_string(), (expr.to_string(), "kernel"));
+                        }
+                    }
+                }
+                Err(e) => warn!("⏰ Could not read schedule metadata for {}: {}", tomain_id, e),
+            }
+        }
+        for entry in self.manifest.iter().filter(|e| e.key().0 == tomain_id) {
+            desired.insert(entry.key().1.clone(), (entry.value().clone(), "manifest"));
+        }
+
+        self.entries.retain(|(id, func), _| id != tomain_id || desired.contains_key(func));
+        for (func, (expr, source)) in desired {
+            let key = (tomain_id.to_string(), func.clone());
+            let unchanged = self.entries.get(&key).map(|e| e.expression == expr).unwrap_or(false);
+            if !unchanged {
+                let mut entry = ScheduleEntry::new(tomain_id, &func, &expr, source);
+                if let Some(previous) = self.entries.get(&key) {
+                    entry.paused = previous.paused;
+                    entry.running = previous.running;
+                    entry.history = previous.history.clone();
+                }
+                info!("⏰ Scheduled {}/{} ({}) from {}", tomain_id, func, expr, source);
+                self.entries.insert(key, entry);
+            }
+        }
+    }
+
+    /// Re-syncs every tomain that has a loaded kernel or a manifest schedule.
+    pub async fn resync_all(&self, sv: Arc<WasmSupervisor>) {
+        let mut tomains: Vec<String> = sv.manager.tenants.read().await.keys().cloned().collect();
+        tomains.extend(self.manifest.iter().map(|e| e.key().0.clone()));
+        tomains.extend(self.entries.iter().map(|e| e.key().0.clone()));
+        tomains.sort();
+        tomains.dedup();
+        for tomain_id in tomains {
+            self.sync_tomain(sv.clone(), &tomain_id).await;
+        }
+    }
+
+    pub fn set_paused(&self, tomain_id: &str, function: &str, paused: bool) -> bool {
+        match self.entries.get_mut(&(tomain_id.to_string(), function.to_string())) {
+            Some(mut entry) => {
+                entry.paused = paused;
+                if !paused {
+                    entry.advance();
+                }
+                info!("⏰ Schedule {}/{} {}", tomain_id, function, if paused { "paused" } else { "resumed" });
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn list(&self, tomain_id: Option<&str>) -> Vec<ScheduleEntry> {
+        let mut entries: Vec<ScheduleEntry> = self.entries.iter()
+            .filter(|e| tomain_id.map(|id| e.key().0 == id).unwrap_or(true))
+            .map(|e| e.value().clone())
+            .collect();
+        entries.sort_by(|a, b| (&a.tomain_id, &a.function).cmp(&(&b.tomain_id, &b.function)));
+        entries
+    }
+
+    /// Main loop: fires due schedules, skipping any whose previous run is still going.
+    pub async fn run(sv: Arc<WasmSupervisor>) {
+        info!("⏰ Scheduler loop active ({}s tick)", TICK.as_secs());
+        loop {
+            tokio::time::sleep(TICK).await;
+            let now = Utc::now();
+
+            let mut due = Vec::new();
+            for mut entry in sv.scheduler.entries.iter_mut() {
+                if entry.paused || entry.next_run.map(|t| t > now).unwrap_or(true) {
+                    continue;
+                }
+                if entry.running {
+                    warn!("⏰ Skipping {}/{}: previous run still in progress", entry.tomain_id, entry.function);
+                    entry.record(RunRecord {
+                        started_at: now,
+                        duration_ms: 0,
+                        outcome: "Skipped: previous run still in progress".to_string(),
+                    });
+                } else {
+                    entry.running = true;
+                    due.push(entry.key().clone());
+                }
+                entry.advance();
+            }
+
+            for key in due {
+                let sv = sv.clone();
+                tokio::spawn(async move {
+                    let jitter = rand::thread_rng().gen_range(0..=MAX_JITTER_MS);
+                    tokio::time::sleep(std::time::Duration::from_millis(jitter)).await;
+
+                    let (tomain_id, function) = &key;
+                    let started_at = Utc::now();
+                    let started = std::time::Instant::now();
+                    let outcome = match sv.clone().call(tomain_id, function, "{}".to_string()).await {
+                        Ok(_) => "Success".to_string(),
+                        Err(e) => format!("Failed: {}", e),
+                    };
+                    info!("⏰ Scheduled run {}/{}: {}", tomain_id, function, outcome);
+
+                    if let Some(mut entry) = sv.scheduler.entries.get_mut(&key) {
+                        entry.running = false;
+                        entry.record(RunRecord {
+                            started_at,
+                            duration_ms: started.elapsed().as_millis() as u64,
+                            outcome,
+                        });
+                    }
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,83 @@
+/// Secret Store — kernel access to the CCP's `secrets` map (tomain → key → value).
+/// A kernel may only read keys listed in `secret_grants` for its tomain and the slot it runs in.
+/// Values are never logged or served by any endpoint; only key names reach the audit log.
+use dashmap::DashMap;
+use serde_json::Value;
+use std::collections::HashSet;
+use tracing::info;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SecretError {
+    /// The key is not granted to this tomain in this environment
+    NotGranted,
+    /// Granted, but no value has been set
+    NotFound,
+}
+
+pub struct SecretStore {
+    /// (tomain_id, key) -> value
+    values: DashMap<(String, String), String>,
+    /// (tomain_id, environment) -> keys readable from that slot
+    grants: DashMap<(String, String), HashSet<String>>,
+}
+
+impl SecretStore {
+    pub fn new() -> Self {
+        Self {
+            values: DashMap::new(),
+            grants: DashMap::new(),
+        }
+    }
+
+    /// Load `secrets` and `secret_grants` from ~/.axiom/session.json.
+    pub fn reload_from_registry(&self) {
+        let path = dirs::home_dir()
+            .unwrap_or_default()
+            .join(".axiom")
+            .join("session.json");
+
+        let Ok(content) = std::fs::read_to_string(&path) else { return };
+        let Ok(json) = serde_json::from_str::<Value>(&content) else { return };
+
+        self.values.clear();
+        if let Some(all) = json.get("secrets").and_then(|s| s.as_object()) {
+            for (tomain_id, keys) in all {
+                for (key, value) in keys.as_object().into_iter().flatten() {
+                    if let Some(value) = value.as_str() {
+                        self.values.insert((tomain_id.clone(), key.clone()), value.to_string());
+                    }
+                }
+            }
+        }
+
+        self.grants.clear();
+        if let Some(all) = json.get("secret_grants").and_then(|g| g.as_object()) {
+            for (tomain_id, envs) in all {
+                for (env, keys) in envs.as_object().into_iter().flatten() {
+                    let keys: HashSet<String> = keys.as_array().into_iter().flatten()
+                        .filter_map(|k| k.as_str().map(|k| k.to_string()))
+                        .collect();
+                    self.grants.insert((tomain_id.clone(), env.to_uppercase()), keys);
+                }
+            }
+        }
+        info!("🔐 Secrets: Loaded {} values, {} grant sets", self.values.len(), self.grants.len());
+    }
+
+    pub fn get(&self, tomain_id: &str, environment: &str, key: &str) -> Result<String, SecretError> {
+        let granted = self.grants.get(&(tomain_id.to_string(), environment.to_uppercase()))
+            .map(|keys| keys.contains(key))
+            .unwrap_or(false);
+        if !granted {
+            return Err(SecretError::NotGranted);
+        }
+        self.values.get(&(tomain_id.to_string(), key.to_string()))
+            .map(|v| v.value().clone())
+            .ok_or(SecretError::NotFound)
+    }
+
+    /// Shell-side read for binding credentials (e.g. OAuth2 client secrets); kernel grants don't apply.
+    pub fn credential(&self, tomain_id: &str, key: &str) -> Option<String> {
+        self.values.get(&(tomain_id.to_string(), key.to_string())).map(|v| v.value().clone())
+    }
+}
@@ -1,25 +1,122 @@
 use anyhow::{Result, Context};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::atomic::{AtomicU64, AtomicU8, AtomicUsize, Ordering};
+use tokio::sync::{Notify, RwLock};
 use wasmtime::*;
 use tracing::info;
 
+/// Lifecycle of a tenant slot instance. A new deployment goes
+/// Loading -> Warming -> Active; the instance it replaces goes
+/// Active -> Draining -> Retired once its in-flight calls finish.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum TenantLifecycle {
+    Loading,
+    Warming,
+    Active,
+    Draining,
+    Retired,
+}
+
+impl TenantLifecycle {
+    fn from_u8(v: u8) -> Self {
+        match v {
+            0 => TenantLifecycle::Loading,
+            1 => TenantLifecycle::Warming,
+            2 => TenantLifecycle::Active,
+            3 => TenantLifecycle::Draining,
+            _ => TenantLifecycle::Retired,
+        }
+    }
+}
+
 pub struct TenantInstance {
     pub id: String,
+    pub env: String,
+    /// Monotonic per-shell deployment counter, distinguishes old and new instances of one slot
+    pub generation: u64,
     pub engine: Engine,
     pub module: Module,
+    lifecycle: AtomicU8,
+    in_flight: AtomicUsize,
+    idle: Notify,
+}
+
+impl TenantInstance {
+    pub fn lifecycle(&self) -> TenantLifecycle {
+        TenantLifecycle::from_u8(self.lifecycle.load(Ordering::Acquire))
+    }
+
+    pub fn set_lifecycle(&self, state: TenantLifecycle) {
+        self.lifecycle.store(state as u8, Ordering::Release);
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Acquire)
+    }
+
+    /// Marks a call as in-flight until the returned guard is dropped.
+    pub fn begin_call(self: &Arc<Self>) -> InFlightGuard {
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
+        InFlightGuard { tenant: self.clone() }
+    }
+
+    /// Waits until no calls are in flight or the deadline passes.
+    /// Returns false if the deadline was hit with calls still running.
+    pub async fn wait_idle(&self, deadline: std::time::Duration) -> bool {
+        let wait = async {
+            loop {
+                let notified = self.idle.notified();
+                if self.in_flight() == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        };
+        tokio::time::timeout(deadline, wait).await.is_ok()
+    }
+}
+
+/// RAII in-flight marker; keeps the instance alive for the duration of a call.
+pub struct InFlightGuard {
+    tenant: Arc<TenantInstance>,
+}
+
+impl InFlightGuard {
+    pub fn tenant(&self) -> Arc<TenantInstance> {
+        self.tenant.clone()
+    }
+}
+
+impl std::ops::Deref for InFlightGuard {
+    type Target = TenantInstance;
+    fn deref(&self) -> &TenantInstance {
+        &self.tenant
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.tenant.in_flight.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.tenant.idle.notify_waiters();
+        }
+    }
 }
 
 pub struct TenantManager {
     /// tomain_id -> { env -> TenantInstance }
     pub tenants: Arc<RwLock<HashMap<String, HashMap<String, Arc<TenantInstance>>>>>,
+    /// Replaced or retired instances still finishing their in-flight calls
+    pub draining: Arc<RwLock<Vec<Arc<TenantInstance>>>>,
+    generation: AtomicU64,
 }
 
 impl TenantManager {
     pub fn new() -> Self {
         Self {
             tenants: Arc::new(RwLock::new(HashMap::new())),
+            draining: Arc::new(RwLock::new(Vec::new())),
+            generation: AtomicU64::new(0),
         }
     }
 
@@ -31,22 +128,31 @@ impl TenantManager {
         Engine::new(&config)
     }
 
-    pub async fn register_tenant(&self, id: &str, env: &str, wasm_bytes: &[u8]) -> Result<()> {
+    /// Compiles a new instance for a slot without making it visible to traffic.
+    pub fn load_tenant(&self, id: &str, env: &str, wasm_bytes: &[u8]) -> Result<Arc<TenantInstance>> {
         let engine = self.create_engine()?;
         let module = Module::new(&engine, wasm_bytes).context("Failed to load Wasm module")?;
-        
-        let instance = Arc::new(TenantInstance {
+
+        Ok(Arc::new(TenantInstance {
             id: id.to_string(),
+            env: env.to_uppercase(),
+            generation: self.generation.fetch_add(1, Ordering::AcqRel) + 1,
             engine,
             module,
-        });
+            lifecycle: AtomicU8::new(TenantLifecycle::Loading as u8),
+            in_flight: AtomicUsize::new(0),
+            idle: Notify::new(),
+        }))
+    }
 
+    /// Swaps a warmed instance into its slot. Returns the instance it replaced, if any,
+    /// which the caller is responsible for draining.
+    pub async fn activate_tenant(&self, instance: Arc<TenantInstance>) -> Option<Arc<TenantInstance>> {
+        instance.set_lifecycle(TenantLifecycle::Active);
         let mut all_tenants = self.tenants.write().await;
-        let tenant_envs = all_tenants.entry(id.to_string()).or_insert_with(HashMap::new);
-        tenant_envs.insert(env.to_uppercase(), instance);
-        
-        info!("Tenant registered: {} in {} slot", id, env);
-        Ok(())
+        let tenant_envs = all_tenants.entry(instance.id.clone()).or_insert_with(HashMap::new);
+        info!("Tenant activated: {} in {} slot (generation {})", instance.id, instance.env, instance.generation);
+        tenant_envs.insert(instance.env.clone(), instance)
     }
 
     pub async fn get_tenant(&self, id: &str, env: &str) -> Option<Arc<TenantInstance>> {
@@ -54,15 +160,129 @@ impl TenantManager {
         all_tenants.get(id)?.get(&env.to_uppercase()).cloned()
     }
 
-    pub async fn remove_tenant(&self, id: &str, env: &str) -> Result<()> {
+    /// Looks up the active instance for a slot and marks a call in flight on it.
+    /// Done under the read lock so a concurrent swap can't drain it before the call is counted.
+    pub async fn checkout_tenant(&self, id: &str, env: &str) -> Option<InFlightGuard> {
+        let all_tenants = self.tenants.read().await;
+        all_tenants.get(id)?.get(&env.to_uppercase()).map(|t| t.begin_call())
+    }
+
+    /// Pulls an instance out of its slot. Returns it so the caller can drain it.
+    pub async fn remove_tenant(&self, id: &str, env: &str) -> Option<Arc<TenantInstance>> {
         let mut all_tenants = self.tenants.write().await;
-        if let Some(tenant_envs) = all_tenants.get_mut(id) {
-            tenant_envs.remove(&env.to_uppercase());
-            if tenant_envs.is_empty() {
-                all_tenants.remove(id);
-            }
+        let removed = all_tenants.get_mut(id).and_then(|tenant_envs| tenant_envs.remove(&env.to_uppercase()));
+        if all_tenants.get(id).map(|e| e.is_empty()).unwrap_or(false) {
+            all_tenants.remove(id);
+        }
+        if removed.is_some() {
+            info!("Tenant removed from traffic: {} from {} slot", id, env);
+        }
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn load(manager: &TenantManager, id: &str, env: &str) -> Arc<TenantInstance> {
+        manager.load_tenant(id, env, b"(module)").expect("empty module compiles")
+    }
+
+    #[test]
+    fn lifecycle_round_trips_through_its_atomic() {
+        let manager = TenantManager::new();
+        let tenant = load(&manager, "acme", "prod");
+        assert_eq!(tenant.lifecycle(), TenantLifecycle::Loading);
+        for state in [
+            TenantLifecycle::Warming,
+            TenantLifecycle::Active,
+            TenantLifecycle::Draining,
+            TenantLifecycle::Retired,
+        ] {
+            tenant.set_lifecycle(state);
+            assert_eq!(tenant.lifecycle(), state);
         }
-        info!("Tenant retired: {} from {} slot", id, env);
-        Ok(())
+    }
+
+    #[test]
+    fn guards_count_in_flight_calls() {
+        let manager = TenantManager::new();
+        let tenant = load(&manager, "acme", "prod");
+        let first = tenant.begin_call();
+        let second = tenant.begin_call();
+        assert_eq!(tenant.in_flight(), 2);
+        drop(first);
+        assert_eq!(tenant.in_flight(), 1);
+        drop(second);
+        assert_eq!(tenant.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn activate_returns_the_replaced_instance() {
+        let manager = TenantManager::new();
+        let old = load(&manager, "acme", "prod");
+        let new = load(&manager, "acme", "prod");
+        assert!(new.generation > old.generation);
+
+        assert!(manager.activate_tenant(old.clone()).await.is_none());
+        assert_eq!(old.lifecycle(), TenantLifecycle::Active);
+
+        let replaced = manager.activate_tenant(new.clone()).await.expect("old instance handed back");
+        assert_eq!(replaced.generation, old.generation);
+        let current = manager.get_tenant("acme", "PROD").await.unwrap();
+        assert_eq!(current.generation, new.generation);
+    }
+
+    #[tokio::test]
+    async fn checkout_pins_the_instance_across_a_swap() {
+        let manager = TenantManager::new();
+        let old = load(&manager, "acme", "prod");
+        manager.activate_tenant(old.clone()).await;
+
+        let guard = manager.checkout_tenant("acme", "prod").await.unwrap();
+        let replaced = manager.activate_tenant(load(&manager, "acme", "prod")).await.unwrap();
+        assert_eq!(guard.generation, old.generation);
+        assert_eq!(replaced.in_flight(), 1);
+
+        drop(guard);
+        assert_eq!(replaced.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn wait_idle_returns_once_the_last_call_finishes() {
+        let manager = TenantManager::new();
+        let tenant = load(&manager, "acme", "prod");
+        let guard = tenant.begin_call();
+
+        let waiter = {
+            let tenant = tenant.clone();
+            tokio::spawn(async move { tenant.wait_idle(Duration::from_secs(5)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(guard);
+        assert!(waiter.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn wait_idle_gives_up_at_the_deadline() {
+        let manager = TenantManager::new();
+        let tenant = load(&manager, "acme", "prod");
+        let _guard = tenant.begin_call();
+        assert!(!tenant.wait_idle(Duration::from_millis(20)).await);
+    }
+
+    #[tokio::test]
+    async fn remove_drops_the_tomain_with_its_last_slot() {
+        let manager = TenantManager::new();
+        manager.activate_tenant(load(&manager, "acme", "prod")).await;
+        manager.activate_tenant(load(&manager, "acme", "dev")).await;
+
+        assert!(manager.remove_tenant("acme", "dev").await.is_some());
+        assert!(manager.tenants.read().await.contains_key("acme"));
+        assert!(manager.remove_tenant("acme", "prod").await.is_some());
+        assert!(!manager.tenants.read().await.contains_key("acme"));
+        assert!(manager.remove_tenant("acme", "prod").await.is_none());
     }
 }
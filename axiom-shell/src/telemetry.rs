@@ -0,0 +1,159 @@
+/// Tracing pipeline — fmt logging plus an optional OTLP span exporter.
+/// The exporter is enabled by OTEL_EXPORTER_OTLP_ENDPOINT (any collector, or a local stand-in)
+/// and speaks gRPC by default or HTTP/protobuf when OTEL_EXPORTER_OTLP_PROTOCOL=http/protobuf.
+use anyhow::Result;
+use dashmap::DashMap;
+use opentelemetry::trace::{Link, SamplingResult, SpanKind, TraceId, TracerProvider as _};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Sampler, ShouldSample, TracerProvider};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Span attribute the sampler reads to pick a slot's ratio. Set it on every root span.
+pub const SLOT_ATTRIBUTE: &str = "axiom.slot";
+
+const DEFAULT_SAMPLE_RATIO: f64 = 0.1;
+
+/// Per-slot trace sampling ratios, hot-reloaded from the `sampling` map in session.json.
+#[derive(Debug)]
+pub struct SamplingRatios {
+    /// slot (GREEN/BLUE/RED/DEV/...) -> ratio in [0, 1]
+    pub ratios: DashMap<String, f64>,
+    pub default_ratio: f64,
+}
+
+impl SamplingRatios {
+    pub fn new() -> Self {
+        let default_ratio = std::env::var("AXIOM_TRACE_SAMPLE_RATIO")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_SAMPLE_RATIO);
+        let ratios = DashMap::new();
+        Self::insert_defaults(&ratios);
+        Self { ratios, default_ratio }
+    }
+
+    // BLUE is the pre-release perspective and RED is audit mode: trace every request there.
+    fn insert_defaults(ratios: &DashMap<String, f64>) {
+        ratios.insert("BLUE".to_string(), 1.0);
+        ratios.insert("RED".to_string(), 1.0);
+    }
+
+    pub fn ratio_for(&self, slot: &str) -> f64 {
+        self.ratios.get(&slot.to_uppercase()).map(|r| *r).unwrap_or(self.default_ratio)
+    }
+
+    pub fn reload_from_registry(&self) {
+        let path = dirs::home_dir()
+            .unwrap_or_default()
+            .join(".axiom")
+            .join("session.json");
+
+        let Ok(content) = std::fs::read_to_string(&path) else { return };
+        let Ok(json) = serde_json::from_str::<Value>(&content) else { return };
+
+        self.ratios.clear();
+        Self::insert_defaults(&self.ratios);
+        if let Some(sampling) = json.get("sampling").and_then(|s| s.as_object()) {
+            for (slot, ratio) in sampling {
+                if let Some(r) = ratio.as_f64() {
+                    self.ratios.insert(slot.to_uppercase(), r.clamp(0.0, 1.0));
+                    info!("📊 Trace sampling for {} slot set to {}", slot, r);
+                }
+            }
+        }
+    }
+}
+
+/// Root-span sampler that applies the ratio of the slot named in the `axiom.slot` attribute.
+/// Wrapped in `Sampler::ParentBased` so an incoming sampled `traceparent` is always honored.
+#[derive(Debug, Clone)]
+struct SlotSampler {
+    ratios: Arc<SamplingRatios>,
+}
+
+impl ShouldSample for SlotSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        let ratio = attributes.iter()
+            .find(|kv| kv.key.as_str() == SLOT_ATTRIBUTE)
+            .map(|kv| self.ratios.ratio_for(&kv.value.as_str()))
+            .unwrap_or(self.ratios.default_ratio);
+        Sampler::TraceIdRatioBased(ratio).should_sample(parent_context, trace_id, name, span_kind, attributes, links)
+    }
+}
+
+/// Flushes pending spans when the shell shuts down.
+pub struct TelemetryGuard {
+    provider: Option<TracerProvider>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.provider.take()
+            && let Err(e) = provider.shutdown()
+        {
+            warn!("Failed to flush OTLP spans on shutdown: {:?}", e);
+        }
+    }
+}
+
+/// Installs the global subscriber. Without an OTLP endpoint this is plain fmt logging.
+pub fn init(ratios: Arc<SamplingRatios>) -> Result<TelemetryGuard> {
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let Ok(endpoint) = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT") else {
+        tracing_subscriber::fmt::init();
+        return Ok(TelemetryGuard { provider: None });
+    };
+
+    let protocol = std::env::var("OTEL_EXPORTER_OTLP_PROTOCOL").unwrap_or_else(|_| "grpc".to_string());
+    let exporter = if protocol.starts_with("http") {
+        opentelemetry_otlp::SpanExporter::builder().with_http().with_endpoint(endpoint.clone()).build()?
+    } else {
+        opentelemetry_otlp::SpanExporter::builder().with_tonic().with_endpoint(endpoint.clone()).build()?
+    };
+
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+        .with_sampler(Sampler::ParentBased(Box::new(SlotSampler { ratios })))
+        .with_resource(opentelemetry_sdk::Resource::new(vec![
+            KeyValue::new("service.name", "axiom-shell"),
+        ]))
+        .build();
+    let tracer = provider.tracer("axiom-shell");
+    opentelemetry::global::set_tracer_provider(provider.clone());
+
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .init();
+
+    info!("🛰️ OTLP trace export enabled ({} via {})", endpoint, protocol);
+    Ok(TelemetryGuard { provider: Some(provider) })
+}
+
+/// Reads a W3C `traceparent`/`tracestate` from ingress headers.
+pub fn extract_context(headers: &axum::http::HeaderMap) -> Context {
+    TraceContextPropagator::new().extract(&opentelemetry_http::HeaderExtractor(headers))
+}
+
+/// Writes the current span's context as a W3C `traceparent` into outbound headers.
+pub fn inject_current_context(headers: &mut reqwest::header::HeaderMap) {
+    let cx = tracing::Span::current().context();
+    TraceContextPropagator::new().inject_context(&cx, &mut opentelemetry_http::HeaderInjector(headers));
+}
@@ -0,0 +1,278 @@
+/// TLS profiles for egress bindings: client certificates (mTLS), private CA bundles, SNI override
+/// and minimum TLS version, from session.json `tls_profiles` (tomain → env → alias). Certificate
+/// and key PEMs are references into the tomain's `secrets`. Each distinct profile gets its own
+/// pooled `reqwest::Client`; bindings without a profile share the Shell's default client.
+/// Profiles that weaken verification are refused for PROD traffic (the live GREEN and RED perspectives).
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use tracing::{info, warn};
+use crate::egress::EgressError;
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsProfile {
+    /// Secret key holding the client certificate chain (PEM)
+    pub client_cert_ref: Option<String>,
+    /// Secret key holding the client private key (PEM)
+    pub client_key_ref: Option<String>,
+    /// Extra trusted CA certificates (PEM bundles), added to the webpki roots
+    pub ca_bundles: Vec<String>,
+    /// Server name to present and verify instead of the URL's host; the connection still goes
+    /// to the bound host, and the Host header follows the override
+    pub sni_override: Option<String>,
+    /// "1.2" or "1.3"; anything lower is insecure
+    pub min_tls_version: Option<String>,
+    pub insecure_skip_verify: bool,
+    pub insecure_skip_hostname_verify: bool,
+}
+
+impl TlsProfile {
+    pub fn is_insecure(&self) -> bool {
+        self.insecure_skip_verify
+            || self.insecure_skip_hostname_verify
+            || matches!(self.min_tls_version.as_deref(), Some("1.0" | "1.1"))
+    }
+
+    fn min_version(&self) -> Result<Option<reqwest::tls::Version>, String> {
+        match self.min_tls_version.as_deref() {
+            None => Ok(None),
+            Some("1.0") => Ok(Some(reqwest::tls::Version::TLS_1_0)),
+            Some("1.1") => Ok(Some(reqwest::tls::Version::TLS_1_1)),
+            Some("1.2") => Ok(Some(reqwest::tls::Version::TLS_1_2)),
+            Some("1.3") => Ok(Some(reqwest::tls::Version::TLS_1_3)),
+            Some(other) => Err(format!("unknown min_tls_version '{}'", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TlsProfileStatus {
+    pub tomain_id: String,
+    pub environment: String,
+    pub alias: String,
+    pub profile: TlsProfile,
+}
+
+pub struct TlsClients {
+    /// (tomain_id, registry env, alias) -> profile; "GLOBAL" applies to every environment without its own
+    profiles: DashMap<(String, String, String), TlsProfile>,
+    /// "{tomain}|{profile json}|{connect authority}" -> client built for it
+    clients: DashMap<String, reqwest::Client>,
+}
+
+impl TlsClients {
+    pub fn new() -> Self {
+        Self {
+            profiles: DashMap::new(),
+            clients: DashMap::new(),
+        }
+    }
+
+    /// Load `tls_profiles` from ~/.axiom/session.json. The client pool is dropped so rotated
+    /// certificates in `secrets` take effect on the next call.
+    pub fn reload_from_registry(&self) {
+        let path = dirs::home_dir()
+            .unwrap_or_default()
+            .join(".axiom")
+            .join("session.json");
+
+        let Ok(content) = std::fs::read_to_string(&path) else { return };
+        let Ok(json) = serde_json::from_str::<Value>(&content) else { return };
+
+        self.profiles.clear();
+        self.clients.clear();
+        if let Some(all) = json.get("tls_profiles").and_then(|c| c.as_object()) {
+            for (tomain_id, envs) in all {
+                for (env, aliases) in envs.as_object().into_iter().flatten() {
+                    let env = crate::runtime::registry_environment(env);
+                    for (alias, profile) in aliases.as_object().into_iter().flatten() {
+                        match serde_json::from_value::<TlsProfile>(profile.clone()) {
+                            Ok(profile) => {
+                                if profile.is_insecure() && (env == "PROD" || env == "GLOBAL") {
+                                    warn!("🛑 TLS: Insecure profile for {} ({}/{}) will be refused for PROD traffic", tomain_id, env, alias);
+                                }
+                                self.profiles.insert((tomain_id.clone(), env.clone(), alias.clone()), profile);
+                            }
+                            Err(e) => warn!("Invalid TLS profile for {} ({}/{}): {}", tomain_id, env, alias, e),
+                        }
+                    }
+                }
+            }
+        }
+        info!("🔒 TLS: Loaded {} binding profiles", self.profiles.len());
+    }
+
+    /// The client (and possibly rewritten URL) to reach `url` for this binding. Bindings without
+    /// a profile get `default` and the URL unchanged. `environment` may be a perspective; it is
+    /// checked as the registry environment it serves.
+    pub async fn client_for(
+        &self,
+        tomain_id: &str,
+        environment: &str,
+        alias: &str,
+        url: &str,
+        secrets: &crate::secrets::SecretStore,
+        default: &reqwest::Client,
+    ) -> Result<(reqwest::Client, String), EgressError> {
+        let env = crate::runtime::registry_environment(environment);
+        let key = |env: &str| (tomain_id.to_string(), env.to_string(), alias.to_string());
+        let Some(profile) = self.profiles.get(&key(&env))
+            .or_else(|| self.profiles.get(&key("GLOBAL")))
+            .map(|p| p.value().clone())
+        else {
+            return Ok((default.clone(), url.to_string()));
+        };
+        if env == "PROD" && profile.is_insecure() {
+            return Err(EgressError::PolicyDenied(format!("insecure TLS profile for '{}' is not allowed in PROD", alias)));
+        }
+
+        // SNI override: address the request to the override name, but connect to the bound host
+        let (url, connect) = match &profile.sni_override {
+            Some(server_name) => {
+                let mut parsed = url::Url::parse(url)
+                    .map_err(|e| EgressError::PolicyDenied(format!("bad URL for '{}': {}", alias, e)))?;
+                let host = parsed.host_str().unwrap_or_default().to_string();
+                let port = parsed.port_or_known_default().unwrap_or(443);
+                parsed.set_host(Some(server_name))
+                    .map_err(|e| EgressError::PolicyDenied(format!("bad sni_override for '{}': {}", alias, e)))?;
+                (parsed.to_string(), Some((server_name.clone(), host, port)))
+            }
+            None => (url.to_string(), None),
+        };
+
+        let pool_key = format!(
+            "{}|{}|{}",
+            tomain_id,
+            serde_json::to_string(&profile).unwrap_or_default(),
+            connect.as_ref().map(|(_, host, port)| format!("{}:{}", host, port)).unwrap_or_default(),
+        );
+        if let Some(client) = self.clients.get(&pool_key) {
+            return Ok((client.clone(), url));
+        }
+
+        let client = build_client(tomain_id, &profile, connect, secrets).await
+            .map_err(|e| {
+                warn!("🔒 TLS: Could not build client for {} -> '{}': {}", tomain_id, alias, e);
+                EgressError::Transport(format!("TLS setup for '{}' failed: {}", alias, e))
+            })?;
+        info!("🔒 TLS: Pooled new client for {} -> '{}' ({})", tomain_id, alias, env);
+        self.clients.insert(pool_key, client.clone());
+        Ok((client, url))
+    }
+
+    pub fn statuses(&self) -> Vec<TlsProfileStatus> {
+        self.profiles.iter().map(|entry| {
+            let (tomain_id, environment, alias) = entry.key().clone();
+            TlsProfileStatus { tomain_id, environment, alias, profile: entry.value().clone() }
+        }).collect()
+    }
+
+    pub fn pooled(&self) -> usize {
+        self.clients.len()
+    }
+}
+
+async fn build_client(
+    tomain_id: &str,
+    profile: &TlsProfile,
+    connect: Option<(String, String, u16)>,
+    secrets: &crate::secrets::SecretStore,
+) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(10));
+
+    for bundle in &profile.ca_bundles {
+        let certs = reqwest::Certificate::from_pem_bundle(bundle.as_bytes())
+            .map_err(|e| format!("invalid CA bundle: {}", e))?;
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    match (&profile.client_cert_ref, &profile.client_key_ref) {
+        (Some(cert_ref), Some(key_ref)) => {
+            let secret = |key: &str| secrets.credential(tomain_id, key)
+                .ok_or_else(|| format!("secret '{}' is not set for {}", key, tomain_id));
+            let pem = format!("{}\n{}", secret(key_ref)?, secret(cert_ref)?);
+            let identity = reqwest::Identity::from_pem(pem.as_bytes())
+                .map_err(|e| format!("invalid client certificate or key: {}", e))?;
+            builder = builder.identity(identity);
+        }
+        (None, None) => {}
+        _ => return Err("client_cert_ref and client_key_ref must be set together".to_string()),
+    }
+
+    if let Some(version) = profile.min_version()? {
+        builder = builder.min_tls_version(version);
+    }
+    if profile.insecure_skip_verify {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    if profile.insecure_skip_hostname_verify {
+        builder = builder.danger_accept_invalid_hostnames(true);
+    }
+
+    if let Some((server_name, host, port)) = connect {
+        let addrs: Vec<std::net::SocketAddr> = tokio::net::lookup_host((host.as_str(), port)).await
+            .map_err(|e| format!("cannot resolve {}: {}", host, e))?
+            .collect();
+        builder = builder.resolve_to_addrs(&server_name, &addrs);
+    }
+
+    builder.build().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_profile(env: &str, profile: TlsProfile) -> TlsClients {
+        let clients = TlsClients::new();
+        clients.profiles.insert(("acme".into(), env.into(), "ledger".into()), profile);
+        clients
+    }
+
+    fn insecure() -> TlsProfile {
+        TlsProfile { insecure_skip_verify: true, ..Default::default() }
+    }
+
+    async fn client_for(clients: &TlsClients, environment: &str) -> Result<(reqwest::Client, String), EgressError> {
+        let secrets = crate::secrets::SecretStore::new();
+        clients.client_for("acme", environment, "ledger", "https://ledger.internal/v1", &secrets, &reqwest::Client::new()).await
+    }
+
+    #[test]
+    fn weak_tls_versions_are_insecure() {
+        assert!(insecure().is_insecure());
+        assert!(TlsProfile { min_tls_version: Some("1.1".into()), ..Default::default() }.is_insecure());
+        assert!(!TlsProfile { min_tls_version: Some("1.2".into()), ..Default::default() }.is_insecure());
+    }
+
+    #[tokio::test]
+    async fn insecure_profiles_are_refused_on_live_perspectives() {
+        // egress_call passes the tomain's perspective, GREEN unless switched
+        for profile_env in ["PROD", "GLOBAL"] {
+            let clients = with_profile(profile_env, insecure());
+            for perspective in ["GREEN", "RED", "prod"] {
+                let denied = client_for(&clients, perspective).await;
+                assert!(matches!(denied, Err(EgressError::PolicyDenied(_))), "{} profile served {}", profile_env, perspective);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn insecure_profiles_still_work_before_release() {
+        let clients = with_profile("GLOBAL", insecure());
+        assert!(client_for(&clients, "BLUE").await.is_ok());
+        assert_eq!(clients.pooled(), 1);
+    }
+
+    #[tokio::test]
+    async fn bindings_without_a_profile_use_the_default_client() {
+        let clients = TlsClients::new();
+        let (_, url) = client_for(&clients, "GREEN").await.unwrap();
+        assert_eq!(url, "https://ledger.internal/v1");
+        assert_eq!(clients.pooled(), 0);
+    }
+}